@@ -0,0 +1,55 @@
+//! Compares laying out a screen of static labels through the painter's galley
+//! cache against laying them out from scratch every frame.
+//!
+//! Run with `cargo bench --bench galley_cache`.
+
+use std::time::{Duration, Instant};
+
+use epaint::{text::FontDefinitions, Color32, FontId, Fonts};
+use guee::painter::Painter;
+
+const NUM_LABELS: usize = 500;
+const NUM_FRAMES: u32 = 200;
+
+/// Runs `frame` repeatedly and prints the average time it took. The closure
+/// returns the time spent on the measured part of the frame.
+fn bench(name: &str, mut frame: impl FnMut() -> Duration) {
+    // Warm up, so the first frame doesn't skew the results
+    frame();
+    let total: Duration = (0..NUM_FRAMES).map(|_| frame()).sum();
+    println!(
+        "{name:>8}: {:?} per frame ({NUM_LABELS} labels)",
+        total / NUM_FRAMES
+    );
+}
+
+fn main() {
+    let labels: Vec<String> = (0..NUM_LABELS)
+        .map(|i| format!("Label number {i}, with some text"))
+        .collect();
+    let font_id = FontId::proportional(14.0);
+
+    bench("uncached", || {
+        // A fresh `Fonts` every frame, so epaint's internal cache is empty
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        let start = Instant::now();
+        for label in &labels {
+            fonts.layout(
+                label.clone(),
+                font_id.clone(),
+                Color32::BLACK,
+                f32::INFINITY,
+            );
+        }
+        start.elapsed()
+    });
+
+    let mut painter = Painter::new(vec![]);
+    bench("cached", || {
+        let start = Instant::now();
+        for label in &labels {
+            painter.galley(label.clone(), font_id.clone(), f32::INFINITY);
+        }
+        start.elapsed()
+    });
+}
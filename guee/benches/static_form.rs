@@ -0,0 +1,54 @@
+//! Measures the cost of running frames for a large static form, when every
+//! frame is run versus when frames are skipped using `Context::needs_run`.
+//!
+//! Run with `cargo bench --bench static_form`.
+
+use std::time::Instant;
+
+use guee::prelude::*;
+
+const NUM_ROWS: usize = 300;
+const NUM_FRAMES: u32 = 100;
+
+fn form() -> DynWidget {
+    BoxContainer::vertical(
+        IdGen::key("form"),
+        (0..NUM_ROWS)
+            .map(|i| {
+                BoxContainer::horizontal(
+                    IdGen::key(i),
+                    vec![
+                        Text::new(format!("Field number {i}")).build(),
+                        TextEdit::new(IdGen::key("edit"), format!("Value {i}")).build(),
+                    ],
+                )
+                .build()
+            })
+            .collect(),
+    )
+    .build()
+}
+
+fn bench(name: &str, ctx: &mut Context, skip_unchanged: bool) {
+    let start = Instant::now();
+    let mut frames_run = 0;
+    for _ in 0..NUM_FRAMES {
+        if skip_unchanged && !ctx.needs_run() {
+            continue;
+        }
+        ctx.run(&mut form(), &mut ());
+        ctx.tessellate();
+        ctx.take_repaint_request();
+        frames_run += 1;
+    }
+    let per_frame = start.elapsed() / NUM_FRAMES;
+    println!("{name:>12}: {per_frame:?} per frame ({frames_run} of {NUM_FRAMES} frames run)");
+}
+
+fn main() {
+    let mut ctx = Context::new(Vec2::new(1024.0, 768.0), vec![]);
+    bench("every frame", &mut ctx, false);
+
+    let mut ctx = Context::new(Vec2::new(1024.0, 768.0), vec![]);
+    bench("needs_run", &mut ctx, true);
+}
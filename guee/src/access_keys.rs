@@ -0,0 +1,109 @@
+//! Access keys, also known as mnemonics, allow activating a control with the
+//! keyboard by pressing Alt plus a letter of its label. While Alt is held, that
+//! letter is displayed underlined.
+//!
+//! Labels mark their access key with a `&` before the letter, e.g. `"&File"`
+//! is activated with Alt+F. A literal ampersand can be written as `&&`.
+
+use epaint::ahash::HashSet;
+use winit::event::VirtualKeyCode;
+
+/// Splits a label with an optional `&` marker into the text to display, and
+/// the access key together with the index (in chars) of the letter to
+/// underline in the displayed text.
+pub fn parse_label(label: &str) -> (String, Option<(usize, char)>) {
+    let mut text = String::with_capacity(label.len());
+    let mut access_key = None;
+    let mut num_chars = 0;
+    let mut chars = label.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '&' {
+            match chars.peek() {
+                Some('&') => {
+                    chars.next();
+                }
+                Some(next) if access_key.is_none() && keycode_for(*next).is_some() => {
+                    access_key = Some((num_chars, next.to_ascii_lowercase()));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        text.push(ch);
+        num_chars += 1;
+    }
+    (text, access_key)
+}
+
+/// Returns the key that has to be pressed to trigger the given access key.
+/// Only ASCII letters and digits can be used as access keys.
+pub fn keycode_for(key: char) -> Option<VirtualKeyCode> {
+    use VirtualKeyCode::*;
+    const LETTERS: [VirtualKeyCode; 26] = [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ];
+    const DIGITS: [VirtualKeyCode; 10] =
+        [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+    match key.to_ascii_lowercase() {
+        c @ 'a'..='z' => Some(LETTERS[(c as u8 - b'a') as usize]),
+        c @ '0'..='9' => Some(DIGITS[(c as u8 - b'0') as usize]),
+        _ => None,
+    }
+}
+
+/// Automatically assigns a distinct access key to each of the given labels,
+/// returning the labels with an `&` marker inserted. Labels that already have
+/// an access key keep it. Otherwise, the first letter not taken by a previous
+/// label is used. When all letters of a label are taken, it gets no access
+/// key.
+pub fn assign_access_keys(labels: &[&str]) -> Vec<String> {
+    let mut used: HashSet<char> = labels
+        .iter()
+        .filter_map(|label| parse_label(label).1)
+        .map(|(_, key)| key)
+        .collect();
+
+    labels
+        .iter()
+        .map(|label| {
+            if parse_label(label).1.is_some() {
+                return label.to_string();
+            }
+            let free = label.char_indices().find(|(_, ch)| {
+                ch.is_ascii_alphabetic() && !used.contains(&ch.to_ascii_lowercase())
+            });
+            match free {
+                Some((byte_idx, ch)) => {
+                    used.insert(ch.to_ascii_lowercase());
+                    format!("{}&{}", &label[..byte_idx], &label[byte_idx..])
+                }
+                None => label.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_label() {
+        assert_eq!(parse_label("&File"), ("File".into(), Some((0, 'f'))));
+        assert_eq!(parse_label("Save &As"), ("Save As".into(), Some((5, 'a'))));
+        assert_eq!(
+            parse_label("Fish && &Chips"),
+            ("Fish & Chips".into(), Some((7, 'c')))
+        );
+        assert_eq!(parse_label("Save & Exit"), ("Save & Exit".into(), None));
+        assert_eq!(parse_label("Plain"), ("Plain".into(), None));
+    }
+
+    #[test]
+    fn test_assign_access_keys() {
+        assert_eq!(
+            assign_access_keys(&["File", "Edit", "&Format", "Find"]),
+            vec!["F&ile", "&Edit", "&Format", "Fi&nd"]
+        );
+    }
+}
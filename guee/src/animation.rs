@@ -0,0 +1,36 @@
+use epaint::emath::lerp;
+
+/// The state of a value animated with
+/// [`Context::animate`](crate::context::Context::animate), stored in the widget
+/// memory.
+#[derive(Clone, Copy, Debug)]
+pub struct AnimationState {
+    pub from: f32,
+    pub to: f32,
+    /// The time when the animation started, as given by `Context::time`.
+    pub start_time: f64,
+}
+
+impl AnimationState {
+    /// Returns the animated value at `time` for an animation of the given
+    /// `duration`, in seconds.
+    pub fn value_at(&self, time: f64, duration: f32) -> f32 {
+        let t = self.progress(time, duration);
+        lerp(self.from..=self.to, ease_out_cubic(t))
+    }
+
+    /// Returns how far along the animation is at `time`, from 0 to 1.
+    pub fn progress(&self, time: f64, duration: f32) -> f32 {
+        if duration <= 0.0 {
+            1.0
+        } else {
+            (((time - self.start_time) / duration as f64) as f32).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Starts fast and slows down towards the end, which feels natural for UI
+/// elements moving to a new position.
+pub fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
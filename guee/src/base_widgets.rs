@@ -1,26 +1,79 @@
 
+pub mod accordion;
+
+pub mod autocomplete;
+
 pub mod button;
 
+pub mod badge;
+
+pub mod card;
+
+pub mod chevron;
+
 pub mod colored_box;
 
+pub mod connection;
+
+#[cfg(feature = "dates")]
+pub mod date_picker;
+
+pub mod form;
+
 pub mod text;
 
 pub mod image;
 
+pub mod icon;
+
+pub mod indent_container;
+
 pub mod text_edit;
 
+pub mod themed_container;
+
+pub mod toggle_button;
+
+pub mod tooltip;
+
 pub mod drag_value;
 
+pub mod menubar;
+
 pub mod menubar_button;
 
 pub mod box_container;
 
+pub mod grid_background;
+
+pub mod grid_container;
+
+pub mod keyed_list;
+
+pub mod range_slider;
+
+pub mod reorderable_list;
+
+pub mod rich_text;
+
 pub mod margin_container;
 
+pub mod popover;
+
+pub mod clip_container;
+
 pub mod stack_container;
 
 pub mod scroll_container;
 
+pub mod segmented_control;
+
+pub mod selectable_row;
+
+pub mod sticky_header_container;
+
+pub mod table;
+
 pub mod split_pane_container;
 
 pub mod sized_container;
@@ -28,3 +81,9 @@ pub mod sized_container;
 pub mod tinker_container;
 
 pub mod spacer;
+
+pub mod spin_box;
+
+pub mod time_picker;
+
+pub mod xy_pad;
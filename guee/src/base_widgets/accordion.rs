@@ -0,0 +1,306 @@
+use epaint::{ahash::HashSet, FontId, RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::{
+    input::MouseButton,
+    painter::{GueeGalley, GueeTextShape, Painter},
+    prelude::*,
+};
+
+/// A vertical list of collapsible sections, each one with a clickable header.
+/// By default, opening a section closes the others.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Accordion {
+    pub id: IdGen,
+    /// The title and contents for each of the sections.
+    pub sections: Vec<(String, DynWidget)>,
+    /// When set, any number of sections can be open at the same time.
+    #[builder(default)]
+    pub allow_multiple: bool,
+    #[builder(default = LayoutHints::fill_horizontal())]
+    pub hints: LayoutHints,
+    /// Fired with the index of a section when it gets opened or closed.
+    #[builder(strip_option)]
+    pub on_section_changed: Option<Callback<usize>>,
+    #[builder(default, strip_option)]
+    pub style_override: Option<AccordionStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// The bounds of each header, relative to the accordion. Computed during
+    /// layout.
+    #[builder(skip)]
+    pub header_rects: Vec<Rect>,
+    /// For each section, the index of its contents in the layout children, if
+    /// open. Computed during layout.
+    #[builder(skip)]
+    pub open_children: Vec<Option<usize>>,
+    #[builder(skip)]
+    pub header_galleys: Vec<GueeGalley>,
+}
+
+#[derive(Builder, Clone)]
+pub struct AccordionStyle {
+    #[builder(default = color!("#303030"))]
+    pub header_fill: Color32,
+    #[builder(default = color!("#3a3a3a"))]
+    pub header_hovered_fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub header_stroke: Stroke,
+    #[builder(default = color!("#c0c0c0"))]
+    pub indicator_color: Color32,
+    #[builder(default = Vec2::new(6.0, 4.0))]
+    pub header_padding: Vec2,
+    #[builder(default = 14.0)]
+    pub font_size: f32,
+    /// Vertical space between two sections.
+    #[builder(default = 2.0)]
+    pub separation: f32,
+}
+
+impl Default for AccordionStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The set of open sections, stored in the widget memory.
+#[derive(Default)]
+pub struct AccordionState {
+    pub open: HashSet<usize>,
+}
+
+impl Accordion {
+    fn style(&self, ctx: &Context) -> AccordionStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    /// Paints the triangle indicator for a section header, pointing right
+    /// when closed and down when open.
+    fn draw_indicator(painter: &mut Painter, center: Pos2, size: f32, open: bool, color: Color32) {
+        let direction = if open {
+            ChevronDirection::Down
+        } else {
+            ChevronDirection::Right
+        };
+        Chevron::paint(painter, center, size, direction.angle(), color);
+    }
+}
+
+impl Widget for Accordion {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let style = self.style(ctx);
+        let open = ctx
+            .memory
+            .get_or(widget_id, AccordionState::default())
+            .open
+            .clone();
+
+        self.header_rects.clear();
+        self.open_children.clear();
+        self.header_galleys.clear();
+
+        let indicator_width = style.font_size;
+        let mut children = vec![];
+        let mut y = 0.0;
+        let mut content_width: f32 = 0.0;
+
+        for (i, (title, contents)) in self.sections.iter_mut().enumerate() {
+            if i > 0 {
+                y += style.separation;
+            }
+
+            let galley = ctx.painter().galley(
+                title.clone(),
+                FontId::proportional(style.font_size),
+                f32::INFINITY,
+            );
+            let header_size = galley.bounds().size()
+                + Vec2::new(indicator_width, 0.0)
+                + 2.0 * style.header_padding;
+            content_width = content_width.max(header_size.x);
+            self.header_rects
+                .push(Rect::from_min_size(Pos2::new(0.0, y), header_size));
+            self.header_galleys.push(galley);
+            y += header_size.y;
+
+            if open.contains(&i) {
+                let mut child_layout = ctx.with_sibling_index(i, || {
+                    contents.widget.layout(
+                        ctx,
+                        widget_id,
+                        Vec2::new(available.x, (available.y - y).max(0.0)),
+                        force_shrink,
+                    )
+                });
+                child_layout.translate_y(y);
+                y += child_layout.bounds.height();
+                content_width = content_width.max(child_layout.bounds.width());
+                self.open_children.push(Some(children.len()));
+                children.push(child_layout);
+            } else {
+                self.open_children.push(None);
+            }
+        }
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => content_width,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => y,
+            SizeHint::Fill => available.y,
+        };
+
+        // Headers always span the whole width of the accordion
+        for rect in &mut self.header_rects {
+            rect.set_width(width);
+        }
+
+        Layout::with_children(widget_id, Vec2::new(width, height), children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let style = self.style(ctx);
+        let offset = layout.bounds.left_top().to_vec2();
+        let mouse_position = ctx.input_state.mouse.position;
+
+        for (i, (_, contents)) in self.sections.iter_mut().enumerate() {
+            let header_rect = self.header_rects[i].translate(offset);
+            let open = self.open_children[i];
+
+            {
+                let mut painter = ctx.painter();
+                painter.rect(RectShape {
+                    rect: header_rect,
+                    rounding: Rounding::same(2.0),
+                    fill: if header_rect.contains(mouse_position) {
+                        style.header_hovered_fill
+                    } else {
+                        style.header_fill
+                    },
+                    stroke: style.header_stroke,
+                });
+
+                let indicator_center = Pos2::new(
+                    header_rect.left() + style.header_padding.x + style.font_size / 2.0,
+                    header_rect.center().y,
+                );
+                Self::draw_indicator(
+                    &mut painter,
+                    indicator_center,
+                    style.font_size * 0.5,
+                    open.is_some(),
+                    style.indicator_color,
+                );
+
+                painter.text_with_galley(GueeTextShape {
+                    galley: self.header_galleys[i].clone(),
+                    pos: header_rect.left_top()
+                        + style.header_padding
+                        + Vec2::new(style.font_size, 0.0),
+                    underline: Stroke::NONE,
+                    angle: 0.0,
+                });
+            }
+
+            if let Some(child_idx) = open {
+                contents.widget.draw(ctx, &layout.children[child_idx]);
+            }
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (i, (_, contents)) in self.sections.iter_mut().enumerate() {
+            if let Some(child_idx) = self.open_children[i] {
+                contents.widget.on_event_capture(
+                    ctx,
+                    &layout.children[child_idx],
+                    cursor_position,
+                    events,
+                    status,
+                );
+            }
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (i, (_, contents)) in self.sections.iter_mut().enumerate() {
+            if let Some(child_idx) = self.open_children[i] {
+                contents.widget.on_event(
+                    ctx,
+                    &layout.children[child_idx],
+                    cursor_position,
+                    events,
+                    status,
+                );
+            }
+        }
+
+        if status.is_consumed() {
+            return;
+        }
+
+        let offset = layout.bounds.left_top().to_vec2();
+        let Some(clicked) = self
+            .header_rects
+            .iter()
+            .position(|rect| rect.translate(offset).contains(cursor_position))
+        else {
+            return;
+        };
+
+        for event in events {
+            if let Event::MousePressed(MouseButton::Primary) = event {
+                let mut state = ctx
+                    .memory
+                    .get_mut_or(layout.widget_id, AccordionState::default());
+                if !state.open.remove(&clicked) {
+                    if !self.allow_multiple {
+                        state.open.clear();
+                    }
+                    state.open.insert(clicked);
+                }
+                if let Some(on_section_changed) = self.on_section_changed.take() {
+                    ctx.dispatch_callback(on_section_changed, clicked);
+                }
+                status.consume_event();
+                break;
+            }
+        }
+    }
+}
+
+impl StyledWidget for Accordion {
+    type Style = AccordionStyle;
+}
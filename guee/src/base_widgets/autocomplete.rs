@@ -0,0 +1,339 @@
+use epaint::{emath::Align2, FontId, RectShape, Rounding};
+use guee_derives::Builder;
+use winit::event::VirtualKeyCode;
+
+use crate::{
+    input::MouseButton,
+    popup::{place_popup, Side},
+    prelude::*,
+};
+
+/// A [`TextEdit`] showing a dropdown of suggestions below it while typing,
+/// for search or command inputs. The app computes the `suggestions` from the
+/// current contents every frame.
+///
+/// Suggestions can be highlighted with the Up and Down keys or the mouse.
+/// Enter or a click accepts the highlighted suggestion, sending it to the
+/// `on_changed` and `on_submit` callbacks of the text edit. Tab only sends it
+/// to `on_changed`, to keep editing. The dropdown is hidden while the field
+/// is empty, and after pressing Escape until the contents change.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Autocomplete {
+    /// The text edit the suggestions are for. Its callbacks also receive the
+    /// accepted suggestions.
+    pub text_edit: TextEdit,
+    pub suggestions: Vec<String>,
+    /// The maximum number of suggestions shown at once.
+    #[builder(default = 8)]
+    pub max_visible: usize,
+    #[builder(default, strip_option)]
+    pub style_override: Option<AutocompleteStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// The size of the dropdown. Computed during layout.
+    #[builder(skip)]
+    pub dropdown_size: Vec2,
+    /// The height of each suggestion row. Computed during layout.
+    #[builder(skip)]
+    pub row_height: f32,
+}
+
+#[derive(Builder, Clone)]
+pub struct AutocompleteStyle {
+    #[builder(default = color!("#262626"))]
+    pub fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub stroke: Stroke,
+    #[builder(default = color!("#4a7fb5"))]
+    pub highlighted_fill: Color32,
+    #[builder(default = Rounding::same(2.0))]
+    pub rounding: Rounding,
+    #[builder(default = FontId::proportional(14.0))]
+    pub font: FontId,
+    /// The space around the text of each suggestion.
+    #[builder(default = Vec2::new(6.0, 3.0))]
+    pub row_padding: Vec2,
+}
+
+impl Default for AutocompleteStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct AutocompleteState {
+    pub highlighted: Option<usize>,
+    /// Set when the dropdown is closed with Escape.
+    pub dismissed: bool,
+    /// Used to reset the state when the contents change.
+    pub last_contents: String,
+}
+
+impl Autocomplete {
+    fn style(&self, ctx: &Context) -> AutocompleteStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    fn visible_suggestions(&self) -> &[String] {
+        &self.suggestions[..self.suggestions.len().min(self.max_visible)]
+    }
+
+    fn is_dropdown_open(&self, ctx: &Context, widget_id: WidgetId) -> bool {
+        !self.text_edit.contents.is_empty()
+            && !self.suggestions.is_empty()
+            && ctx.is_focused(widget_id)
+            && !ctx
+                .state::<AutocompleteState>(widget_id)
+                .get_or_default()
+                .dismissed
+    }
+
+    /// Returns the bounds of the dropdown, kept inside the screen.
+    fn dropdown_rect(&self, ctx: &Context, layout: &Layout) -> Rect {
+        let screen = Rect::from_min_size(Pos2::ZERO, ctx.screen_size());
+        let pos = place_popup(
+            TextEdit::field_bounds(layout),
+            self.dropdown_size,
+            Side::Below,
+            Align::Start,
+            screen,
+        );
+        Rect::from_min_size(pos, self.dropdown_size)
+    }
+
+    fn row_rect(&self, dropdown: Rect, row: usize) -> Rect {
+        Rect::from_min_size(
+            dropdown.min + Vec2::new(0.0, self.row_height * row as f32),
+            Vec2::new(dropdown.width(), self.row_height),
+        )
+    }
+
+    /// Sends the suggestion to the callbacks of the text edit.
+    fn accept(&mut self, ctx: &Context, row: usize, submit: bool) {
+        let suggestion = self.suggestions[row].clone();
+        if let Some(on_changed) = self.text_edit.on_changed.take() {
+            ctx.dispatch_callback(on_changed, suggestion.clone());
+        }
+        if submit {
+            if let Some(on_submit) = self.text_edit.on_submit.take() {
+                ctx.dispatch_callback(on_submit, suggestion);
+            }
+        }
+    }
+}
+
+impl Widget for Autocomplete {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let layout = self
+            .text_edit
+            .layout(ctx, parent_id, available, force_shrink);
+
+        let style = self.style(ctx);
+        let mut text_size = Vec2::ZERO;
+        for suggestion in self.visible_suggestions() {
+            let size =
+                ctx.painter()
+                    .measure_text(suggestion.clone(), style.font.clone(), f32::INFINITY);
+            text_size = text_size.max(size);
+        }
+        self.row_height = text_size.y + style.row_padding.y * 2.0;
+        let width =
+            (text_size.x + style.row_padding.x * 2.0).max(TextEdit::field_bounds(&layout).width());
+        self.dropdown_size = Vec2::new(
+            width,
+            self.row_height * self.visible_suggestions().len() as f32,
+        );
+
+        layout
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        self.text_edit.draw(ctx, layout);
+        if !self.is_dropdown_open(ctx, layout.widget_id) {
+            return;
+        }
+
+        let style = self.style(ctx);
+        let dropdown = self.dropdown_rect(ctx, layout);
+        let highlighted = ctx
+            .state::<AutocompleteState>(layout.widget_id)
+            .get_or_default()
+            .highlighted;
+
+        ctx.painter().with_overlay(|painter| {
+            painter.rect(RectShape {
+                rect: dropdown,
+                rounding: style.rounding,
+                fill: style.fill,
+                stroke: style.stroke,
+            });
+            for (row, suggestion) in self.visible_suggestions().iter().enumerate() {
+                let rect = self.row_rect(dropdown, row);
+                if highlighted == Some(row) {
+                    painter.rect(RectShape {
+                        rect,
+                        rounding: style.rounding,
+                        fill: style.highlighted_fill,
+                        stroke: Stroke::NONE,
+                    });
+                }
+                painter.text(
+                    rect.left_center() + Vec2::new(style.row_padding.x, 0.0),
+                    Align2::LEFT_CENTER,
+                    suggestion,
+                    style.font.clone(),
+                );
+            }
+        });
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        let text_edit: &dyn Widget = &self.text_edit;
+        text_edit.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.text_edit
+            .on_event_capture(ctx, layout, cursor_position, events, status);
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let widget_id = layout.widget_id;
+        {
+            let mut state = ctx
+                .state::<AutocompleteState>(widget_id)
+                .get_mut_or_default();
+            if state.last_contents != self.text_edit.contents {
+                state.last_contents = self.text_edit.contents.clone();
+                state.highlighted = None;
+                state.dismissed = false;
+            }
+        }
+
+        if !self.is_dropdown_open(ctx, widget_id) || status.is_consumed() {
+            self.text_edit
+                .on_event(ctx, layout, cursor_position, events, status);
+            return;
+        }
+
+        let num_rows = self.visible_suggestions().len();
+        let dropdown = self.dropdown_rect(ctx, layout);
+        let hovered_row =
+            (0..num_rows).find(|row| self.row_rect(dropdown, *row).contains(cursor_position));
+
+        // The keys used by the dropdown don't reach the text edit
+        let mut text_edit_events = Vec::with_capacity(events.len());
+        let mut accepted = None;
+        let mut state = ctx
+            .state::<AutocompleteState>(widget_id)
+            .get_mut_or_default();
+        if hovered_row.is_some() {
+            state.highlighted = hovered_row;
+        }
+        for event in events {
+            match event {
+                Event::KeyPressed(VirtualKeyCode::Down) => {
+                    state.highlighted = Some(match state.highlighted {
+                        Some(row) => (row + 1) % num_rows,
+                        None => 0,
+                    });
+                }
+                Event::KeyPressed(VirtualKeyCode::Up) => {
+                    state.highlighted = Some(match state.highlighted {
+                        Some(row) => (row + num_rows - 1) % num_rows,
+                        None => num_rows - 1,
+                    });
+                }
+                Event::KeyPressed(VirtualKeyCode::Escape) => {
+                    state.dismissed = true;
+                }
+                Event::KeyPressed(VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter)
+                    if state.highlighted.is_some() =>
+                {
+                    accepted = state.highlighted.map(|row| (row, true));
+                }
+                Event::KeyPressed(VirtualKeyCode::Tab) if state.highlighted.is_some() => {
+                    ctx.claim_tab_key();
+                    accepted = state.highlighted.map(|row| (row, false));
+                }
+                Event::MousePressed(MouseButton::Primary) if hovered_row.is_some() => {
+                    accepted = hovered_row.map(|row| (row, true));
+                }
+                _ => text_edit_events.push(event.clone()),
+            }
+        }
+        drop(state);
+
+        match accepted {
+            Some((row, submit)) if row < num_rows => {
+                self.accept(ctx, row, submit);
+                status.consume_event();
+            }
+            _ => {
+                self.text_edit
+                    .on_event(ctx, layout, cursor_position, &text_edit_events, status);
+                if text_edit_events.len() < events.len() && status.is_ignored() {
+                    status.consume_event();
+                }
+            }
+        }
+    }
+}
+
+impl StyledWidget for Autocomplete {
+    type Style = AutocompleteStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropdown_below_field() {
+        let ctx = Context::new(Vec2::new(400.0, 300.0), vec![]);
+        let suggestions = (0..20).map(|i| format!("suggestion {i}")).collect();
+        let mut autocomplete = Autocomplete::new(
+            TextEdit::new(IdGen::key("search"), "sug".to_string()),
+            suggestions,
+        )
+        .max_visible(5);
+
+        let mut layout =
+            autocomplete.layout(&ctx, WidgetId::null(), Vec2::new(400.0, 300.0), false);
+        layout.to_absolute(Vec2::ZERO);
+        let field = TextEdit::field_bounds(&layout);
+        let dropdown = autocomplete.dropdown_rect(&ctx, &layout);
+        assert_eq!(dropdown.top(), field.bottom());
+        assert_eq!(dropdown.left(), field.left());
+        assert!(dropdown.width() >= field.width());
+        assert_eq!(dropdown.height(), autocomplete.row_height * 5.0);
+    }
+}
@@ -0,0 +1,160 @@
+use epaint::{FontId, RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::{painter::GueeTextShape, prelude::*};
+
+/// Draws a small bubble with a count (e.g. unread messages) on top of the
+/// top-right corner of its contents. The contents are laid out and drawn as if
+/// the badge wasn't there.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Badge {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    pub count: u32,
+    /// When set, the badge is also displayed when the count is zero.
+    #[builder(default)]
+    pub show_zero: bool,
+    /// Counts larger than this are displayed as `{max_count}+`.
+    #[builder(default = 99)]
+    pub max_count: u32,
+    #[builder(default, strip_option)]
+    pub style_override: Option<BadgeStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct BadgeStyle {
+    #[builder(default = color!("#d53b3b"))]
+    pub fill: Color32,
+    #[builder(default = Color32::WHITE)]
+    pub text_color: Color32,
+    #[builder(default = 11.0)]
+    pub font_size: f32,
+    /// The space between the count text and the border of the bubble.
+    #[builder(default = Vec2::new(4.0, 1.0))]
+    pub padding: Vec2,
+}
+
+impl Default for BadgeStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Badge {
+    fn label(&self) -> Option<String> {
+        if self.count == 0 && !self.show_zero {
+            None
+        } else if self.count > self.max_count {
+            Some(format!("{}+", self.max_count))
+        } else {
+            Some(self.count.to_string())
+        }
+    }
+}
+
+impl Widget for Badge {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let contents_layout = self
+            .contents
+            .widget
+            .layout(ctx, widget_id, available, force_shrink);
+        Layout::with_children(
+            widget_id,
+            contents_layout.bounds.size(),
+            vec![contents_layout],
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        self.contents.widget.draw(ctx, &layout.children[0]);
+
+        let Some(label) = self.label() else {
+            return;
+        };
+
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+
+        let mut painter = ctx.painter();
+        let galley = painter.galley(label, FontId::proportional(style.font_size), f32::INFINITY);
+        let text_size = galley.bounds().size();
+        let height = text_size.y + 2.0 * style.padding.y;
+        // Single digits get a circle, longer counts stretch into a pill.
+        let width = (text_size.x + 2.0 * style.padding.x).max(height);
+        let bubble = Rect::from_center_size(
+            layout.children[0].bounds.right_top(),
+            Vec2::new(width, height),
+        );
+
+        painter.with_overlay(|painter| {
+            painter.rect(RectShape {
+                rect: bubble,
+                rounding: Rounding::same(height / 2.0),
+                fill: style.fill,
+                stroke: Stroke::NONE,
+            });
+            let old_color = painter.text_color;
+            painter.text_color = style.text_color;
+            painter.text_with_galley(GueeTextShape {
+                pos: bubble.center() - text_size / 2.0,
+                galley,
+                underline: Stroke::NONE,
+                angle: 0.0,
+            });
+            painter.text_color = old_color;
+        });
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.contents.widget.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, events, status)
+    }
+}
+
+impl StyledWidget for Badge {
+    type Style = BadgeStyle;
+}
@@ -1,11 +1,11 @@
 use crate::{
     context::Context,
     input::{Event, EventStatus},
-    layout::{Align, Axis, AxisDirections, Layout, LayoutHints, SizeHint},
+    layout::{Align, Axis, AxisDirections, Direction, Layout, LayoutHints, SizeHint},
     widget::{DynWidget, Widget},
     widget_id::{IdGen, WidgetId},
 };
-use epaint::{Pos2, Vec2};
+use epaint::{Color32, Pos2, Rect, RectShape, Rounding, Stroke, Vec2};
 use guee_derives::Builder;
 use itertools::Itertools;
 
@@ -23,6 +23,17 @@ pub struct BoxContainer {
     main_align: Align,
     #[builder(default)]
     cross_align: Align,
+    /// Overrides the direction set in the [`Context`], e.g. for a row of
+    /// numbers that should always read left to right.
+    #[builder(default, strip_option)]
+    direction: Option<Direction>,
+    /// When set, alternating backgrounds are drawn behind the children, like
+    /// the rows of a table. Each stripe spans the whole box across the axis.
+    #[builder(default)]
+    striped: bool,
+    /// The even and odd stripe colors. Uses the theme's colors when not set.
+    #[builder(default, strip_option)]
+    stripe_colors: Option<(Color32, Color32)>,
 }
 
 impl BoxContainer {
@@ -33,6 +44,32 @@ impl BoxContainer {
     pub fn horizontal(id_gen: IdGen, contents: Vec<DynWidget>) -> BoxContainer {
         Self::new(id_gen, Axis::Horizontal, contents)
     }
+
+    /// Returns the stripe behind each child. Stripes extend up to the next
+    /// child, so the separation between children is covered too.
+    fn stripe_rects(&self, layout: &Layout) -> Vec<Rect> {
+        let bounds = layout.bounds;
+        let mut rects = Vec::with_capacity(layout.children.len());
+        for (i, child) in layout.children.iter().enumerate() {
+            let next = layout.children.get(i + 1).map(|next| next.bounds);
+            let rect = match self.axis {
+                Axis::Vertical => {
+                    let bottom = next.map(|n| n.top()).unwrap_or(child.bounds.bottom());
+                    Rect::from_x_y_ranges(bounds.x_range(), child.bounds.top()..=bottom)
+                }
+                Axis::Horizontal => {
+                    // Children may be laid out right to left
+                    let end = match next {
+                        Some(n) if n.left() >= child.bounds.left() => n.left(),
+                        _ => child.bounds.right(),
+                    };
+                    Rect::from_x_y_ranges(child.bounds.left()..=end, bounds.y_range())
+                }
+            };
+            rects.push(rect);
+        }
+        rects
+    }
 }
 
 impl Widget for BoxContainer {
@@ -50,12 +87,12 @@ impl Widget for BoxContainer {
             return Layout::leaf(widget_id, Vec2::ZERO);
         }
 
-        // Compute the child layouts as if they were all in shrink mode. This
-        // helps compute some metrics later on.
-        let shrink_child_layouts = self
+        // Measure the children as if they were all in shrink mode. This helps
+        // compute some metrics later on.
+        let shrink_child_sizes = self
             .contents
             .iter_mut()
-            .map(|x| x.widget.layout(ctx, parent_id, available, true))
+            .map(|x| x.widget.measure(ctx, widget_id, available))
             .collect_vec();
 
         // The `cross_space` is the amount of space this box container will
@@ -71,8 +108,7 @@ impl Widget for BoxContainer {
                 let axis = self.axis;
                 let mut size_cross = 0.0;
 
-                for c_layout in &shrink_child_layouts {
-                    let s = c_layout.bounds.size();
+                for s in &shrink_child_sizes {
                     size_cross = f32::max(size_cross, s.cross_dir(axis));
                 }
                 size_cross
@@ -84,7 +120,7 @@ impl Widget for BoxContainer {
         let mut total_filled_weight = 0;
         let mut total_shrink_space = 0.0;
         let mut fill_child_count = 0;
-        for (c, shrk) in self.contents.iter_mut().zip(&shrink_child_layouts) {
+        for (c, shrk) in self.contents.iter_mut().zip(&shrink_child_sizes) {
             match c
                 .widget
                 .layout_hints()
@@ -93,7 +129,7 @@ impl Widget for BoxContainer {
                 .or_force(force_shrink)
             {
                 SizeHint::Shrink => {
-                    total_shrink_space += shrk.bounds.size().main_dir(axis);
+                    total_shrink_space += shrk.main_dir(axis);
                 }
                 SizeHint::Fill => {
                     fill_child_count += 1;
@@ -107,7 +143,7 @@ impl Widget for BoxContainer {
 
         let mut main_offset = 0.0;
         let mut children = vec![];
-        for ch in &mut self.contents {
+        for (i, ch) in self.contents.iter_mut().enumerate() {
             let c_available = match ch
                 .widget
                 .layout_hints()
@@ -129,9 +165,10 @@ impl Widget for BoxContainer {
                 Axis::Vertical => Vec2::Y,
                 Axis::Horizontal => Vec2::X,
             };
-            let ch_layout = ch
-                .widget
-                .layout(ctx, widget_id, c_available, force_shrink)
+            let ch_layout = ctx
+                .with_sibling_index(i, || {
+                    ch.widget.layout(ctx, widget_id, c_available, force_shrink)
+                })
                 .clear_translation()
                 .translated(axis_vec * main_offset);
             main_offset += ch_layout.bounds.size().main_dir(axis) + self.separation;
@@ -185,21 +222,64 @@ impl Widget for BoxContainer {
             }
         }
 
-        Layout::with_children(
-            widget_id,
-            axis.new_vec2(
-                children
-                    .last()
-                    // The rightmost or bottommost position, depending on axis
-                    .map(|x| x.bounds.max.to_vec2().main_dir(axis))
-                    .unwrap_or(0.0),
-                cross_space,
-            ),
-            children,
-        )
+        let size = axis.new_vec2(
+            children
+                .last()
+                // The rightmost or bottommost position, depending on axis
+                .map(|x| x.bounds.max.to_vec2().main_dir(axis))
+                .unwrap_or(0.0),
+            cross_space,
+        );
+
+        // Right to left layouts are computed as usual and then mirrored. This
+        // reverses the order of rows and the cross alignment of columns.
+        if self.direction.unwrap_or(ctx.direction()) == Direction::RightToLeft {
+            for ch_layout in &mut children {
+                ch_layout.mirror_x(size.x);
+            }
+        }
+
+        Layout::with_children(widget_id, size, children)
+    }
+
+    fn measure(&mut self, ctx: &Context, parent_id: WidgetId, available: Vec2) -> Vec2 {
+        let widget_id = self.id.resolve(parent_id);
+        if self.contents.is_empty() {
+            return Vec2::ZERO;
+        }
+
+        // In shrink mode, children are simply stacked along the main axis
+        let axis = self.axis;
+        let mut main_size = 0.0;
+        let mut cross_size: f32 = 0.0;
+        for ch in &mut self.contents {
+            let ch_available = axis.new_vec2(
+                available.main_dir(axis) - main_size,
+                available.cross_dir(axis),
+            );
+            let ch_size = ch.widget.measure(ctx, widget_id, ch_available);
+            main_size += ch_size.main_dir(axis) + self.separation;
+            cross_size = cross_size.max(ch_size.cross_dir(axis));
+        }
+        axis.new_vec2(main_size - self.separation, cross_size)
     }
 
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        if self.striped {
+            let (even, odd) = self.stripe_colors.unwrap_or_else(|| {
+                let theme = ctx.theme.borrow();
+                (theme.stripe_even, theme.stripe_odd)
+            });
+            for (i, rect) in self.stripe_rects(layout).into_iter().enumerate() {
+                ctx.painter().rect(RectShape {
+                    rect,
+                    rounding: Rounding::none(),
+                    fill: if i % 2 == 0 { even } else { odd },
+                    stroke: Stroke::NONE,
+                });
+            }
+        }
+
         for (child, layout) in self.contents.iter_mut().zip(layout.children.iter()) {
             child.widget.draw(ctx, layout);
         }
@@ -209,6 +289,20 @@ impl Widget for BoxContainer {
         self.layout_hints
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (ch, ch_layout) in self.contents.iter_mut().zip(layout.children.iter()) {
+            ch.widget
+                .on_event_capture(ctx, ch_layout, cursor_position, events, status);
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -223,3 +317,150 @@ impl Widget for BoxContainer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use epaint::FontFamily;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_fill_children_split_space() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = BoxContainer::horizontal(
+            IdGen::key("hbox"),
+            vec![
+                ColoredBox::background(Color32::RED).build(),
+                ColoredBox::background(Color32::BLUE).build(),
+            ],
+        )
+        .separation(0.0)
+        .layout_hints(LayoutHints::fill())
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        assert_eq!(layout.bounds.size(), Vec2::new(200.0, 100.0));
+        let [left, right] = [&layout.children[0], &layout.children[1]];
+        assert_eq!(
+            left.bounds,
+            Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 100.0))
+        );
+        assert_eq!(
+            right.bounds,
+            Rect::from_min_size(Pos2::new(100.0, 0.0), Vec2::new(100.0, 100.0))
+        );
+    }
+
+    #[test]
+    fn test_shrink_children_are_stacked() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = BoxContainer::vertical(
+            IdGen::key("vbox"),
+            vec![
+                ColoredBox::new(IdGen::key(1))
+                    .min_size(Vec2::new(10.0, 20.0))
+                    .build(),
+                ColoredBox::new(IdGen::key(2))
+                    .min_size(Vec2::new(30.0, 20.0))
+                    .build(),
+            ],
+        )
+        .separation(5.0)
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        assert_eq!(layout.bounds.size(), Vec2::new(30.0, 45.0));
+        assert_eq!(layout.children[1].bounds.min, Pos2::new(0.0, 25.0));
+    }
+
+    #[test]
+    fn test_measure_ignores_screen_size() {
+        let ctx = Context::new(Vec2::new(10.0, 10.0), vec![]);
+        let mut widget = BoxContainer::horizontal(
+            IdGen::key("hbox"),
+            vec![
+                ColoredBox::new(IdGen::key(1))
+                    .min_size(Vec2::new(40.0, 20.0))
+                    .build(),
+                ColoredBox::background(Color32::RED).build(),
+            ],
+        )
+        .separation(0.0)
+        .layout_hints(LayoutHints::fill())
+        .build();
+
+        assert_eq!(ctx.measure(&mut widget), Vec2::new(40.0, 20.0));
+    }
+
+    #[test]
+    fn test_anonymous_children_ids() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = BoxContainer::horizontal(
+            IdGen::key("hbox"),
+            vec![
+                Text::new("same".into()).build(),
+                Text::new("same".into()).build(),
+                Spacer::h(1.0).build(),
+                Spacer::h(1.0).build(),
+                Icon::new('x').font_family(FontFamily::Proportional).build(),
+                Icon::new('x').font_family(FontFamily::Proportional).build(),
+            ],
+        )
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        let ids: Vec<WidgetId> = layout.children.iter().map(|ch| ch.widget_id).collect();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(!ids[i + 1..].contains(id), "Duplicate id for child {i}");
+        }
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        let mut ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        ctx.set_direction(Direction::RightToLeft);
+        let mut widget = BoxContainer::horizontal(
+            IdGen::key("hbox"),
+            vec![
+                ColoredBox::new(IdGen::key(1))
+                    .min_size(Vec2::new(10.0, 20.0))
+                    .build(),
+                ColoredBox::new(IdGen::key(2))
+                    .min_size(Vec2::new(20.0, 20.0))
+                    .build(),
+            ],
+        )
+        .separation(0.0)
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        assert_eq!(layout.bounds.size(), Vec2::new(30.0, 20.0));
+        assert_eq!(layout.children[0].bounds.min, Pos2::new(20.0, 0.0));
+        assert_eq!(layout.children[1].bounds.min, Pos2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_stripes_cover_separation() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let child = |key| {
+            ColoredBox::new(IdGen::key(key))
+                .min_size(Vec2::new(10.0, 20.0))
+                .build()
+        };
+        let mut widget = BoxContainer::vertical(IdGen::key("vbox"), vec![child(1), child(2)])
+            .separation(5.0)
+            .layout_hints(LayoutHints::fill_horizontal())
+            .striped(true);
+
+        let mut layout = widget.layout(&ctx, WidgetId::null(), Vec2::new(200.0, 100.0), false);
+        layout.to_absolute(Vec2::ZERO);
+        let stripes = widget.stripe_rects(&layout);
+        assert_eq!(
+            stripes,
+            vec![
+                Rect::from_min_size(Pos2::ZERO, Vec2::new(200.0, 25.0)),
+                Rect::from_min_size(Pos2::new(0.0, 25.0), Vec2::new(200.0, 20.0)),
+            ]
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
+    access_keys,
     callback::Callback,
     context::Context,
     extension_traits::Color32Ext,
     input::{Event, EventStatus, MouseButton},
+    interaction::{Interaction, InteractionState, InteractionStyle},
     layout::{Layout, LayoutHints, SizeHint},
     prelude::{Align, BoxContainer, StyledWidget},
     widget::{DynWidget, Widget},
@@ -10,6 +12,7 @@ use crate::{
 };
 use epaint::{emath::Align2, Color32, Pos2, Rect, RectShape, Rounding, Stroke, TextureId, Vec2};
 use guee_derives::Builder;
+use winit::event::VirtualKeyCode;
 
 use super::{image::Image, text::Text};
 
@@ -21,6 +24,10 @@ pub struct Button {
     pub pressed: bool,
     #[builder(skip)]
     pub hovered: bool,
+    /// Disabled buttons ignore all input, and are drawn using the disabled
+    /// colors of their style.
+    #[builder(default)]
+    pub disabled: bool,
     #[builder(default)]
     pub hints: LayoutHints,
     #[builder(default = Vec2::new(10.0, 10.0))]
@@ -32,8 +39,136 @@ pub struct Button {
     pub on_click: Option<Callback<()>>,
     #[builder(default, strip_option)]
     pub style_override: Option<ButtonStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
     #[builder(default)]
     pub min_size: Vec2,
+    /// Pressing Alt plus this key clicks the button. Set automatically by the
+    /// label constructors when the label has an `&` marker, see
+    /// [`access_keys`](crate::access_keys).
+    #[builder(default, strip_option)]
+    pub access_key: Option<char>,
+    /// Shown in the status bar while the button is hovered, see
+    /// [`Context::set_status`].
+    #[builder(default, strip_option)]
+    pub status_text: Option<String>,
+    /// When set, the contents are clipped to the bounds of the button, so
+    /// e.g. a long label doesn't spill out of a fixed size button.
+    #[builder(default)]
+    pub clip_contents: bool,
+    /// Orders this widget in the Tab traversal, see
+    /// [`focus_order`](crate::focus_order). Widgets without an index come
+    /// after the ones with one, in layout order.
+    #[builder(default, strip_option)]
+    pub tab_index: Option<i32>,
+    /// When set, Tab skips this button.
+    #[builder(default)]
+    pub skip_tab: bool,
+}
+
+/// Where the icon goes relative to the label, see [`IconLabel`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IconPosition {
+    #[default]
+    Left,
+    Right,
+    Above,
+    Below,
+}
+
+/// Builds a [`Button`] showing an icon, a label, or both. The result is a
+/// regular button, so it can be styled like any other:
+///
+/// ```ignore
+/// IconLabel::new()
+///     .label("Open".to_string())
+///     .icon((texture_id, uv_rect))
+///     .position(IconPosition::Above)
+///     .button()
+///     .on_click(on_open)
+/// ```
+#[derive(Builder)]
+pub struct IconLabel {
+    /// The label, which may contain an `&` access key marker.
+    #[builder(skip)]
+    pub label: Option<String>,
+    /// The icon texture, and the region of it to show.
+    #[builder(skip)]
+    pub icon: Option<(TextureId, Rect)>,
+    #[builder(default = Vec2::new(16.0, 16.0))]
+    pub icon_size: Vec2,
+    #[builder(default)]
+    pub position: IconPosition,
+    /// The space between the icon and the label.
+    #[builder(default = 8.0)]
+    pub spacing: f32,
+}
+
+impl IconLabel {
+    pub fn label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn icon(mut self, icon: (TextureId, Rect)) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn button(self) -> Button {
+        let (label, access_key) = match &self.label {
+            Some(label) => {
+                let (label, access_key) = access_keys::parse_label(label);
+                (Some(label), access_key)
+            }
+            None => (None, None),
+        };
+        let image = |icon: TextureId, uv_rect: Rect| {
+            Image::new(IdGen::key(icon), icon, LayoutHints::shrink())
+                .min_size(self.icon_size)
+                .uv_rect(uv_rect)
+                .build()
+        };
+
+        let mut button = match (label, self.icon) {
+            (Some(label), Some((icon, uv_rect))) => {
+                let new_id = IdGen::key((icon, &label));
+                let img = image(icon, uv_rect);
+                let text = Button::label_text(label, access_key).build();
+                let icon_first = matches!(self.position, IconPosition::Left | IconPosition::Above);
+                let children = if icon_first {
+                    vec![img, text]
+                } else {
+                    vec![text, img]
+                };
+                let row_id = new_id.with("row");
+                let contents = match self.position {
+                    IconPosition::Left | IconPosition::Right => {
+                        BoxContainer::horizontal(row_id, children)
+                    }
+                    IconPosition::Above | IconPosition::Below => {
+                        BoxContainer::vertical(row_id, children)
+                    }
+                }
+                .separation(self.spacing)
+                .cross_align(Align::Center);
+                Button::new(new_id.with("button"), contents.build())
+            }
+            (None, Some((icon, uv_rect))) => {
+                Button::new(IdGen::key(("button", icon)), image(icon, uv_rect))
+            }
+            (Some(label), None) => Button::new(
+                IdGen::key(&label),
+                Button::label_text(label, access_key).build(),
+            ),
+            (None, None) => Button::new(IdGen::key("button"), Text::new(String::new()).build()),
+        };
+        button.access_key = access_key.map(|(_, key)| key);
+        button
+    }
 }
 
 #[derive(Builder, Default, Clone)]
@@ -46,12 +181,46 @@ pub struct ButtonStyle {
     pub idle_stroke: Stroke,
     #[builder(default = Rounding::same(2.0))]
     pub rounding: Rounding,
+    /// The stroke while focused. Defaults to the idle stroke, since focused
+    /// buttons already get a focus ring.
+    #[builder(default)]
+    pub focused_stroke: Option<Stroke>,
+    /// Defaults to a faded out idle fill.
+    #[builder(default)]
+    pub disabled_fill: Option<Color32>,
+    /// Defaults to a faded out idle stroke.
+    #[builder(default)]
+    pub disabled_stroke: Option<Stroke>,
 }
 
 impl Button {
     pub fn with_label(label: impl Into<String>) -> Self {
-        let label = label.into();
-        Button::new(IdGen::key(&label), Text::new(label).build())
+        let (label, access_key) = access_keys::parse_label(&label.into());
+        let mut button = Button::new(
+            IdGen::key(&label),
+            Self::label_text(label, access_key).build(),
+        );
+        button.access_key = access_key.map(|(_, key)| key);
+        button
+    }
+
+    /// Returns the current state of the button, used to pick its colors.
+    pub fn interaction_state(&self, ctx: &Context, layout: &Layout) -> InteractionState {
+        InteractionState {
+            hovered: self.hovered,
+            pressed: self.pressed,
+            focused: ctx.is_focused(layout.widget_id),
+            disabled: self.disabled,
+        }
+    }
+
+    /// Returns the text widget for a label, underlining its access key.
+    fn label_text(label: String, access_key: Option<(usize, char)>) -> Text {
+        let text = Text::new(label);
+        match access_key {
+            Some((idx, _)) => text.access_key_index(idx),
+            None => text,
+        }
     }
 
     pub fn with_icon(icon: TextureId, uv_rect: Rect, size: Vec2) -> Self {
@@ -68,25 +237,23 @@ impl Button {
         uv_rect: Rect,
         icon_size: Vec2,
     ) -> Self {
-        let label = label.into();
-        let new_id = IdGen::key((icon, &label));
-        let img = Image::new(IdGen::key(icon), icon, LayoutHints::shrink())
-            .min_size(icon_size)
-            .uv_rect(uv_rect)
-            .build();
-        let text = Text::new(label).build();
-        let contents = BoxContainer::horizontal(new_id.with("row"), vec![img, text])
-            .separation(8.0)
-            .cross_align(Align::Center);
-        Button::new(new_id.with("button"), contents.build())
+        IconLabel::new()
+            .label(label.into())
+            .icon((icon, uv_rect))
+            .icon_size(icon_size)
+            .button()
     }
 
     pub fn with_colored_label(label: impl Into<String>, color: Color32) -> Self {
-        let label = label.into();
-        Button::new(
+        let (label, access_key) = access_keys::parse_label(&label.into());
+        let mut button = Button::new(
             IdGen::key(&label),
-            Text::new(label).color_override(color).build(),
-        )
+            Self::label_text(label, access_key)
+                .color_override(color)
+                .build(),
+        );
+        button.access_key = access_key.map(|(_, key)| key);
+        button
     }
 }
 
@@ -99,6 +266,9 @@ impl Widget for Button {
         force_shrink: bool,
     ) -> Layout {
         let widget_id = self.id.resolve(parent_id);
+        if let Some(key) = self.access_key {
+            ctx.register_access_key(key, widget_id);
+        }
         let padding = self.padding;
         let mut contents_layout =
             self.contents
@@ -128,38 +298,53 @@ impl Widget for Button {
     }
 
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
-        let default_style = ButtonStyle::default();
-        let theme = ctx.theme.borrow();
-        let style = self
-            .style_override
-            .as_ref()
-            .unwrap_or_else(|| theme.get_style::<Self>().unwrap_or(&default_style));
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
 
+        let state = self.interaction_state(ctx, layout);
+        let (fill, stroke) = style.resolve(state);
         ctx.painter().rect(RectShape {
             rect: layout.bounds,
             rounding: style.rounding,
-            fill: if self.pressed {
-                style.pressed_fill
-            } else if self.hovered {
-                style.hovered_fill
-            } else {
-                style.idle_fill
-            },
-            stroke: if self.pressed {
-                style.pressed_stroke
-            } else if self.hovered {
-                style.hovered_stroke
-            } else {
-                style.idle_stroke
-            },
+            fill,
+            stroke,
         });
-        self.contents.widget.draw(ctx, &layout.children[0]);
+        if state.focused && !state.disabled {
+            let focus_ring = ctx.theme.borrow().focus_ring.clone();
+            ctx.painter().focus_ring(layout.bounds, &focus_ring);
+        }
+        if self.clip_contents {
+            ctx.with_clip_rect(layout.bounds, || {
+                self.contents.widget.draw(ctx, &layout.children[0])
+            });
+        } else {
+            self.contents.widget.draw(ctx, &layout.children[0]);
+        }
     }
 
     fn layout_hints(&self) -> LayoutHints {
         self.hints
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -168,17 +353,55 @@ impl Widget for Button {
         events: &[Event],
         event_status: &mut EventStatus,
     ) {
+        if self.disabled {
+            return;
+        }
+        if !self.skip_tab {
+            ctx.register_focusable(layout.widget_id, layout.bounds, self.tab_index);
+        }
         if event_status.is_consumed() {
             return;
         }
 
-        if layout.bounds.contains(cursor_position) {
+        // Focused buttons are clicked with Enter or Space
+        let key_clicked = ctx.is_focused(layout.widget_id)
+            && events.iter().any(|ev| {
+                matches!(
+                    ev,
+                    Event::KeyPressed(VirtualKeyCode::Return | VirtualKeyCode::Space)
+                )
+            });
+        if key_clicked {
+            if let Some(on_click) = self.on_click.take() {
+                ctx.dispatch_callback(on_click, ())
+            }
+            event_status.consume_event();
+            return;
+        }
+
+        if let Some(key) = self.access_key {
+            if ctx.access_key_pressed(key, events) {
+                if let Some(on_click) = self.on_click.take() {
+                    ctx.dispatch_callback(on_click, ())
+                }
+                event_status.consume_event();
+                return;
+            }
+        }
+
+        if ctx.is_pointer_over(layout.widget_id, layout.bounds, cursor_position) {
             self.hovered = true;
+            if let Some(text) = &self.status_text {
+                ctx.set_status(text.clone());
+            }
             for event in events {
                 if let Event::MousePressed(MouseButton::Primary) = event {
                     if let Some(on_click) = self.on_click.take() {
                         ctx.dispatch_callback(on_click, ())
                     }
+                    // Keeps other widgets from reacting to the pointer until
+                    // the button is released
+                    ctx.capture_pointer(layout.widget_id);
                     self.pressed = true;
                     *event_status = EventStatus::Consumed;
                 }
@@ -187,6 +410,31 @@ impl Widget for Button {
     }
 }
 
+impl InteractionStyle for ButtonStyle {
+    fn resolve(&self, state: InteractionState) -> (Color32, Stroke) {
+        match state.resolve() {
+            Interaction::Idle => (self.idle_fill, self.idle_stroke),
+            Interaction::Focused => (
+                self.idle_fill,
+                self.focused_stroke.unwrap_or(self.idle_stroke),
+            ),
+            Interaction::Hovered => (self.hovered_fill, self.hovered_stroke),
+            Interaction::Pressed => (self.pressed_fill, self.pressed_stroke),
+            Interaction::Disabled => {
+                let faded_stroke = Stroke::new(
+                    self.idle_stroke.width,
+                    self.idle_stroke.color.linear_multiply(0.5),
+                );
+                (
+                    self.disabled_fill
+                        .unwrap_or_else(|| self.idle_fill.linear_multiply(0.5)),
+                    self.disabled_stroke.unwrap_or(faded_stroke),
+                )
+            }
+        }
+    }
+}
+
 impl ButtonStyle {
     pub fn with_base_colors(
         fill: Color32,
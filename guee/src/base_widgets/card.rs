@@ -0,0 +1,182 @@
+use epaint::{RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// A rounded panel with a drop shadow, wrapping some contents with padding.
+/// Useful to group related widgets, like the entries of a dashboard.
+///
+/// Room for the shadow is reserved around the panel, so the shadow never
+/// goes past the bounds given to the card by its parent.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Card {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    #[builder(default, strip_option)]
+    pub style_override: Option<CardStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct CardStyle {
+    #[builder(default = color!("#2a2a2a"))]
+    pub background: Color32,
+    #[builder(default = Stroke::NONE)]
+    pub stroke: Stroke,
+    #[builder(default = 6.0)]
+    pub corner_radius: f32,
+    /// How far the shadow spreads around the panel. Zero disables it.
+    #[builder(default = 6.0)]
+    pub elevation: f32,
+    #[builder(default = color!("#00000060"))]
+    pub shadow_color: Color32,
+    /// The space between the panel bounds and its contents.
+    #[builder(default = Vec2::new(12.0, 12.0))]
+    pub padding: Vec2,
+}
+
+impl Default for CardStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Card {
+    fn style(&self, ctx: &Context) -> CardStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    /// Returns the bounds of the panel, inside the room left for the shadow.
+    fn panel_rect(layout: &Layout, style: &CardStyle) -> Rect {
+        layout.bounds.shrink(style.elevation.max(0.0))
+    }
+}
+
+impl Widget for Card {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let style = self.style(ctx);
+        let inset = style.padding + Vec2::splat(style.elevation.max(0.0));
+
+        let mut contents_layout =
+            self.contents
+                .widget
+                .layout(ctx, widget_id, available - inset * 2.0, force_shrink);
+        contents_layout.translate(inset);
+        let contents_size = contents_layout.bounds.size() + inset * 2.0;
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => contents_size.x,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => contents_size.y,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), vec![contents_layout])
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let style = self.style(ctx);
+        let panel = Self::panel_rect(layout, &style);
+        let rounding = Rounding::same(style.corner_radius);
+
+        let mut painter = ctx.painter();
+        if style.elevation > 0.0 {
+            painter.drop_shadow(panel, rounding, style.elevation, style.shadow_color);
+        }
+        painter.rect(RectShape {
+            rect: panel,
+            rounding,
+            fill: style.background,
+            stroke: style.stroke,
+        });
+        drop(painter);
+
+        self.contents.widget.draw(ctx, &layout.children[0]);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, events, status)
+    }
+}
+
+impl StyledWidget for Card {
+    type Style = CardStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadow_fits_in_bounds() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let style = CardStyle::new()
+            .elevation(4.0)
+            .padding(Vec2::new(10.0, 6.0));
+        let mut card = Card::new(
+            IdGen::key("card"),
+            ColoredBox::new(IdGen::key("contents"))
+                .min_size(Vec2::new(50.0, 20.0))
+                .build(),
+        )
+        .style_override(style.clone());
+
+        let mut layout = card.layout(&ctx, WidgetId::null(), Vec2::new(200.0, 100.0), false);
+        layout.to_absolute(Vec2::ZERO);
+        assert_eq!(layout.bounds.size(), Vec2::new(78.0, 40.0));
+        let panel = Card::panel_rect(&layout, &style);
+        assert_eq!(
+            panel,
+            Rect::from_min_size(Pos2::new(4.0, 4.0), Vec2::new(70.0, 32.0))
+        );
+        assert_eq!(layout.children[0].bounds.min, Pos2::new(14.0, 10.0));
+    }
+}
@@ -0,0 +1,151 @@
+use std::f32::consts::PI;
+
+use epaint::emath::{lerp, Rot2};
+use guee_derives::Builder;
+
+use crate::{painter::Painter, prelude::*};
+
+/// The direction a [`Chevron`] points to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChevronDirection {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+impl ChevronDirection {
+    /// The angle of the direction in radians, clockwise from pointing right.
+    pub fn angle(self) -> f32 {
+        match self {
+            ChevronDirection::Right => 0.0,
+            ChevronDirection::Down => 0.5 * PI,
+            ChevronDirection::Left => PI,
+            ChevronDirection::Up => 1.5 * PI,
+        }
+    }
+}
+
+/// A small triangle showing whether something is expanded, or the direction
+/// a dropdown opens to. It points to `direction` when `progress` is 0, and
+/// rotates towards `open_direction` as `progress` goes up to 1.
+///
+/// Instead of setting the `progress` directly, `open` can be set to animate
+/// the rotation when it changes.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Chevron {
+    pub id: IdGen,
+    pub direction: ChevronDirection,
+    #[builder(default = ChevronDirection::Down)]
+    pub open_direction: ChevronDirection,
+    #[builder(default)]
+    pub progress: f32,
+    /// When set, overrides `progress` with an animation towards 1 when open
+    /// and towards 0 when closed.
+    #[builder(default, strip_option)]
+    pub open: Option<bool>,
+    /// The duration of the animation, in seconds.
+    #[builder(default = 0.15)]
+    pub duration: f32,
+    #[builder(default = 10.0)]
+    pub size: f32,
+    /// Defaults to the text color of the theme.
+    #[builder(default, strip_option)]
+    pub color: Option<Color32>,
+}
+
+impl Chevron {
+    /// Paints a triangle of the given `size` centered at `center`, pointing
+    /// towards `angle`. See [`ChevronDirection::angle`].
+    pub fn paint(painter: &mut Painter, center: Pos2, size: f32, angle: f32, color: Color32) {
+        let h = size / 2.0;
+        let rot = Rot2::from_angle(angle);
+        let points = [
+            Vec2::new(-h / 2.0, -h),
+            Vec2::new(h / 2.0, 0.0),
+            Vec2::new(-h / 2.0, h),
+        ]
+        .into_iter()
+        .map(|p| center + rot * p)
+        .collect();
+        painter.convex_polygon(points, color, Stroke::NONE);
+    }
+
+    /// Returns the angle to point to at the given `progress`, rotating the
+    /// shortest way between `direction` and `open_direction`.
+    pub fn angle_at(&self, progress: f32) -> f32 {
+        let from = self.direction.angle();
+        let mut delta = self.open_direction.angle() - from;
+        if delta > PI {
+            delta -= 2.0 * PI;
+        } else if delta < -PI {
+            delta += 2.0 * PI;
+        }
+        from + lerp(0.0..=delta, progress.clamp(0.0, 1.0))
+    }
+}
+
+impl Widget for Chevron {
+    fn layout(
+        &mut self,
+        _ctx: &Context,
+        parent_id: WidgetId,
+        _available: Vec2,
+        _force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        Layout::leaf(widget_id, Vec2::splat(self.size))
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let progress = match self.open {
+            Some(open) => ctx.animate(
+                layout.widget_id.with("rotation"),
+                if open { 1.0 } else { 0.0 },
+                self.duration,
+            ),
+            None => self.progress,
+        };
+        let color = self.color.unwrap_or_else(|| ctx.theme.borrow().text_color);
+        Self::paint(
+            &mut ctx.painter(),
+            layout.bounds.center(),
+            self.size,
+            self.angle_at(progress),
+            color,
+        );
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        LayoutHints::shrink()
+    }
+
+    fn on_event(
+        &mut self,
+        _ctx: &Context,
+        _layout: &Layout,
+        _cursor_position: Pos2,
+        _events: &[Event],
+        _status: &mut EventStatus,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotates_the_shortest_way() {
+        let chevron = Chevron::new(IdGen::key("chevron"), ChevronDirection::Right)
+            .open_direction(ChevronDirection::Up);
+        assert_eq!(chevron.angle_at(0.0), 0.0);
+        assert_eq!(chevron.angle_at(1.0), -0.5 * PI);
+
+        // Going from up to right crosses the zero angle
+        let chevron = Chevron::new(IdGen::key("chevron"), ChevronDirection::Up)
+            .open_direction(ChevronDirection::Right);
+        assert!((chevron.angle_at(0.5) - 1.75 * PI).abs() < 1e-5);
+    }
+}
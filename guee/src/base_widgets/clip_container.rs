@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// Clips its contents to its own bounds, so they can't draw outside of them.
+/// In shrink mode, the container is as big as its contents but never bigger
+/// than the available space, so oversized contents are cut off.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct ClipContainer {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    /// When set, clicks and scrolling outside the bounds of the container are
+    /// not sent to the contents, and the cursor is reported as being far away
+    /// while outside, so hidden parts of the contents can't be hovered.
+    #[builder(default = true)]
+    pub clip_events: bool,
+}
+
+impl ClipContainer {
+    /// Returns the cursor position and events the contents should see.
+    fn clipped_events<'a>(
+        &self,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &'a [Event],
+    ) -> (Pos2, Cow<'a, [Event]>) {
+        if !self.clip_events || layout.bounds.contains(cursor_position) {
+            return (cursor_position, Cow::Borrowed(events));
+        }
+        let events = events
+            .iter()
+            .filter(|ev| !matches!(ev, Event::MousePressed(_) | Event::MouseWheel(_)))
+            .cloned()
+            .collect();
+        (Pos2::new(f32::INFINITY, f32::INFINITY), Cow::Owned(events))
+    }
+}
+
+impl Widget for ClipContainer {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let contents_layout = self
+            .contents
+            .widget
+            .layout(ctx, widget_id, available, force_shrink);
+        let contents_size = contents_layout.bounds.size();
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => contents_size.x.min(available.x),
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => contents_size.y.min(available.y),
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), vec![contents_layout])
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        ctx.with_clip_rect(layout.bounds, || {
+            self.contents.widget.draw(ctx, &layout.children[0])
+        });
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let (cursor_position, events) = self.clipped_events(layout, cursor_position, events);
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            &events,
+            status,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let (cursor_position, events) = self.clipped_events(layout, cursor_position, events);
+        self.contents
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, &events, status);
+    }
+}
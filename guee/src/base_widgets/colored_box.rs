@@ -1,13 +1,39 @@
 use crate::{
     context::Context,
     input::{Event, EventStatus},
-    layout::{Layout, LayoutHints, SizeHint},
+    layout::{Edges, Layout, LayoutHints, SizeHint},
+    painter::GradientSpec,
     widget::Widget,
     widget_id::{IdGen, WidgetId},
 };
-use epaint::{Color32, Pos2, RectShape, Rounding, Stroke, Vec2};
+use epaint::{Color32, Pos2, Rect, RectShape, Rounding, Stroke, Vec2};
 use guee_derives::Builder;
 
+/// How the inside of a [`ColoredBox`] is painted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Background {
+    Solid(Color32),
+    Gradient(GradientSpec),
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Color32::TRANSPARENT)
+    }
+}
+
+impl From<Color32> for Background {
+    fn from(color: Color32) -> Self {
+        Background::Solid(color)
+    }
+}
+
+impl From<GradientSpec> for Background {
+    fn from(gradient: GradientSpec) -> Self {
+        Background::Gradient(gradient)
+    }
+}
+
 #[derive(Builder)]
 #[builder(widget)]
 pub struct ColoredBox {
@@ -18,10 +44,17 @@ pub struct ColoredBox {
     pub min_size: Vec2,
     #[builder(default)]
     pub rounding: Rounding,
-    #[builder(default)]
-    pub fill: Color32,
+    /// Set with [`ColoredBox::fill`], which takes either a color or a
+    /// gradient.
+    #[builder(skip)]
+    pub fill: Background,
+    /// A stroke around the whole box, following the rounding.
     #[builder(default)]
     pub stroke: Stroke,
+    /// Independent strokes for each side, drawn inside the box on top of the
+    /// `stroke`. The corners of these borders are never rounded.
+    #[builder(default)]
+    pub borders: Edges<Stroke>,
 }
 
 impl ColoredBox {
@@ -30,6 +63,35 @@ impl ColoredBox {
             .hints(LayoutHints::fill())
             .fill(color)
     }
+
+    pub fn fill(mut self, fill: impl Into<Background>) -> Self {
+        self.fill = fill.into();
+        self
+    }
+
+    fn draw_borders(&self, ctx: &Context, rect: Rect) {
+        let Edges {
+            left,
+            right,
+            top,
+            bottom,
+        } = self.borders;
+        // Borders are drawn inside the box, so they don't overlap neighbours
+        let sides = [
+            (left, [rect.left_top(), rect.left_bottom()], Vec2::X),
+            (right, [rect.right_top(), rect.right_bottom()], -Vec2::X),
+            (top, [rect.left_top(), rect.right_top()], Vec2::Y),
+            (bottom, [rect.left_bottom(), rect.right_bottom()], -Vec2::Y),
+        ];
+        let mut painter = ctx.painter();
+        for (stroke, [a, b], inwards) in sides {
+            if stroke.width <= 0.0 || stroke.color == Color32::TRANSPARENT {
+                continue;
+            }
+            let offset = inwards * stroke.width / 2.0;
+            painter.line_segment([a + offset, b + offset], stroke);
+        }
+    }
 }
 
 impl Widget for ColoredBox {
@@ -54,13 +116,26 @@ impl Widget for ColoredBox {
         Layout::leaf(widget_id, Vec2::new(width, height))
     }
 
+    fn measure(&mut self, _ctx: &Context, _parent_id: WidgetId, _available: Vec2) -> Vec2 {
+        self.min_size
+    }
+
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let fill = match self.fill {
+            Background::Solid(color) => color,
+            Background::Gradient(gradient) => {
+                ctx.painter()
+                    .gradient_rect(layout.bounds, self.rounding, gradient);
+                Color32::TRANSPARENT
+            }
+        };
         ctx.painter().rect(RectShape {
             rect: layout.bounds,
             rounding: self.rounding,
-            fill: self.fill,
+            fill,
             stroke: self.stroke,
         });
+        self.draw_borders(ctx, layout.bounds);
     }
 
     fn layout_hints(&self) -> LayoutHints {
@@ -0,0 +1,199 @@
+use epaint::CubicBezierShape;
+use guee_derives::Builder;
+
+use crate::{input::MouseButton, prelude::*};
+
+/// The longest the tangents of a wire can get, so wires between distant
+/// points don't bulge out too much.
+const MAX_TANGENT_LENGTH: f32 = 150.0;
+
+/// The number of line segments used to approximate a wire when hit testing.
+const HIT_TEST_SEGMENTS: usize = 32;
+
+/// A wire between two points, like the links between the ports of a node
+/// editor. The wire leaves `from` towards the right and enters `to` from the
+/// left.
+///
+/// The points are given in layout coordinates, independently of where the
+/// widget is laid out, so connections are typically placed in a
+/// [`StackContainer`] on top of the nodes they connect. Only clicks close to
+/// the wire are consumed, so the rest of the events pass through to the
+/// widgets below.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Connection {
+    pub id: IdGen,
+    pub from: Pos2,
+    pub to: Pos2,
+    #[builder(skip)]
+    pub hovered: bool,
+    #[builder(default)]
+    pub selected: bool,
+    /// How close to the wire the cursor needs to be for it to be hovered.
+    #[builder(default = 6.0)]
+    pub hit_distance: f32,
+    #[builder(default = LayoutHints::fill())]
+    pub hints: LayoutHints,
+    #[builder(strip_option)]
+    pub on_click: Option<Callback<()>>,
+    #[builder(default, strip_option)]
+    pub style_override: Option<ConnectionStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct ConnectionStyle {
+    #[builder(default = Stroke::new(2.0, color!("#8a8a8a")))]
+    pub stroke: Stroke,
+    #[builder(default = Stroke::new(3.0, color!("#b0b0b0")))]
+    pub hovered_stroke: Stroke,
+    #[builder(default = Stroke::new(3.0, color!("#4a7fb5")))]
+    pub selected_stroke: Stroke,
+}
+
+impl Default for ConnectionStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the control points of the wire between `from` and `to`. The
+/// tangents are horizontal, and grow with the distance between the points.
+pub fn wire_points(from: Pos2, to: Pos2) -> [Pos2; 4] {
+    let tangent = (from.distance(to) * 0.5).min(MAX_TANGENT_LENGTH);
+    [
+        from,
+        from + Vec2::new(tangent, 0.0),
+        to - Vec2::new(tangent, 0.0),
+        to,
+    ]
+}
+
+fn sample_bezier(points: &[Pos2; 4], t: f32) -> Pos2 {
+    let s = 1.0 - t;
+    let [p0, p1, p2, p3] = points.map(|p| p.to_vec2());
+    (p0 * s * s * s + p1 * 3.0 * s * s * t + p2 * 3.0 * s * t * t + p3 * t * t * t).to_pos2()
+}
+
+fn distance_to_segment(pos: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_sq();
+    if len_sq == 0.0 {
+        return pos.distance(a);
+    }
+    let t = ((pos - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    pos.distance(a + ab * t)
+}
+
+/// Returns the distance from `pos` to the cubic bezier curve with the given
+/// control `points`. The curve is approximated by line segments, so the
+/// result is not exact, but good enough for hit testing.
+pub fn distance_to_bezier(points: &[Pos2; 4], pos: Pos2) -> f32 {
+    let mut distance = f32::INFINITY;
+    let mut prev = points[0];
+    for i in 1..=HIT_TEST_SEGMENTS {
+        let next = sample_bezier(points, i as f32 / HIT_TEST_SEGMENTS as f32);
+        distance = distance.min(distance_to_segment(pos, prev, next));
+        prev = next;
+    }
+    distance
+}
+
+impl Connection {
+    /// Returns whether `pos` is close enough to the wire to interact with it.
+    pub fn hit_test(&self, pos: Pos2) -> bool {
+        let points = wire_points(self.from, self.to);
+        // Cheap rejection before measuring the distance to the curve
+        let bounds = Rect::from_points(&points).expand(self.hit_distance);
+        bounds.contains(pos) && distance_to_bezier(&points, pos) <= self.hit_distance
+    }
+}
+
+impl Widget for Connection {
+    fn layout(
+        &mut self,
+        _ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => 0.0,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => 0.0,
+            SizeHint::Fill => available.y,
+        };
+        Layout::leaf(widget_id, Vec2::new(width, height))
+    }
+
+    fn draw(&mut self, ctx: &Context, _layout: &Layout) {
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+        let stroke = if self.selected {
+            style.selected_stroke
+        } else if self.hovered {
+            style.hovered_stroke
+        } else {
+            style.stroke
+        };
+        ctx.painter().cubic_bezier(CubicBezierShape {
+            points: wire_points(self.from, self.to),
+            closed: false,
+            fill: Color32::TRANSPARENT,
+            stroke,
+        });
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        _layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if status.is_consumed() || !self.hit_test(cursor_position) {
+            return;
+        }
+        self.hovered = true;
+        for event in events {
+            if let Event::MousePressed(MouseButton::Primary) = event {
+                if let Some(on_click) = self.on_click.take() {
+                    ctx.dispatch_callback(on_click, ());
+                }
+                status.consume_event();
+            }
+        }
+    }
+}
+
+impl StyledWidget for Connection {
+    type Style = ConnectionStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_to_bezier() {
+        let points = wire_points(Pos2::new(0.0, 0.0), Pos2::new(100.0, 100.0));
+        assert_eq!(sample_bezier(&points, 0.5), Pos2::new(50.0, 50.0));
+        assert!(distance_to_bezier(&points, Pos2::new(50.0, 50.0)) < 0.01);
+        assert!(distance_to_bezier(&points, Pos2::new(0.0, 5.0)) <= 5.0);
+        assert!(distance_to_bezier(&points, Pos2::new(100.0, 0.0)) > 30.0);
+    }
+}
@@ -0,0 +1,472 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use guee_derives::Builder;
+use winit::event::VirtualKeyCode;
+
+use crate::{callback::PollToken, popup::Side, prelude::*};
+
+/// The calendar always shows six weeks, so its size doesn't change between
+/// months.
+const GRID_DAYS: usize = 42;
+
+/// A field showing a formatted date, which opens a calendar [`Popover`] to
+/// pick a different one when clicked.
+///
+/// While the calendar is open, the arrow keys move the highlighted day and
+/// Enter picks it.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct DatePicker {
+    pub id: IdGen,
+    pub value: NaiveDate,
+    /// Fired with the new date when the user picks a day.
+    #[builder(strip_option)]
+    pub on_changed: Option<Callback<NaiveDate>>,
+    /// The weekday in the first column of the calendar.
+    #[builder(default = Weekday::Mon)]
+    pub first_day_of_week: Weekday,
+    /// A `chrono` format string, used to display the date in the field.
+    #[builder(default = "%Y-%m-%d".to_string())]
+    pub format: String,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    #[builder(default, strip_option)]
+    pub style_override: Option<DatePickerStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// Created during layout.
+    #[builder(skip)]
+    pub popover: Option<DynWidget>,
+    /// The tokens for the buttons in the calendar. Created during layout.
+    #[builder(skip)]
+    pub tokens: Option<CalendarTokens>,
+}
+
+#[derive(Builder, Clone)]
+pub struct DatePickerStyle {
+    #[builder(default = color!("#1d1d1d"))]
+    pub field_fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub field_stroke: Stroke,
+    #[builder(default = color!("#3a3a3a"))]
+    pub hovered_fill: Color32,
+    #[builder(default = color!("#4a7fb5"))]
+    pub selected_fill: Color32,
+    /// The stroke around today's date.
+    #[builder(default = Stroke::new(1.0, color!("#4a7fb5")))]
+    pub today_stroke: Stroke,
+    /// The stroke around the day highlighted with the keyboard.
+    #[builder(default = Stroke::new(1.0, color!("#c0c0c0")))]
+    pub focused_stroke: Stroke,
+    /// The text color for the days of the previous and next months.
+    #[builder(default = color!("#707070"))]
+    pub outside_month_color: Color32,
+    #[builder(default = color!("#909090"))]
+    pub weekday_color: Color32,
+    #[builder(default = Vec2::new(28.0, 24.0))]
+    pub day_size: Vec2,
+}
+
+impl Default for DatePickerStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct DatePickerState {
+    /// The year and month shown in the calendar. Reset to the month of the
+    /// current value when the calendar is closed.
+    pub shown_month: Option<(i32, u32)>,
+    /// The day highlighted with the keyboard.
+    pub focused_day: Option<NaiveDate>,
+}
+
+pub struct CalendarTokens {
+    pub prev_month: PollToken<()>,
+    pub next_month: PollToken<()>,
+    pub days: Vec<(NaiveDate, PollToken<()>)>,
+}
+
+/// Returns the year and month that are `delta` months away from the given
+/// ones.
+pub fn add_months(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 + delta;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+/// Returns the days shown in the calendar for the given month, in row-major
+/// order. The grid starts on the `first_day_of_week` on or before the first
+/// of the month, and is padded with days of the previous and next months.
+pub fn month_grid(year: i32, month: u32, first_day_of_week: Weekday) -> Vec<NaiveDate> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).expect("Invalid month");
+    let lead =
+        (first.weekday().num_days_from_monday() + 7 - first_day_of_week.num_days_from_monday()) % 7;
+    let start = first - Duration::days(lead as i64);
+    start.iter_days().take(GRID_DAYS).collect()
+}
+
+impl DatePicker {
+    fn style(&self, ctx: &Context) -> DatePickerStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    fn shown_month(&self, ctx: &Context, widget_id: WidgetId) -> (i32, u32) {
+        ctx.state::<DatePickerState>(widget_id)
+            .get_or_default()
+            .shown_month
+            .unwrap_or((self.value.year(), self.value.month()))
+    }
+
+    /// Returns the button style for the given day of the calendar.
+    fn day_style(
+        style: &DatePickerStyle,
+        selected: bool,
+        today: bool,
+        focused: bool,
+    ) -> ButtonStyle {
+        let stroke = if focused {
+            style.focused_stroke
+        } else if today {
+            style.today_stroke
+        } else {
+            Stroke::NONE
+        };
+        let (idle_fill, hovered_fill) = if selected {
+            (style.selected_fill, style.selected_fill)
+        } else {
+            (Color32::TRANSPARENT, style.hovered_fill)
+        };
+        ButtonStyle::new(
+            style.selected_fill,
+            stroke,
+            hovered_fill,
+            stroke,
+            idle_fill,
+            stroke,
+        )
+    }
+
+    /// Builds the month header and the grid of days.
+    fn build_calendar(
+        &mut self,
+        ctx: &Context,
+        style: &DatePickerStyle,
+        (year, month): (i32, u32),
+        focused_day: Option<NaiveDate>,
+    ) -> DynWidget {
+        let nav_style = ButtonStyle::new(
+            style.selected_fill,
+            Stroke::NONE,
+            style.hovered_fill,
+            Stroke::NONE,
+            Color32::TRANSPARENT,
+            Stroke::NONE,
+        );
+        let (prev_cb, prev_month) = ctx.create_internal_callback();
+        let (next_cb, next_month) = ctx.create_internal_callback();
+        let title = NaiveDate::from_ymd_opt(year, month, 1)
+            .expect("Invalid month")
+            .format("%B %Y")
+            .to_string();
+        let header = BoxContainer::horizontal(
+            IdGen::key("header"),
+            vec![
+                Button::new(IdGen::key("prev"), Text::new("<".into()).build())
+                    .padding(Vec2::new(6.0, 4.0))
+                    .style_override(nav_style.clone())
+                    .on_click(prev_cb)
+                    .build(),
+                BoxContainer::horizontal(IdGen::key("title"), vec![Text::new(title).build()])
+                    .cross_align(Align::Center)
+                    .main_align(Align::Center)
+                    .layout_hints(LayoutHints::fill_horizontal())
+                    .build(),
+                Button::new(IdGen::key("next"), Text::new(">".into()).build())
+                    .padding(Vec2::new(6.0, 4.0))
+                    .style_override(nav_style)
+                    .on_click(next_cb)
+                    .build(),
+            ],
+        )
+        .cross_align(Align::Center)
+        .layout_hints(LayoutHints::fill_horizontal())
+        .build();
+
+        let mut cells = Vec::with_capacity(GRID_DAYS + 7);
+        let first_day = self.first_day_of_week;
+        for i in 0..7 {
+            let weekday = (0..i).fold(first_day, |day, _| day.succ());
+            let label: String = weekday.to_string().chars().take(2).collect();
+            cells.push(
+                SizedContainer::new(
+                    BoxContainer::horizontal(
+                        IdGen::key(("weekday", i)),
+                        vec![Text::new(label).color_override(style.weekday_color).build()],
+                    )
+                    .main_align(Align::Center)
+                    .cross_align(Align::Center)
+                    .layout_hints(LayoutHints::fill())
+                    .build(),
+                    style.day_size,
+                )
+                .build(),
+            );
+        }
+
+        let today = Local::now().date_naive();
+        let mut days = Vec::with_capacity(GRID_DAYS);
+        for (i, day) in month_grid(year, month, first_day).into_iter().enumerate() {
+            let (cb, tk) = ctx.create_internal_callback();
+            let label = Text::new(day.day().to_string());
+            let label = if day.month() == month {
+                label
+            } else {
+                label.color_override(style.outside_month_color)
+            };
+            let day_style = Self::day_style(
+                style,
+                day == self.value,
+                day == today,
+                Some(day) == focused_day,
+            );
+            cells.push(
+                Button::new(IdGen::key(("day", i)), label.build())
+                    .padding(Vec2::ZERO)
+                    .min_size(style.day_size)
+                    .style_override(day_style)
+                    .skip_tab(true)
+                    .on_click(cb)
+                    .build(),
+            );
+            days.push((day, tk));
+        }
+        self.tokens = Some(CalendarTokens {
+            prev_month,
+            next_month,
+            days,
+        });
+
+        BoxContainer::vertical(
+            IdGen::key("calendar"),
+            vec![
+                header,
+                GridContainer::new(IdGen::key("days"), 7, cells)
+                    .spacing(Vec2::new(2.0, 2.0))
+                    .build(),
+            ],
+        )
+        .separation(4.0)
+        .build()
+    }
+
+    /// Moves the keyboard highlight, returning the day to pick if Enter was
+    /// pressed.
+    fn on_key_events(
+        &self,
+        ctx: &Context,
+        widget_id: WidgetId,
+        events: &[Event],
+    ) -> Option<NaiveDate> {
+        let mut state = ctx.state::<DatePickerState>(widget_id).get_mut_or_default();
+        let mut picked = None;
+        for event in events {
+            let Event::KeyPressed(key) = event else {
+                continue;
+            };
+            let focused = state.focused_day.unwrap_or(self.value);
+            let moved = match key {
+                VirtualKeyCode::Left => focused - Duration::days(1),
+                VirtualKeyCode::Right => focused + Duration::days(1),
+                VirtualKeyCode::Up => focused - Duration::days(7),
+                VirtualKeyCode::Down => focused + Duration::days(7),
+                VirtualKeyCode::Return => {
+                    picked = state.focused_day;
+                    continue;
+                }
+                _ => continue,
+            };
+            state.focused_day = Some(moved);
+            state.shown_month = Some((moved.year(), moved.month()));
+        }
+        picked
+    }
+
+    fn pick(&mut self, ctx: &Context, widget_id: WidgetId, day: NaiveDate) {
+        Popover::set_open(ctx, widget_id, false, false);
+        if day != self.value {
+            if let Some(on_changed) = self.on_changed.take() {
+                ctx.dispatch_callback(on_changed, day);
+            }
+        }
+    }
+}
+
+impl Widget for DatePicker {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let style = self.style(ctx);
+
+        let field = Button::new(
+            IdGen::key("field"),
+            Text::new(self.value.format(&self.format).to_string()).build(),
+        )
+        .padding(Vec2::new(6.0, 3.0))
+        .align_contents(epaint::emath::Align2::LEFT_CENTER)
+        .hints(self.hints)
+        .style_override(ButtonStyle::new(
+            style.field_fill,
+            style.field_stroke,
+            style.field_fill,
+            style.field_stroke,
+            style.field_fill,
+            style.field_stroke,
+        ))
+        .build();
+
+        let contents = if Popover::is_open(ctx, widget_id) {
+            let shown_month = self.shown_month(ctx, widget_id);
+            let focused_day = ctx
+                .state::<DatePickerState>(widget_id)
+                .get_or_default()
+                .focused_day;
+            self.build_calendar(ctx, &style, shown_month, focused_day)
+        } else {
+            self.tokens = None;
+            Spacer::v(0.0).build()
+        };
+
+        let mut popover = Popover::new(self.id, field, contents)
+            .side(Side::Below)
+            .build();
+        let layout = popover
+            .widget
+            .layout(ctx, parent_id, available, force_shrink);
+        self.popover = Some(popover);
+        layout
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        if let Some(popover) = &mut self.popover {
+            popover.widget.draw(ctx, layout);
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if let Some(popover) = &mut self.popover {
+            popover
+                .widget
+                .on_event_capture(ctx, layout, cursor_position, events, status);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let widget_id = layout.widget_id;
+        let was_open = Popover::is_open(ctx, widget_id);
+        if let Some(popover) = &mut self.popover {
+            popover
+                .widget
+                .on_event(ctx, layout, cursor_position, events, status);
+        }
+
+        let mut picked = None;
+        if let Some(tokens) = self.tokens.take() {
+            let delta = if ctx.poll_callback_result(tokens.prev_month).is_some() {
+                -1
+            } else if ctx.poll_callback_result(tokens.next_month).is_some() {
+                1
+            } else {
+                0
+            };
+            if delta != 0 {
+                let (year, month) = self.shown_month(ctx, widget_id);
+                let mut state = ctx.state::<DatePickerState>(widget_id).get_mut_or_default();
+                state.shown_month = Some(add_months(year, month, delta));
+                state.focused_day = None;
+            }
+            picked = tokens
+                .days
+                .into_iter()
+                .find_map(|(day, tk)| ctx.poll_callback_result(tk).map(|_| day));
+        }
+
+        if was_open && Popover::is_open(ctx, widget_id) && picked.is_none() {
+            picked = self.on_key_events(ctx, widget_id, events);
+        }
+        if let Some(day) = picked {
+            self.pick(ctx, widget_id, day);
+        }
+
+        if !Popover::is_open(ctx, widget_id) {
+            let mut state = ctx.state::<DatePickerState>(widget_id).get_mut_or_default();
+            state.shown_month = None;
+            state.focused_day = None;
+        }
+    }
+}
+
+impl StyledWidget for DatePicker {
+    type Style = DatePickerStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_month_grid() {
+        // February 2024 starts on a Thursday
+        let grid = month_grid(2024, 2, Weekday::Mon);
+        assert_eq!(grid.len(), GRID_DAYS);
+        assert_eq!(grid[0], date(2024, 1, 29));
+        assert_eq!(grid[3], date(2024, 2, 1));
+
+        let grid = month_grid(2024, 2, Weekday::Sun);
+        assert_eq!(grid[0], date(2024, 1, 28));
+        assert_eq!(grid[4], date(2024, 2, 1));
+
+        // No leading days when the month starts on the first day of the week
+        let grid = month_grid(2024, 9, Weekday::Sun);
+        assert_eq!(grid[0], date(2024, 9, 1));
+    }
+
+    #[test]
+    fn test_add_months() {
+        assert_eq!(add_months(2024, 1, -1), (2023, 12));
+        assert_eq!(add_months(2024, 12, 1), (2025, 1));
+        assert_eq!(add_months(2024, 5, 14), (2025, 7));
+    }
+}
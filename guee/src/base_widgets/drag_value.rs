@@ -16,6 +16,21 @@ pub struct DragValue {
     #[builder(default = 0.1)]
     pub speed: f64,
 
+    /// How far the mouse has to move for each discrete increment. The x
+    /// component is used when dragging horizontally to change the value, and
+    /// the y component when dragging vertically to pick a speed in the scale
+    /// selector. Lower values make scrubbing faster. Measured in points, so
+    /// it's unaffected by the display scale.
+    #[builder(default = DEFAULT_MOUSE_PRECISION)]
+    pub mouse_precision: Vec2,
+
+    /// When set, the cursor is hidden while dragging and warped back to where
+    /// the drag started every frame, so the value can be dragged indefinitely
+    /// without the cursor reaching the edge of the screen. Requires the
+    /// integration to call [`Context::apply_cursor_requests`].
+    #[builder(default)]
+    pub relative_drag: bool,
+
     /// When set, shows a scale selector allowing the user to adjust the base
     /// speed by a multiplier.
     #[builder(default)]
@@ -28,12 +43,13 @@ pub struct DragValue {
 
     /// A recommended range of values for this slider. The values returned can
     /// go beyond the limits when using the text edit feature, or when dragging
-    /// again after the slider reached the soft max/min value
+    /// again after the slider reached the soft max/min value.
     #[builder(default = -f64::INFINITY..=f64::INFINITY)]
     pub soft_range: RangeInclusive<f64>,
 
     /// The range of movement for this slider. The values returned can never go
-    /// above or beyond those limits.
+    /// above or beyond those limits. Typed values outside this range are
+    /// clamped, and the text is corrected when the edit is committed.
     #[builder(default = -f64::INFINITY..=f64::INFINITY)]
     pub hard_range: RangeInclusive<f64>,
 
@@ -46,12 +62,53 @@ pub struct DragValue {
     #[builder(strip_option)]
     pub on_changed: Option<Callback<f64>>,
 
+    /// The style of the scale selector. The text field itself is styled as a
+    /// [`TextEdit`].
+    #[builder(default, strip_option)]
+    pub style_override: Option<DragValueStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+
     /// Inner TextEdit, used to implement some functionalities for this widget
     /// avoiding code repetition.
     #[builder(skip, default = TextEdit::new(IdGen::key(""), "".to_string()))]
     pub text_edit: TextEdit,
 }
 
+/// The default value for `DragValue::mouse_precision`.
+const DEFAULT_MOUSE_PRECISION: Vec2 = Vec2::new(20.0, 50.0);
+
+#[derive(Builder, Clone)]
+pub struct DragValueStyle {
+    #[builder(default = color!("#373737B0"))]
+    pub selected_fill: Color32,
+    #[builder(default = color!("#212121B0"))]
+    pub idle_fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#3c3c3c")))]
+    pub stroke: Stroke,
+    /// The size of each row of the scale selector.
+    #[builder(default = Vec2::new(60.0, 27.0))]
+    pub row_size: Vec2,
+    /// Horizontal space between the widget and the scale selector, and
+    /// vertical padding above the row labels.
+    #[builder(default = Vec2::new(4.0, 1.0))]
+    pub padding: Vec2,
+    #[builder(default = FontId::proportional(14.0))]
+    pub font: FontId,
+    /// The font of the hint below the scale selector.
+    #[builder(default = FontId::proportional(10.0))]
+    pub hint_font: FontId,
+}
+
+impl Default for DragValueStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ScaleSelector {
     /// True for left, false for right
@@ -133,6 +190,10 @@ pub struct DragValueState {
     /// True when the current drag event started at the bottom soft limit. This
     /// allows the slider to go past the soft min.
     pub lower_soft_limit: bool,
+
+    /// Where the cursor was when the current drag started, when using
+    /// relative drag. The cursor is warped back here every frame.
+    pub drag_origin: Option<Pos2>,
 }
 
 impl DragValue {
@@ -171,6 +232,34 @@ impl DragValue {
         self
     }
 
+    /// Parses a typed value, clamping it to the `hard_range`. Unlike dragging,
+    /// typing ignores the `soft_range`.
+    pub fn parse_typed_value(&self, s: &str) -> Option<f64> {
+        Self::contents_from_string(s).map(|val| {
+            let val = val.clamp(*self.hard_range.start(), *self.hard_range.end());
+            self.round_value(val)
+        })
+    }
+
+    // TODO: Make #[derive(Builder)] capable of forwarding builder functions to
+    // some of the fields
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.text_edit = self.text_edit.tab_index(tab_index);
+        self
+    }
+
+    // TODO: Make #[derive(Builder)] capable of forwarding builder functions to
+    // some of the fields
+    pub fn skip_tab(mut self, skip_tab: bool) -> Self {
+        self.text_edit = self.text_edit.skip_tab(skip_tab);
+        self
+    }
+
+    fn round_value(&self, val: f64) -> f64 {
+        let pow = 10.0f64.powi(self.num_decimals as i32);
+        (val * pow).round() / pow
+    }
+
     fn clamp_and_round_value(&self, state: &DragValueState, val: f64) -> f64 {
         let lower_bound = if state.lower_soft_limit {
             *self.hard_range.start()
@@ -187,8 +276,7 @@ impl DragValue {
         let val = val.clamp(lower_bound, upper_bound);
 
         // Round to decimal places
-        let pow = 10.0f64.powi(self.num_decimals as i32);
-        (val * pow).round() / pow
+        self.round_value(val)
     }
 }
 
@@ -214,6 +302,7 @@ impl Widget for DragValue {
                 draw_scale_selector: false,
                 upper_soft_limit: false,
                 lower_soft_limit: false,
+                drag_origin: None,
             },
         );
 
@@ -238,7 +327,10 @@ impl Widget for DragValue {
 
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
         self.text_edit.draw(ctx, layout);
-        let state = ctx.memory.get::<DragValueState>(layout.widget_id);
+        // The state is created during layout, but don't rely on it
+        let Some(state) = ctx.memory.try_get::<DragValueState>(layout.widget_id) else {
+            return;
+        };
 
         if state.draw_scale_selector {
             let scale_selector = self
@@ -247,8 +339,12 @@ impl Widget for DragValue {
                 .expect("The draw_scale_selector property was set but no scale selector exists");
             let selected_row = state.selected_row.expect("Should be initialized");
 
-            let padding = Vec2::new(4.0, 1.0);
-            let size = Vec2::new(60.0, 27.0);
+            let style = ctx
+                .theme
+                .borrow()
+                .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+            let padding = style.padding;
+            let size = style.row_size;
 
             let top_left = if scale_selector.show_left_of_widget {
                 layout.bounds.left_center()
@@ -267,21 +363,19 @@ impl Widget for DragValue {
                     painter.rect(RectShape {
                         rect: Rect::from_min_size(pos, size),
                         rounding: Rounding::none(),
-                        // TODO: THEME
                         fill: if selected_row == i {
-                            color!("#373737B0")
+                            style.selected_fill
                         } else {
-                            color!("#212121B0")
+                            style.idle_fill
                         },
-                        stroke: Stroke::new(1.0, color!("#3c3c3c")),
+                        stroke: style.stroke,
                     });
 
                     painter.text(
                         pos + Vec2::new(size.x * 0.5, padding.y),
                         Align2::CENTER_TOP,
                         label,
-                        // TODO: THEME
-                        FontId::proportional(14.0),
+                        style.font.clone(),
                     );
                 }
 
@@ -295,7 +389,7 @@ impl Widget for DragValue {
                     bottom_left,
                     Align2::LEFT_TOP,
                     CTRL_KEY_LABEL,
-                    FontId::proportional(10.0),
+                    style.hint_font.clone(),
                 );
             })
         }
@@ -340,6 +434,8 @@ impl Widget for DragValue {
         // transform the value
         let (cb, tk) = ctx.create_internal_callback();
         self.text_edit.on_changed = Some(cb);
+        let (submit_cb, submit_tk) = ctx.create_internal_callback::<String>();
+        self.text_edit.on_submit = Some(submit_cb);
 
         // If the child is not focused, ignore its event processing logic
         // We instead do our own focus handling
@@ -355,6 +451,7 @@ impl Widget for DragValue {
 
         // Check if the component was just focused or dragged during this frame
         let just_focused = state.last_focus_state != focused_now && focused_now;
+        let just_unfocused = state.last_focus_state != focused_now && !focused_now;
         state.last_focus_state = focused_now;
         let just_dragged = dragging != state.last_drag_state && dragging;
         state.last_drag_state = dragging;
@@ -369,6 +466,18 @@ impl Widget for DragValue {
 
         state.draw_scale_selector = !focused_now && dragging && self.scale_selector.is_some();
 
+        if self.relative_drag && !focused_now && dragging {
+            let origin = *state
+                .drag_origin
+                .get_or_insert(ctx.input_state.mouse.position);
+            if just_dragged {
+                ctx.set_cursor_hidden(true);
+            }
+            ctx.warp_cursor(origin);
+        } else if state.drag_origin.take().is_some() {
+            ctx.set_cursor_hidden(false);
+        }
+
         // When the TextEdit is focused, it should behave like a regular
         // TextEdit, letting the user write anything in the text box
         if focused_now {
@@ -378,17 +487,24 @@ impl Widget for DragValue {
                 status.consume_event();
 
                 // Additionally, if the contents can be parsed as float, emit
-                // our on_changed event
-                if let Some(new_value) = Self::contents_from_string(&result) {
-                    if let Some(on_changed) = self.on_changed.take() {
-                        ctx.dispatch_callback(
-                            on_changed,
-                            self.clamp_and_round_value(&state, new_value),
-                        );
-                    }
+                // our on_changed event. Out of range values are clamped, but
+                // the text is left as typed until the edit is committed.
+                if let Some(new_value) = self.parse_typed_value(&result) {
+                    ctx.dispatch_callback_ref(&mut self.on_changed, new_value);
                 }
             }
-        } else if dragging {
+        }
+
+        // Committing the edit, either by submitting or by losing focus, shows
+        // the value that was actually emitted.
+        if ctx.poll_callback_result(submit_tk).is_some() || just_unfocused {
+            if let Some(new_value) = self.parse_typed_value(&state.string_contents) {
+                state.string_contents =
+                    Self::format_contents(new_value, self.num_decimals as usize);
+            }
+        }
+
+        if !focused_now && dragging {
             // Scale selector
             if let Some(scale_selector) = &self.scale_selector {
                 // Check if a drag event started exactly this frame, and initialize
@@ -416,8 +532,9 @@ impl Widget for DragValue {
                     .map(|s| s.clamp(0, scale_selector.len() - 1));
             }
 
-            // Handle mouse movement
-            const MOUSE_PRECISION: Vec2 = Vec2::new(20.0, 50.0);
+            // Handle mouse movement. A zero precision would never stop
+            // incrementing, so keep it positive.
+            let mouse_precision = self.mouse_precision.max(Vec2::splat(1.0));
 
             let modify_scale: bool = ctx.input_state.modifiers.ctrl_or_command;
 
@@ -428,8 +545,8 @@ impl Widget for DragValue {
                 state.acc_drag += ctx.input_state.mouse.delta().x * Vec2::X;
             }
 
-            let discrete_increments = (state.acc_drag / MOUSE_PRECISION).floor();
-            state.acc_drag = state.acc_drag.rem_euclid(MOUSE_PRECISION);
+            let discrete_increments = (state.acc_drag / mouse_precision).floor();
+            state.acc_drag = state.acc_drag.rem_euclid(mouse_precision);
 
             let speed = match &self.scale_selector {
                 Some(scale_selector) => {
@@ -445,10 +562,33 @@ impl Widget for DragValue {
             let delta_value = discrete_increments.x as f64 * speed;
             let new_value = self.clamp_and_round_value(&state, self.value + delta_value);
 
-            if let Some(on_changed) = self.on_changed.take() {
-                ctx.dispatch_callback(on_changed, new_value);
+            if self.on_changed.is_some() {
+                ctx.dispatch_callback_ref(&mut self.on_changed, new_value);
                 status.consume_event();
             }
         }
     }
 }
+
+impl StyledWidget for DragValue {
+    type Style = DragValueStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_values_are_clamped_to_hard_range() {
+        let drag_value = DragValue::new(IdGen::key("value"), 5.0)
+            .soft_range(0.0..=10.0)
+            .hard_range(-100.0..=100.0)
+            .num_decimals(1);
+
+        assert_eq!(drag_value.parse_typed_value("150"), Some(100.0));
+        assert_eq!(drag_value.parse_typed_value("-1e9"), Some(-100.0));
+        // Typing can go past the soft range
+        assert_eq!(drag_value.parse_typed_value("42.26"), Some(42.3));
+        assert_eq!(drag_value.parse_typed_value("abc"), None);
+    }
+}
@@ -0,0 +1,311 @@
+use epaint::FontId;
+use guee_derives::Builder;
+
+use crate::{
+    painter::{GueeGalley, GueeTextShape},
+    prelude::*,
+};
+
+/// A list of labeled fields, like the ones in a settings dialog. Labels are
+/// right-aligned in a column as wide as the widest label, so all the fields
+/// line up to the right of it.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Form {
+    pub id: IdGen,
+    pub rows: Vec<FormRow>,
+    #[builder(default = LayoutHints::fill_horizontal())]
+    pub hints: LayoutHints,
+    #[builder(default, strip_option)]
+    pub style_override: Option<FormStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// The labels, required markers and notes, relative to the form.
+    /// Computed during layout.
+    #[builder(skip)]
+    pub texts: Vec<PlacedText>,
+}
+
+/// A single row of a [`Form`].
+#[derive(Builder)]
+pub struct FormRow {
+    pub label: String,
+    pub field: DynWidget,
+    /// Shows a marker next to the label.
+    #[builder(default)]
+    pub required: bool,
+    /// A note shown below the field.
+    #[builder(skip)]
+    pub help: Option<String>,
+    /// An error shown below the field, using the theme's error color. Takes
+    /// the place of the `help` text when set.
+    #[builder(skip)]
+    pub error: Option<String>,
+}
+
+impl FormRow {
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+}
+
+#[derive(Builder, Clone)]
+pub struct FormStyle {
+    #[builder(default = 14.0)]
+    pub font_size: f32,
+    #[builder(default = 12.0)]
+    pub note_font_size: f32,
+    #[builder(default = color!("#909090"))]
+    pub help_color: Color32,
+    #[builder(default = color!("#d53b3b"))]
+    pub required_color: Color32,
+    /// Horizontal space between the labels and the fields, and vertical space
+    /// between rows.
+    #[builder(default = Vec2::new(8.0, 6.0))]
+    pub spacing: Vec2,
+    /// Vertical space between a field and the note below it.
+    #[builder(default = 2.0)]
+    pub note_spacing: f32,
+}
+
+impl Default for FormStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A piece of text drawn by a [`Form`] at a fixed position.
+pub struct PlacedText {
+    pub galley: GueeGalley,
+    pub pos: Pos2,
+    /// Uses the current text color when not set.
+    pub color: Option<Color32>,
+}
+
+/// Space between a label and its required marker.
+const MARKER_SPACING: f32 = 2.0;
+
+impl Form {
+    fn style(&self, ctx: &Context) -> FormStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+}
+
+impl Widget for Form {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let style = self.style(ctx);
+        let error_color = ctx.theme.borrow().error_color;
+        let font_id = FontId::proportional(style.font_size);
+        let note_font_id = FontId::proportional(style.note_font_size);
+        self.texts.clear();
+
+        let marker = ctx
+            .painter()
+            .galley("*".to_string(), font_id.clone(), f32::INFINITY);
+        let marker_width = marker.bounds().width() + MARKER_SPACING;
+        let labels: Vec<GueeGalley> = self
+            .rows
+            .iter()
+            .map(|row| {
+                ctx.painter()
+                    .galley(row.label.clone(), font_id.clone(), f32::INFINITY)
+            })
+            .collect();
+
+        let label_width = self
+            .rows
+            .iter()
+            .zip(&labels)
+            .map(|(row, label)| {
+                label.bounds().width() + if row.required { marker_width } else { 0.0 }
+            })
+            .fold(0.0, f32::max);
+        let field_x = label_width + style.spacing.x;
+        let field_available = Vec2::new((available.x - field_x).max(0.0), available.y);
+
+        let mut children = Vec::with_capacity(self.rows.len());
+        let mut content_width: f32 = 0.0;
+        let mut y = 0.0;
+        for (i, (row, label)) in self.rows.iter_mut().zip(labels).enumerate() {
+            let field_layout = ctx.with_sibling_index(i, || {
+                row.field
+                    .widget
+                    .layout(ctx, widget_id, field_available, force_shrink)
+            });
+            let field_size = field_layout.bounds.size();
+            let label_size = label.bounds().size();
+            let row_height = field_size.y.max(label_size.y);
+
+            // Labels are centered vertically with their field
+            let label_y = y + (row_height - label_size.y) / 2.0;
+            let mut label_right = label_width;
+            if row.required {
+                label_right -= marker_width;
+                self.texts.push(PlacedText {
+                    pos: Pos2::new(label_width - marker.bounds().width(), label_y),
+                    galley: marker.clone(),
+                    color: Some(style.required_color),
+                });
+            }
+            self.texts.push(PlacedText {
+                pos: Pos2::new(label_right - label_size.x, label_y),
+                galley: label,
+                color: None,
+            });
+
+            let field_y = y + (row_height - field_size.y) / 2.0;
+            children.push(
+                field_layout
+                    .clear_translation()
+                    .translated(Vec2::new(field_x, field_y)),
+            );
+            content_width = content_width.max(field_x + field_size.x);
+            y += row_height;
+
+            let note = match (&row.error, &row.help) {
+                (Some(error), _) => Some((error, error_color)),
+                (None, Some(help)) => Some((help, style.help_color)),
+                (None, None) => None,
+            };
+            if let Some((note, color)) = note {
+                let galley = ctx.painter().galley(
+                    note.clone(),
+                    note_font_id.clone(),
+                    field_available.x.max(field_size.x),
+                );
+                y += style.note_spacing;
+                self.texts.push(PlacedText {
+                    pos: Pos2::new(field_x, y),
+                    color: Some(color),
+                    galley: galley.clone(),
+                });
+                y += galley.bounds().height();
+                content_width = content_width.max(field_x + galley.bounds().width());
+            }
+            y += style.spacing.y;
+        }
+        if !self.rows.is_empty() {
+            y -= style.spacing.y;
+        }
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => content_width,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => y,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        for (row, field_layout) in self.rows.iter_mut().zip(&layout.children) {
+            row.field.widget.draw(ctx, field_layout);
+        }
+
+        let offset = layout.bounds.left_top().to_vec2();
+        let mut painter = ctx.painter();
+        let old_color = painter.text_color;
+        for text in &self.texts {
+            painter.text_color = text.color.unwrap_or(old_color);
+            painter.text_with_galley(GueeTextShape {
+                pos: text.pos + offset,
+                galley: text.galley.clone(),
+                underline: Stroke::NONE,
+                angle: 0.0,
+            });
+        }
+        painter.text_color = old_color;
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (row, field_layout) in self.rows.iter_mut().zip(&layout.children) {
+            row.field
+                .widget
+                .on_event_capture(ctx, field_layout, cursor_position, events, status);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (row, field_layout) in self.rows.iter_mut().zip(&layout.children) {
+            row.field
+                .widget
+                .on_event(ctx, field_layout, cursor_position, events, status);
+        }
+    }
+}
+
+impl StyledWidget for Form {
+    type Style = FormStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_fields_are_aligned() {
+        let ctx = Context::new(Vec2::new(400.0, 300.0), vec![]);
+        let field = |key| {
+            ColoredBox::new(IdGen::key(key))
+                .min_size(Vec2::new(50.0, 20.0))
+                .build()
+        };
+        let mut widget = Form::new(
+            IdGen::key("form"),
+            vec![
+                FormRow::new("Name".to_string(), field("name")),
+                FormRow::new("A much longer label".to_string(), field("long"))
+                    .required(true)
+                    .help("Some help".to_string()),
+                FormRow::new("Short".to_string(), field("short")),
+            ],
+        )
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        let xs: Vec<f32> = layout.children.iter().map(|ch| ch.bounds.left()).collect();
+        assert!(xs[0] > 0.0);
+        assert!(xs.iter().all(|x| *x == xs[0]));
+        // The help text pushes the next row down
+        assert!(layout.children[2].bounds.top() > layout.children[1].bounds.bottom() + 6.0);
+    }
+}
@@ -0,0 +1,206 @@
+use std::ops::RangeInclusive;
+
+use epaint::{CircleShape, RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// Minor gridlines closer than this on screen, in pixels, are not drawn.
+const MIN_MINOR_SPACING: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GridKind {
+    #[default]
+    Lines,
+    /// Dots at the intersections of the gridlines.
+    Dots,
+}
+
+/// An infinite grid filling the widget's area, like the background of a node
+/// editor. The grid is aligned to the origin of the layout coordinates, so it
+/// pans and zooms along with the painter transform set by a parent container.
+/// Only the part of the grid visible inside the clip rect is drawn.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct GridBackground {
+    pub id: IdGen,
+    #[builder(default = LayoutHints::fill())]
+    pub hints: LayoutHints,
+    #[builder(default)]
+    pub kind: GridKind,
+    /// The distance between minor gridlines, in layout coordinates.
+    #[builder(default = 16.0)]
+    pub minor_spacing: f32,
+    /// Every how many minor gridlines there's a major one.
+    #[builder(default = 4)]
+    pub major_every: u32,
+    #[builder(default, strip_option)]
+    pub style_override: Option<GridBackgroundStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct GridBackgroundStyle {
+    #[builder(default = color!("#1d1d1d"))]
+    pub background: Color32,
+    #[builder(default = color!("#262626"))]
+    pub minor_color: Color32,
+    #[builder(default = color!("#333333"))]
+    pub major_color: Color32,
+    #[builder(default = 1.0)]
+    pub line_width: f32,
+    #[builder(default = 1.5)]
+    pub dot_radius: f32,
+}
+
+impl Default for GridBackgroundStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the indices of the gridlines with the given `spacing` that fall
+/// inside `min..=max`. Gridline `i` is at `i * spacing`.
+fn visible_lines(min: f32, max: f32, spacing: f32) -> RangeInclusive<i64> {
+    (min / spacing).ceil() as i64..=(max / spacing).floor() as i64
+}
+
+impl Widget for GridBackground {
+    fn layout(
+        &mut self,
+        _ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => 0.0,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => 0.0,
+            SizeHint::Fill => available.y,
+        };
+        Layout::leaf(widget_id, Vec2::new(width, height))
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+        let mut painter = ctx.painter();
+        painter.rect(RectShape {
+            rect: layout.bounds,
+            rounding: Rounding::none(),
+            fill: style.background,
+            stroke: Stroke::NONE,
+        });
+
+        // The clip rect is in screen coordinates, so it's mapped back to find
+        // the visible part of the grid.
+        let visible = layout.bounds.intersect(
+            painter
+                .transform
+                .inverse_transform_rectangle(painter.clip_rect),
+        );
+        if visible.width() <= 0.0 || visible.height() <= 0.0 || self.minor_spacing <= 0.0 {
+            return;
+        }
+
+        let major_every = self.major_every.max(1) as i64;
+        let is_major = |i: i64| i.rem_euclid(major_every) == 0;
+        // When zoomed out, only major lines are drawn so the grid doesn't turn
+        // into a solid color (and into too many shapes). Indices always count
+        // minor lines, so major lines are the multiples of `major_every`.
+        let step = if painter.transform.scale() * self.minor_spacing < MIN_MINOR_SPACING {
+            major_every
+        } else {
+            1
+        };
+        let spacing = self.minor_spacing * step as f32;
+        let xs = visible_lines(visible.left(), visible.right(), spacing);
+        let ys = visible_lines(visible.top(), visible.bottom(), spacing);
+        let color = |i: i64| {
+            if is_major(i) {
+                style.major_color
+            } else {
+                style.minor_color
+            }
+        };
+
+        match self.kind {
+            GridKind::Lines => {
+                for i in xs.map(|i| i * step) {
+                    let x = i as f32 * self.minor_spacing;
+                    painter.line_segment(
+                        [Pos2::new(x, visible.top()), Pos2::new(x, visible.bottom())],
+                        Stroke::new(style.line_width, color(i)),
+                    );
+                }
+                for j in ys.map(|j| j * step) {
+                    let y = j as f32 * self.minor_spacing;
+                    painter.line_segment(
+                        [Pos2::new(visible.left(), y), Pos2::new(visible.right(), y)],
+                        Stroke::new(style.line_width, color(j)),
+                    );
+                }
+            }
+            GridKind::Dots => {
+                for i in xs.map(|i| i * step) {
+                    for j in ys.clone().map(|j| j * step) {
+                        let fill = if is_major(i) && is_major(j) {
+                            style.major_color
+                        } else {
+                            style.minor_color
+                        };
+                        painter.circle(CircleShape {
+                            center: Pos2::new(
+                                i as f32 * self.minor_spacing,
+                                j as f32 * self.minor_spacing,
+                            ),
+                            radius: style.dot_radius,
+                            fill,
+                            stroke: Stroke::NONE,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event(
+        &mut self,
+        _ctx: &Context,
+        _layout: &Layout,
+        _cursor_position: Pos2,
+        _events: &[Event],
+        _status: &mut EventStatus,
+    ) {
+    }
+}
+
+impl StyledWidget for GridBackground {
+    type Style = GridBackgroundStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_lines() {
+        assert_eq!(visible_lines(0.0, 100.0, 16.0), 0..=6);
+        assert_eq!(visible_lines(-20.0, 20.0, 16.0), -1..=1);
+        assert_eq!(visible_lines(1.0, 15.0, 16.0), 1..=0);
+    }
+}
@@ -0,0 +1,330 @@
+use guee_derives::Builder;
+
+use crate::{
+    base_widgets::split_pane_container::paint_resize_handle, input::MouseButton, prelude::*,
+};
+
+/// Maximum time between two clicks on a column handle for them to count as a
+/// double click, in seconds.
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+
+/// Lays out its cells in a grid with a fixed number of columns, filling rows
+/// from left to right. Each column is as wide as its widest cell, and each row
+/// as tall as its tallest cell.
+///
+/// When `resizable_columns` is set, the first row acts as a header: The user
+/// can drag the boundary at the right of each header cell to resize its
+/// column, and double click it to fit the column to its contents again.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct GridContainer {
+    pub id: IdGen,
+    pub columns: usize,
+    /// The cells of the grid, in row-major order.
+    pub cells: Vec<DynWidget>,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    /// Horizontal space between columns, and vertical space between rows.
+    #[builder(default = Vec2::new(4.0, 2.0))]
+    pub spacing: Vec2,
+    #[builder(default)]
+    pub resizable_columns: bool,
+    #[builder(default = 4.0)]
+    pub handle_width: f32,
+    /// Resized columns can't get narrower than this.
+    #[builder(default = 16.0)]
+    pub min_column_width: f32,
+    #[builder(default, strip_option)]
+    pub style_override: Option<GridContainerStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// The final width of each column. Computed during layout.
+    #[builder(skip)]
+    pub column_widths: Vec<f32>,
+    /// The height of the first row. Computed during layout.
+    #[builder(skip)]
+    pub header_height: f32,
+    #[builder(skip)]
+    pub hovered_handle: Option<usize>,
+}
+
+#[derive(Builder, Clone)]
+pub struct GridContainerStyle {
+    #[builder(default = color!("#5a5a5a"))]
+    pub handle_color: Color32,
+}
+
+impl Default for GridContainerStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct GridContainerState {
+    /// The widths set by the user for resized columns. Columns without an
+    /// entry are sized to fit their contents.
+    pub column_widths: Vec<Option<f32>>,
+    /// The column handle that was last clicked, and when.
+    pub last_handle_click: Option<(usize, f64)>,
+}
+
+impl GridContainer {
+    fn num_rows(&self) -> usize {
+        if self.columns == 0 {
+            0
+        } else {
+            (self.cells.len() + self.columns - 1) / self.columns
+        }
+    }
+
+    /// Returns the rect for the resize handle at the right of column `col`.
+    fn handle_rect(&self, col: usize, bounds: Rect) -> Rect {
+        let x =
+            self.column_widths[..=col].iter().sum::<f32>() + self.spacing.x * (col as f32 + 0.5);
+        Rect::from_center_size(
+            Pos2::new(x, self.header_height / 2.0),
+            Vec2::new(self.handle_width, self.header_height),
+        )
+        .translate(bounds.left_top().to_vec2())
+    }
+}
+
+impl Widget for GridContainer {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        self.hovered_handle = None;
+        if self.columns == 0 {
+            self.column_widths.clear();
+            return Layout::leaf(widget_id, Vec2::ZERO);
+        }
+        let num_rows = self.num_rows();
+
+        // Measure all cells in shrink mode to find the natural size of each
+        // column and row.
+        let mut auto_widths = vec![0.0f32; self.columns];
+        let mut row_heights = vec![0.0f32; num_rows];
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            let size = cell.widget.measure(ctx, widget_id, available);
+            let (row, col) = (i / self.columns, i % self.columns);
+            auto_widths[col] = auto_widths[col].max(size.x);
+            row_heights[row] = row_heights[row].max(size.y);
+        }
+
+        let stored_widths = ctx
+            .memory
+            .get_or(widget_id, GridContainerState::default())
+            .column_widths
+            .clone();
+        self.column_widths = auto_widths
+            .iter()
+            .enumerate()
+            .map(
+                |(col, auto)| match stored_widths.get(col).copied().flatten() {
+                    Some(width) if self.resizable_columns => width,
+                    _ => *auto,
+                },
+            )
+            .collect();
+        self.header_height = row_heights.first().copied().unwrap_or(0.0);
+
+        let mut children = Vec::with_capacity(self.cells.len());
+        let mut y = 0.0;
+        for (row, row_height) in row_heights.iter().enumerate() {
+            let mut x = 0.0;
+            for col in 0..self.columns {
+                let Some(cell) = self.cells.get_mut(row * self.columns + col) else {
+                    break;
+                };
+                let cell_size = Vec2::new(self.column_widths[col], *row_height);
+                let cell_layout = ctx
+                    .with_sibling_index(row * self.columns + col, || {
+                        cell.widget.layout(ctx, widget_id, cell_size, false)
+                    })
+                    .clear_translation()
+                    .translated(Vec2::new(x, y));
+                children.push(cell_layout);
+                x += self.column_widths[col] + self.spacing.x;
+            }
+            y += row_height + self.spacing.y;
+        }
+
+        let content_size = Vec2::new(
+            self.column_widths.iter().sum::<f32>()
+                + self.spacing.x * self.columns.saturating_sub(1) as f32,
+            row_heights.iter().sum::<f32>() + self.spacing.y * num_rows.saturating_sub(1) as f32,
+        );
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => content_size.x,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => content_size.y,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        for (cell, cell_layout) in self.cells.iter_mut().zip(&layout.children) {
+            cell.widget.draw(ctx, cell_layout);
+        }
+
+        if let Some(col) = self.hovered_handle {
+            let style = ctx
+                .theme
+                .borrow()
+                .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+            let handle_rect = self.handle_rect(col, layout.bounds);
+            paint_resize_handle(ctx, handle_rect, style.handle_color);
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (cell, cell_layout) in self.cells.iter_mut().zip(&layout.children) {
+            cell.widget
+                .on_event_capture(ctx, cell_layout, cursor_position, events, status);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if self.resizable_columns && !status.is_consumed() {
+            for col in 0..self.columns {
+                let handle_rect = self
+                    .handle_rect(col, layout.bounds)
+                    // Make it easier to interact with
+                    .expand2(Vec2::new(3.0, 0.0));
+                let handle_id = layout.widget_id.with(("column_handle", col));
+
+                if handle_rect.contains(cursor_position) {
+                    self.hovered_handle = Some(col);
+
+                    let pressed = events
+                        .iter()
+                        .any(|ev| matches!(ev, Event::MousePressed(MouseButton::Primary)));
+                    if pressed {
+                        let now = ctx.time();
+                        let mut state = ctx
+                            .memory
+                            .get_mut_or(layout.widget_id, GridContainerState::default());
+                        match state.last_handle_click {
+                            Some((last_col, t))
+                                if last_col == col && now - t < DOUBLE_CLICK_TIME =>
+                            {
+                                // Double click: Fit the column to its contents
+                                if let Some(width) = state.column_widths.get_mut(col) {
+                                    *width = None;
+                                }
+                                state.last_handle_click = None;
+                            }
+                            _ => state.last_handle_click = Some((col, now)),
+                        }
+                        status.consume_event();
+                        break;
+                    }
+                }
+
+                if ctx.claim_drag_event(handle_id, handle_rect, MouseButton::Primary) {
+                    let delta = ctx.input_state.mouse.delta().x;
+                    let mut state = ctx
+                        .memory
+                        .get_mut_or(layout.widget_id, GridContainerState::default());
+                    if state.column_widths.len() < self.columns {
+                        state.column_widths.resize(self.columns, None);
+                    }
+                    let width = state.column_widths[col].unwrap_or(self.column_widths[col]);
+                    state.column_widths[col] = Some((width + delta).max(self.min_column_width));
+                    // Prevents hovering other widgets while dragging
+                    self.hovered_handle = Some(col);
+                    status.consume_event();
+                    break;
+                }
+            }
+        }
+
+        for (cell, cell_layout) in self.cells.iter_mut().zip(&layout.children) {
+            cell.widget
+                .on_event(ctx, cell_layout, cursor_position, events, status);
+        }
+    }
+}
+
+impl StyledWidget for GridContainer {
+    type Style = GridContainerStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GridContainerState;
+    use crate::prelude::*;
+
+    fn cell(key: usize, size: Vec2) -> DynWidget {
+        ColoredBox::new(IdGen::key(key)).min_size(size).build()
+    }
+
+    fn grid() -> DynWidget {
+        GridContainer::new(
+            IdGen::key("grid"),
+            2,
+            vec![
+                cell(0, Vec2::new(10.0, 10.0)),
+                cell(1, Vec2::new(20.0, 10.0)),
+                cell(2, Vec2::new(30.0, 15.0)),
+            ],
+        )
+        .spacing(Vec2::ZERO)
+        .resizable_columns(true)
+        .build()
+    }
+
+    #[test]
+    fn test_columns_fit_contents() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let layout = ctx.layout_only(&mut grid());
+        assert_eq!(layout.bounds.size(), Vec2::new(50.0, 25.0));
+        assert_eq!(layout.children[1].bounds.min, Pos2::new(30.0, 0.0));
+        assert_eq!(layout.children[2].bounds.min, Pos2::new(0.0, 10.0));
+    }
+
+    #[test]
+    fn test_resized_columns_use_stored_width() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = grid();
+        let grid_id = ctx.layout_only(&mut widget).widget_id;
+        ctx.memory
+            .get_mut::<GridContainerState>(grid_id)
+            .column_widths = vec![Some(60.0)];
+        let layout = ctx.layout_only(&mut widget);
+        assert_eq!(layout.bounds.size(), Vec2::new(80.0, 25.0));
+        assert_eq!(layout.children[1].bounds.min, Pos2::new(60.0, 0.0));
+    }
+}
@@ -0,0 +1,94 @@
+use epaint::{FontFamily, FontId, Pos2, Stroke, Vec2};
+use guee_derives::Builder;
+
+use crate::{
+    icons::DEFAULT_ICON_FAMILY,
+    painter::{GueeGalley, GueeTextShape},
+    prelude::*,
+};
+
+/// Draws a single glyph from an icon font. See the [`icons`](crate::icons)
+/// module for a list of codepoints.
+///
+/// The font family must have been registered as an
+/// [`ExtraFont`](crate::painter::ExtraFont) when creating the `Context`,
+/// otherwise text layout will panic.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Icon {
+    pub codepoint: char,
+    #[builder(default = 16.0)]
+    pub size: f32,
+    #[builder(default, strip_option)]
+    pub color: Option<Color32>,
+    /// The named font family to take the glyph from. Different icon fonts can
+    /// coexist by registering them under different family names.
+    #[builder(default = FontFamily::Name(DEFAULT_ICON_FAMILY.into()))]
+    pub font_family: FontFamily,
+    #[builder(skip)]
+    pub last_galley: Option<GueeGalley>,
+}
+
+impl Widget for Icon {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        _available: Vec2,
+        _force_shrink: bool, // ignore, always shrinked
+    ) -> Layout {
+        let galley = ctx.painter().galley(
+            self.codepoint.to_string(),
+            FontId::new(self.size, self.font_family.clone()),
+            f32::INFINITY,
+        );
+        let size = galley.bounds().size();
+        self.last_galley = Some(galley);
+        let widget_id = IdGen::index(ctx.sibling_index())
+            .with("icon")
+            .resolve(parent_id);
+        Layout::leaf(widget_id, size)
+    }
+
+    fn measure(&mut self, ctx: &Context, _parent_id: WidgetId, _available: Vec2) -> Vec2 {
+        ctx.painter().measure_text(
+            self.codepoint.to_string(),
+            FontId::new(self.size, self.font_family.clone()),
+            f32::INFINITY,
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let galley = self
+            .last_galley
+            .clone()
+            .expect("Layout should be called before draw");
+
+        let mut painter = ctx.painter();
+        let old_color = painter.text_color;
+        if let Some(color) = self.color {
+            painter.text_color = color;
+        }
+        painter.text_with_galley(GueeTextShape {
+            galley,
+            pos: layout.bounds.left_top(),
+            underline: Stroke::NONE,
+            angle: 0.0,
+        });
+        painter.text_color = old_color;
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        LayoutHints::shrink()
+    }
+
+    fn on_event(
+        &mut self,
+        _ctx: &Context,
+        _layout: &Layout,
+        _cursor_position: Pos2,
+        _events: &[Event],
+        _status: &mut EventStatus,
+    ) {
+    }
+}
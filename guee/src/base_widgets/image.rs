@@ -1,6 +1,7 @@
 use crate::prelude::*;
 use epaint::{Pos2, Vec2};
 use guee_derives::Builder;
+use std::sync::Arc;
 
 #[derive(Builder)]
 #[builder(widget)]
@@ -8,37 +9,126 @@ pub struct Image {
     pub id: IdGen,
     pub texture_id: TextureId,
     pub hints: LayoutHints,
+    /// The size of the image when shrinking. When zero, the size of the
+    /// texture is used instead, if known.
     #[builder(default)]
     pub min_size: Vec2,
     #[builder(default = Rect::from_min_size(Pos2::ZERO, Vec2::new(1.0, 1.0)))]
     pub uv_rect: Rect,
     #[builder(default = Color32::WHITE)]
     pub tint: Color32,
+    /// When set, the image is drawn centered inside its bounds, keeping the
+    /// aspect ratio of the texture. This only works for textures managed by
+    /// the painter, for which the size is known.
+    #[builder(default)]
+    pub preserve_aspect_ratio: bool,
+    /// The encoded image bytes, when this image was created with
+    /// [`Image::from_bytes`]. Resolved into `texture_id` during layout.
+    #[builder(skip)]
+    pub encoded_bytes: Option<Arc<[u8]>>,
+    /// Set during layout when the encoded bytes could not be loaded. Nothing
+    /// will be drawn in that case.
+    #[builder(skip)]
+    pub load_failed: bool,
+}
+
+impl Image {
+    /// Creates an image from the given encoded PNG or JPEG `bytes`. The image
+    /// is decoded and uploaded as a texture the first time it is laid out, and
+    /// cached from then on using this widget's id. Unless a `min_size` is
+    /// set, the image is as big as the decoded texture.
+    ///
+    /// Borrowed slices are copied. Pass an `Arc<[u8]>` to avoid the copy when
+    /// the widget is rebuilt every frame.
+    #[cfg(feature = "images")]
+    pub fn from_bytes(id: IdGen, bytes: impl Into<Arc<[u8]>>) -> Self {
+        Self {
+            encoded_bytes: Some(bytes.into()),
+            ..Self::new(id, TextureId::default(), LayoutHints::shrink())
+        }
+    }
+
+    /// Decodes the encoded bytes, if any, and stores the resulting texture.
+    fn load(&mut self, ctx: &Context, widget_id: WidgetId) {
+        #[cfg(feature = "images")]
+        if let Some(bytes) = &self.encoded_bytes {
+            match ctx.load_image_bytes(widget_id, bytes) {
+                Ok(texture_id) => self.texture_id = texture_id,
+                Err(_) => self.load_failed = true,
+            }
+        }
+        #[cfg(not(feature = "images"))]
+        let _ = (ctx, widget_id);
+    }
+
+    /// Returns the size of the image when shrinking: the `min_size`, or the
+    /// size of the shown part of the texture when `min_size` is zero.
+    fn shrink_size(&self, ctx: &Context) -> Vec2 {
+        if self.min_size != Vec2::ZERO {
+            return self.min_size;
+        }
+        ctx.painter()
+            .texture_size(self.texture_id)
+            .map_or(Vec2::ZERO, |size| size * self.uv_rect.size())
+    }
+
+    /// Returns the rectangle where the image should be drawn inside `bounds`.
+    fn image_rect(&self, ctx: &Context, bounds: Rect) -> Rect {
+        if !self.preserve_aspect_ratio {
+            return bounds;
+        }
+        let Some(texture_size) = ctx.painter().texture_size(self.texture_id) else {
+            return bounds;
+        };
+        let image_size = texture_size * self.uv_rect.size();
+        if image_size.x <= 0.0 || image_size.y <= 0.0 {
+            return bounds;
+        }
+        let scale = f32::min(
+            bounds.width() / image_size.x,
+            bounds.height() / image_size.y,
+        );
+        Rect::from_center_size(bounds.center(), image_size * scale)
+    }
 }
 
 impl Widget for Image {
     fn layout(
         &mut self,
-        _ctx: &Context,
+        ctx: &Context,
         parent_id: WidgetId,
         available: Vec2,
         force_shrink: bool,
     ) -> Layout {
         let widget_id = self.id.resolve(parent_id);
+        self.load(ctx, widget_id);
+
+        let shrink_size = self.shrink_size(ctx);
         let size_hints = self.hints.size_hints;
         let width = match size_hints.width.or_force(force_shrink) {
-            SizeHint::Shrink => self.min_size.x,
+            SizeHint::Shrink => shrink_size.x,
             SizeHint::Fill => available.x,
         };
         let height = match size_hints.height.or_force(force_shrink) {
-            SizeHint::Shrink => self.min_size.y,
+            SizeHint::Shrink => shrink_size.y,
             SizeHint::Fill => available.y,
         };
         Layout::leaf(widget_id, Vec2::new(width, height))
     }
 
+    fn measure(&mut self, ctx: &Context, parent_id: WidgetId, _available: Vec2) -> Vec2 {
+        let widget_id = self.id.resolve(parent_id);
+        self.load(ctx, widget_id);
+        self.shrink_size(ctx)
+    }
+
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
-        ctx.painter().image(layout.bounds, self.texture_id, self.uv_rect, self.tint);
+        if self.load_failed {
+            return;
+        }
+        let rect = self.image_rect(ctx, layout.bounds);
+        ctx.painter()
+            .image(rect, self.texture_id, self.uv_rect, self.tint);
     }
 
     fn layout_hints(&self) -> LayoutHints {
@@ -55,3 +145,44 @@ impl Widget for Image {
     ) {
     }
 }
+
+#[cfg(all(test, feature = "images"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Encodes a red square of the given size as a PNG.
+    fn png(size: u32) -> Vec<u8> {
+        let pixels = ::image::RgbaImage::from_pixel(size, size, ::image::Rgba([255, 0, 0, 255]));
+        let mut bytes = Vec::new();
+        ::image::DynamicImage::ImageRgba8(pixels)
+            .write_to(
+                &mut Cursor::new(&mut bytes),
+                ::image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_swap_bytes() {
+        let ctx = Context::new(Vec2::new(100.0, 100.0), vec![]);
+        let layout_image = |bytes: Vec<u8>| {
+            let mut image = Image::from_bytes(IdGen::key("image"), bytes);
+            let layout = image.layout(&ctx, WidgetId::null(), Vec2::new(100.0, 100.0), false);
+            (image.texture_id, layout.bounds.size())
+        };
+
+        let (first, size) = layout_image(png(1));
+        assert_eq!(size, Vec2::splat(1.0));
+        assert_eq!(layout_image(png(1)).0, first);
+
+        // New bytes under the same id replace the old texture
+        let (second, size) = layout_image(png(2));
+        assert_eq!(size, Vec2::splat(2.0));
+        assert_ne!(second, first);
+        assert_eq!(ctx.painter().texture_size(second), Some(Vec2::splat(2.0)));
+        assert_eq!(ctx.painter().texture_size(first), None);
+    }
+}
@@ -0,0 +1,149 @@
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// Offsets its contents by a fixed inset from the start of the line, like the
+/// levels of a tree view or a nested list. Optionally draws a vertical guide
+/// line in the middle of the inset, to help follow the nesting.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct IndentContainer {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    #[builder(default = 16.0)]
+    pub indent: f32,
+    #[builder(default)]
+    pub show_guide: bool,
+    #[builder(default, strip_option)]
+    pub style_override: Option<IndentContainerStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct IndentContainerStyle {
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub guide_stroke: Stroke,
+}
+
+impl Default for IndentContainerStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for IndentContainer {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let indent = self.indent.min(available.x).max(0.0);
+
+        let mut content_layout = self.contents.widget.layout(
+            ctx,
+            widget_id,
+            available - Vec2::new(indent, 0.0),
+            force_shrink,
+        );
+        // In right to left layouts, the indent goes on the right side
+        if ctx.direction() == Direction::LeftToRight {
+            content_layout.translate_x(indent);
+        }
+        Layout::with_children(
+            widget_id,
+            content_layout.bounds.size() + Vec2::new(indent, 0.0),
+            vec![content_layout],
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        if self.show_guide {
+            let style = ctx
+                .theme
+                .borrow()
+                .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+            let x = if ctx.direction() == Direction::LeftToRight {
+                layout.bounds.left() + self.indent / 2.0
+            } else {
+                layout.bounds.right() - self.indent / 2.0
+            };
+            ctx.painter().line_segment(
+                [
+                    Pos2::new(x, layout.bounds.top()),
+                    Pos2::new(x, layout.bounds.bottom()),
+                ],
+                style.guide_stroke,
+            );
+        }
+
+        self.contents.widget.draw(ctx, &layout.children[0]);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.contents.widget.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, events, status)
+    }
+}
+
+impl StyledWidget for IndentContainer {
+    type Style = IndentContainerStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_contents_are_indented() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = IndentContainer::new(
+            IdGen::key("indent"),
+            ColoredBox::background(Color32::RED).build(),
+        )
+        .indent(20.0)
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        assert_eq!(layout.bounds.size(), Vec2::new(200.0, 100.0));
+        assert_eq!(
+            layout.children[0].bounds,
+            Rect::from_min_size(Pos2::new(20.0, 0.0), Vec2::new(180.0, 100.0))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Lists of widgets whose state follows the data, not the position.
+//!
+//! Widgets persist their state across frames (scroll position, focus, cursor,
+//! etc.) keyed by their [`WidgetId`]. When building a list of widgets from a
+//! `Vec` of items, it is tempting to derive each child's id from its index.
+//! But then, when the list is reordered or an item is removed, the stored state
+//! stays at the same position and ends up attached to a different item.
+//!
+//! A [`KeyedList`] solves this by resolving the ids of each child under a
+//! stable key, typically some unique identifier of the item being displayed.
+//! This is the guee analog of React's `key` property.
+//!
+//! ```ignore
+//! KeyedList::vertical(
+//!     IdGen::key("items"),
+//!     state
+//!         .items
+//!         .iter()
+//!         .map(|item| (item.uuid, TextEdit::new(IdGen::key("name"), item.name.clone()).build()))
+//!         .collect(),
+//! )
+//! .build()
+//! ```
+
+use std::hash::Hash;
+
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// Builds lists of widgets where each child is identified by a stable key. See
+/// the [module-level docs](self) for details.
+pub struct KeyedList;
+
+impl KeyedList {
+    /// Returns a vertical [`BoxContainer`] with the given keyed `items`.
+    pub fn vertical<K: Hash>(id: IdGen, items: Vec<(K, DynWidget)>) -> BoxContainer {
+        BoxContainer::vertical(id, Self::scoped(items))
+    }
+
+    /// Returns a horizontal [`BoxContainer`] with the given keyed `items`.
+    pub fn horizontal<K: Hash>(id: IdGen, items: Vec<(K, DynWidget)>) -> BoxContainer {
+        BoxContainer::horizontal(id, Self::scoped(items))
+    }
+
+    fn scoped<K: Hash>(items: Vec<(K, DynWidget)>) -> Vec<DynWidget> {
+        items
+            .into_iter()
+            .map(|(key, contents)| IdScope::new(IdGen::key(key), contents).build())
+            .collect()
+    }
+}
+
+/// A transparent container that resolves the ids of its contents under a
+/// different parent id: The one obtained by resolving its own `id` against its
+/// parent. It does not generate a layout node on its own.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct IdScope {
+    pub id: IdGen,
+    pub contents: DynWidget,
+}
+
+impl Widget for IdScope {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let scope_id = self.id.resolve(parent_id);
+        self.contents
+            .widget
+            .layout(ctx, scope_id, available, force_shrink)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        self.contents.widget.draw(ctx, layout)
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.contents.widget.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event_capture(ctx, layout, cursor_position, events, status)
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event(ctx, layout, cursor_position, events, status)
+    }
+}
@@ -22,6 +22,9 @@ pub struct MarginContainer {
     background_stroke: Stroke,
     #[builder(default = Rounding::none())]
     background_rounding: Rounding,
+    /// When set, the contents are clipped to the bounds of this container.
+    #[builder(default)]
+    clip_contents: bool,
 }
 
 impl Widget for MarginContainer {
@@ -54,13 +57,36 @@ impl Widget for MarginContainer {
             stroke: self.background_stroke,
         });
 
-        self.contents.widget.draw(ctx, &layout.children[0])
+        if self.clip_contents {
+            ctx.with_clip_rect(layout.bounds, || {
+                self.contents.widget.draw(ctx, &layout.children[0])
+            })
+        } else {
+            self.contents.widget.draw(ctx, &layout.children[0])
+        }
     }
 
     fn layout_hints(&self) -> LayoutHints {
         self.contents.widget.layout_hints()
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -0,0 +1,159 @@
+use guee_derives::Builder;
+
+use crate::{base_widgets::menubar_button::MenubarButton, callback::PollToken, prelude::*};
+
+/// A horizontal row of [`MenubarButton`]s, coordinated like a classic
+/// application menubar: Once a menu is open, hovering another menu button
+/// switches to that menu without needing a click.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Menubar {
+    pub id: IdGen,
+    pub menus: Vec<MenubarButton>,
+    /// Fired with the index of the menu and the index of the option inside
+    /// that menu when an option is selected.
+    #[builder(strip_option)]
+    pub on_option_selected: Option<Callback<(usize, usize)>>,
+    #[builder(default = LayoutHints::fill_horizontal())]
+    pub hints: LayoutHints,
+    /// Horizontal space between two menu buttons.
+    #[builder(default)]
+    pub separation: f32,
+    #[builder(skip)]
+    pub poll_tokens: Vec<PollToken<usize>>,
+}
+
+impl Menubar {
+    /// Creates a menubar from a list of menu labels, each one with the labels
+    /// of its options.
+    pub fn from_labels(id: IdGen, menus: Vec<(String, Vec<String>)>) -> Self {
+        Self::new(
+            id,
+            menus
+                .into_iter()
+                .map(|(label, options)| MenubarButton::new(IdGen::key(&label), label, options))
+                .collect(),
+        )
+    }
+
+    fn is_open(ctx: &Context, menu_layout: &Layout) -> bool {
+        MenubarButton::is_open(ctx, menu_layout.widget_id)
+    }
+
+    fn set_open(ctx: &Context, menu_layout: &Layout, is_open: bool) {
+        MenubarButton::set_open(ctx, menu_layout.widget_id, is_open)
+    }
+}
+
+impl Widget for Menubar {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+
+        // Route each menu's selection through an internal callback, so we can
+        // tell which menu it came from.
+        if self.poll_tokens.is_empty() {
+            for menu in &mut self.menus {
+                let (cb, tk) = ctx.create_internal_callback();
+                menu.on_option_selected = Some(cb);
+                self.poll_tokens.push(tk);
+            }
+        }
+
+        let mut children = Vec::with_capacity(self.menus.len());
+        let mut x = 0.0;
+        let mut height: f32 = 0.0;
+        for (i, menu) in self.menus.iter_mut().enumerate() {
+            let mut menu_layout = ctx.with_sibling_index(i, || {
+                menu.layout(
+                    ctx,
+                    widget_id,
+                    Vec2::new((available.x - x).max(0.0), available.y),
+                    true,
+                )
+            });
+            menu_layout.translate_x(x);
+            x += menu_layout.bounds.width() + self.separation;
+            height = height.max(menu_layout.bounds.height());
+            children.push(menu_layout);
+        }
+        let content_width = (x - self.separation).max(0.0);
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => content_width,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => height,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        for (menu, menu_layout) in self.menus.iter_mut().zip(&layout.children) {
+            menu.draw(ctx, menu_layout);
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (menu, menu_layout) in self.menus.iter_mut().zip(&layout.children) {
+            menu.on_event_capture(ctx, menu_layout, cursor_position, events, status);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (menu, menu_layout) in self.menus.iter_mut().zip(&layout.children) {
+            menu.on_event(ctx, menu_layout, cursor_position, events, status);
+        }
+
+        for (menu_idx, tk) in self.poll_tokens.iter().copied().enumerate() {
+            if let Some(option_idx) = ctx.poll_callback_result(tk) {
+                // Selecting an option closes the whole menubar
+                for menu_layout in &layout.children {
+                    Self::set_open(ctx, menu_layout, false);
+                }
+                if let Some(on_option_selected) = self.on_option_selected.take() {
+                    ctx.dispatch_callback(on_option_selected, (menu_idx, option_idx));
+                }
+            }
+        }
+
+        // When a menu is open, hovering a sibling's button switches to it.
+        let open_menu = layout.children.iter().position(|l| Self::is_open(ctx, l));
+        let hovered_menu = layout
+            .children
+            .iter()
+            .position(|l| l.children[0].bounds.contains(cursor_position));
+        if let (Some(open), Some(hovered)) = (open_menu, hovered_menu) {
+            if open != hovered {
+                Self::set_open(ctx, &layout.children[open], false);
+                Self::set_open(ctx, &layout.children[hovered], true);
+            }
+        }
+    }
+}
@@ -3,24 +3,52 @@ use std::iter::repeat;
 use epaint::{emath::Align2, RectShape, Rounding};
 use guee_derives::Builder;
 
-use crate::{callback::PollToken, input::MouseButton, prelude::*};
+use crate::{
+    callback::PollToken,
+    popup::{place_popup, Side},
+    prelude::*,
+};
+
+/// The space between the button and the menu below it.
+const POPUP_GAP: f32 = 3.0;
+
+/// An entry in the menu of a [`MenubarButton`].
+#[derive(Clone, Debug)]
+pub enum MenuEntry {
+    /// A selectable option.
+    Item(String),
+    /// A horizontal line, to visually group options.
+    Separator,
+    /// An option that opens a nested menu to its side when hovered.
+    Submenu(String, Vec<MenuEntry>),
+}
 
 #[derive(Builder)]
-#[builder(widget)]
+#[builder(widget, rename_new = "with_entries")]
 pub struct MenubarButton {
     pub id: IdGen,
     pub label: String,
-    pub button_options: Vec<String>,
+    /// The entries of the menu. When an item is selected, `on_option_selected`
+    /// receives its index, counting only items (not separators or submenus)
+    /// in depth-first order. For flat menus, this is simply the position of
+    /// the option.
+    pub entries: Vec<MenuEntry>,
     #[builder(strip_option)]
     pub on_option_selected: Option<Callback<usize>>,
     #[builder(default)]
     pub layout_hints: LayoutHints,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
     #[builder(skip)]
     pub inner_widgets: Option<InnerWidgets>,
     #[builder(default = Vec2::new(2.0, 5.0))]
     pub inner_padding: Vec2,
     #[builder(default)]
     pub menu_min_width: f32,
+    /// Icons for the top-level entries of the menu, in order.
     #[builder(default)]
     pub button_icons: Vec<(TextureId, Rect)>,
     #[builder(default = Vec2::new(16.0, 16.0))]
@@ -29,13 +57,47 @@ pub struct MenubarButton {
 
 pub struct InnerWidgets {
     pub outer_button: DynWidget,
-    pub inner_contents: DynWidget,
-    pub inner_poll_tokens: Vec<PollToken<()>>,
     pub outer_poll_token: PollToken<()>,
+    pub menu: MenuPopup,
+}
+
+/// The widgets for one level of a (possibly nested) menu.
+pub struct MenuPopup {
+    pub contents: DynWidget,
+    /// One row for each entry in the menu.
+    pub rows: Vec<MenuRow>,
 }
 
+pub enum MenuRow {
+    Item {
+        item_idx: usize,
+        poll_token: PollToken<()>,
+    },
+    Separator,
+    Submenu {
+        poll_token: PollToken<()>,
+        popup: MenuPopup,
+    },
+}
+
+/// Whether the menu is open is tracked by the context, see
+/// [`MenubarButton::is_open`].
+#[derive(Default)]
 pub struct MenubarButtonState {
-    is_open: bool,
+    /// The row index of the open submenu at each nesting level.
+    pub open_submenus: Vec<usize>,
+    /// For each open popup, starting with the menu itself, how much it's
+    /// moved from its usual position to keep it inside the screen. Computed
+    /// with [`place_popup`] while drawing, and applied on the next frame.
+    pub popup_offsets: Vec<Vec2>,
+}
+
+impl MenubarButtonState {
+    /// Closes all the submenus.
+    pub fn close_submenus(&mut self) {
+        self.open_submenus.clear();
+        self.popup_offsets.clear();
+    }
 }
 
 #[derive(Builder, Default, Clone)]
@@ -46,6 +108,176 @@ pub struct MenubarButtonStyle {
     pub menu_stroke: Stroke,
 }
 
+impl MenubarButton {
+    /// Creates a button with a flat menu, where each option is an item.
+    pub fn new(id: IdGen, label: String, button_options: Vec<String>) -> Self {
+        Self::with_entries(
+            id,
+            label,
+            button_options.into_iter().map(MenuEntry::Item).collect(),
+        )
+    }
+
+    /// Builds the widgets for a menu with the given `entries`, recursively
+    /// building any submenus. Items are numbered starting at `next_item_idx`.
+    fn build_popup(
+        &self,
+        ctx: &Context,
+        style: &MenubarButtonStyle,
+        id: IdGen,
+        entries: &[MenuEntry],
+        icons: &[(TextureId, Rect)],
+        next_item_idx: &mut usize,
+    ) -> MenuPopup {
+        let padding = Vec2::new(10.0, 2.0);
+        let mut rows = Vec::with_capacity(entries.len());
+        let mut row_widgets = Vec::with_capacity(entries.len());
+
+        for (i, (entry, icon)) in entries
+            .iter()
+            .zip(icons.iter().map(Some).chain(repeat(None)))
+            .enumerate()
+        {
+            let button = match entry {
+                MenuEntry::Item(label) => {
+                    let (cb, poll_token) = ctx.create_internal_callback();
+                    rows.push(MenuRow::Item {
+                        item_idx: *next_item_idx,
+                        poll_token,
+                    });
+                    *next_item_idx += 1;
+
+                    let button = if let Some((tex_id, uv_rect)) = icon {
+                        Button::with_icon_and_label(label, *tex_id, *uv_rect, self.icon_size)
+                    } else {
+                        Button::with_label(label)
+                    };
+                    button.on_click(cb)
+                }
+                MenuEntry::Separator => {
+                    rows.push(MenuRow::Separator);
+                    row_widgets.push(
+                        ColoredBox::new(IdGen::key(("separator", i)))
+                            .hints(LayoutHints::fill_horizontal())
+                            .min_size(Vec2::new(0.0, 1.0))
+                            .fill(style.menu_stroke.color)
+                            .build(),
+                    );
+                    continue;
+                }
+                MenuEntry::Submenu(label, sub_entries) => {
+                    let (cb, poll_token) = ctx.create_internal_callback();
+                    let popup = self.build_popup(
+                        ctx,
+                        style,
+                        IdGen::key(("submenu", label)),
+                        sub_entries,
+                        &[],
+                        next_item_idx,
+                    );
+                    rows.push(MenuRow::Submenu { poll_token, popup });
+
+                    let contents = BoxContainer::horizontal(
+                        IdGen::key("row"),
+                        vec![
+                            Text::new(label.clone()).build(),
+                            Spacer::fill_h(1).build(),
+                            Text::new("›".into()).build(),
+                        ],
+                    )
+                    .separation(8.0)
+                    .layout_hints(LayoutHints::fill_horizontal())
+                    .build();
+                    Button::new(IdGen::key(("submenu_button", label)), contents).on_click(cb)
+                }
+            };
+
+            row_widgets.push(
+                button
+                    .padding(padding)
+                    .align_contents(Align2::LEFT_CENTER)
+                    .style_override(style.inner_button.clone())
+                    .hints(LayoutHints::fill_horizontal())
+                    .min_size(Vec2::new(self.menu_min_width, 0.0))
+                    .build(),
+            );
+        }
+
+        MenuPopup {
+            contents: MarginContainer::new(
+                id,
+                BoxContainer::vertical(IdGen::key("contents_v"), row_widgets).build(),
+            )
+            .margin(self.inner_padding)
+            .build(),
+            rows,
+        }
+    }
+
+    /// Returns whether the menu of the button with the given `widget_id` is
+    /// open.
+    pub fn is_open(ctx: &Context, widget_id: WidgetId) -> bool {
+        ctx.is_popup_open(widget_id)
+    }
+
+    /// Opens or closes the menu of the button with the given `widget_id`.
+    /// Opening a menu closes all the other popups.
+    pub fn set_open(ctx: &Context, widget_id: WidgetId, is_open: bool) {
+        if is_open == Self::is_open(ctx, widget_id) {
+            return;
+        }
+        ctx.memory
+            .get_mut_or(widget_id, MenubarButtonState::default())
+            .close_submenus();
+        if is_open {
+            ctx.open_popup(widget_id);
+        } else {
+            ctx.close_popup(widget_id);
+        }
+    }
+
+    /// Returns the layout for the given row, given the layout of its menu.
+    fn row_layout(popup_layout: &Layout, row_idx: usize) -> &Layout {
+        &popup_layout.children[0].children[row_idx]
+    }
+
+    /// Recomputes the offset of the popup at the given `depth`, so it fits in
+    /// the screen. The menu opens below the button, and submenus open to the
+    /// right of their parent menu, flipping to the other side when needed.
+    fn update_popup_offset(ctx: &Context, layout: &Layout, depth: usize) {
+        let mut state = ctx.memory.get_mut::<MenubarButtonState>(layout.widget_id);
+        let offset = state
+            .popup_offsets
+            .get(depth)
+            .copied()
+            .unwrap_or(Vec2::ZERO);
+        let bounds = layout.children[depth + 1].bounds.translate(-offset);
+
+        let (anchor, side) = if depth == 0 {
+            let button = layout.children[0].bounds;
+            (button.expand2(Vec2::new(0.0, POPUP_GAP)), Side::Below)
+        } else {
+            let parent = layout.children[depth].bounds;
+            let anchor = Rect::from_min_max(
+                Pos2::new(parent.left(), bounds.top()),
+                Pos2::new(parent.right(), bounds.top()),
+            );
+            (anchor, Side::Right)
+        };
+        let screen = Rect::from_min_size(Pos2::ZERO, ctx.input_state.screen_size);
+        let pos = place_popup(anchor, bounds.size(), side, Align::Start, screen);
+
+        let new_offset = pos - bounds.min;
+        if (new_offset - offset).length() > 0.5 {
+            if state.popup_offsets.len() <= depth {
+                state.popup_offsets.resize(depth + 1, Vec2::ZERO);
+            }
+            state.popup_offsets[depth] = new_offset;
+            ctx.request_repaint();
+        }
+    }
+}
+
 impl Widget for MenubarButton {
     fn layout(
         &mut self,
@@ -62,13 +294,19 @@ impl Widget for MenubarButton {
         if self.inner_widgets.is_none() {
             let default_theme = MenubarButtonStyle::default();
             let theme = ctx.theme.borrow();
-            let theme = theme.get_style::<Self>().unwrap_or(&default_theme);
+            let theme = theme
+                .get_style_variant::<Self>(self.variant)
+                .unwrap_or(&default_theme);
 
-            let (inner_cbs, inner_poll_tokens): (Vec<Callback<()>>, Vec<PollToken<()>>) =
-                (0..self.button_options.len())
-                    .map(|_| ctx.create_internal_callback())
-                    .unzip();
             let (outer_cb, outer_poll_token) = ctx.create_internal_callback();
+            let menu = self.build_popup(
+                ctx,
+                theme,
+                IdGen::key("contents"),
+                &self.entries,
+                &self.button_icons,
+                &mut 0,
+            );
 
             self.inner_widgets = Some(InnerWidgets {
                 outer_button: Button::with_label(&self.label)
@@ -76,52 +314,16 @@ impl Widget for MenubarButton {
                     .style_override(theme.outer_button.clone())
                     .on_click(outer_cb)
                     .build(),
-                inner_contents: MarginContainer::new(
-                    IdGen::key("contents"),
-                    BoxContainer::vertical(
-                        IdGen::key("contents_v"),
-                        self.button_options
-                            .iter()
-                            .zip(
-                                // Add the button icons
-                                self.button_icons.iter().map(Some).chain(repeat(None)),
-                            )
-                            .zip(inner_cbs.into_iter())
-                            .map(|((s, ico), cb)| {
-                                let button = if let Some((tex_id, uv_rect)) = ico {
-                                    Button::with_icon_and_label(
-                                        s,
-                                        *tex_id,
-                                        *uv_rect,
-                                        self.icon_size,
-                                    )
-                                } else {
-                                    Button::with_label(s)
-                                };
-                                button
-                                    .on_click(cb)
-                                    .padding(padding)
-                                    .align_contents(Align2::LEFT_CENTER)
-                                    .style_override(theme.inner_button.clone())
-                                    .hints(LayoutHints::fill_horizontal())
-                                    .min_size(Vec2::new(self.menu_min_width, 0.0))
-                                    .build()
-                            })
-                            .collect(),
-                    )
-                    .build(),
-                )
-                .margin(self.inner_padding)
-                .build(),
-                inner_poll_tokens,
                 outer_poll_token,
+                menu,
             })
         }
 
-        let is_open = ctx
-            .memory
-            .get_or(widget_id, MenubarButtonState { is_open: false })
-            .is_open;
+        let is_open = Self::is_open(ctx, widget_id);
+        let state = ctx.memory.get_or(widget_id, MenubarButtonState::default());
+        let open_submenus = state.open_submenus.clone();
+        let popup_offsets = state.popup_offsets.clone();
+        drop(state);
 
         let mut children = Vec::new();
 
@@ -137,12 +339,47 @@ impl Widget for MenubarButton {
 
         if is_open {
             let inner_contents_layout = inner_widgets
-                .inner_contents
+                .menu
+                .contents
                 .widget
                 .layout(ctx, widget_id, available, force_shrink)
-                .translated((outer_button_bounds.left_bottom() + Vec2::new(0.0, 3.0)).to_vec2());
-
+                .translated(
+                    outer_button_bounds.left_bottom().to_vec2()
+                        + Vec2::new(0.0, POPUP_GAP)
+                        + popup_offsets.first().copied().unwrap_or(Vec2::ZERO),
+                );
             children.push(inner_contents_layout);
+
+            // Each open submenu is placed next to the row that opened it
+            let mut popup = &mut inner_widgets.menu;
+            for (depth, row_idx) in open_submenus.iter().copied().enumerate() {
+                let current = popup;
+                let Some(MenuRow::Submenu { popup: submenu, .. }) = current.rows.get_mut(row_idx)
+                else {
+                    break;
+                };
+
+                let parent_layout = children.last().unwrap();
+                let row_top = Self::row_layout(parent_layout, row_idx)
+                    .bounds
+                    .translate(
+                        parent_layout.bounds.left_top().to_vec2()
+                            + parent_layout.children[0].bounds.left_top().to_vec2(),
+                    )
+                    .top();
+
+                let submenu_layout =
+                    submenu
+                        .contents
+                        .widget
+                        .layout(ctx, widget_id, available, force_shrink);
+                let x = parent_layout.bounds.right();
+                let y = row_top - self.inner_padding.y * 0.5;
+                let offset = popup_offsets.get(depth + 1).copied().unwrap_or(Vec2::ZERO);
+                children.push(submenu_layout.translated(Vec2::new(x, y) + offset));
+
+                popup = submenu;
+            }
         }
 
         Layout::with_children(widget_id, outer_button_bounds.size(), children)
@@ -156,33 +393,60 @@ impl Widget for MenubarButton {
             .widget
             .draw(ctx, &layout.children[0]);
 
+        let is_open = Self::is_open(ctx, layout.widget_id);
         let state = ctx.memory.get::<MenubarButtonState>(layout.widget_id);
-        if state.is_open && layout.children.len() > 1 {
+        let open_submenus = state.open_submenus.clone();
+        drop(state);
+
+        if is_open {
+            // Clicks on the button don't count as clicking outside the menu.
+            // The menu may have been opened during this frame, before it could
+            // be laid out, so the button is always registered.
+            for child in &layout.children {
+                ctx.register_popup_area(layout.widget_id, child.bounds);
+            }
+        }
+
+        if is_open && layout.children.len() > 1 {
             let prev_overlay = ctx.painter().set_overlay(true);
 
             let theme = ctx.theme.borrow();
-            let theme = theme.get_style::<Self>();
-
-            ctx.painter().rect(RectShape {
-                rect: layout.children[1].bounds.translate(Vec2::new(3.0, 2.0)),
-                rounding: Rounding::same(2.0),
-                fill: color!("#00000033"),
-                stroke: Stroke::NONE,
-            });
-
-            ctx.painter().rect(RectShape {
-                rect: layout.children[1].bounds,
-                rounding: Rounding::same(2.0),
-                fill: theme.map(|x| x.menu_fill).unwrap_or(color!("#191919")),
-                stroke: theme
-                    .map(|x| x.menu_stroke)
-                    .unwrap_or(Stroke::new(1.0, color!("#dddddd"))),
-            });
-
-            inner_widgets
-                .inner_contents
-                .widget
-                .draw(ctx, &layout.children[1]);
+            let theme = theme.get_style_variant::<Self>(self.variant);
+
+            let mut popup = &mut inner_widgets.menu;
+            for (depth, popup_layout) in layout.children[1..].iter().enumerate() {
+                ctx.painter().rect(RectShape {
+                    rect: popup_layout.bounds.translate(Vec2::new(3.0, 2.0)),
+                    rounding: Rounding::same(2.0),
+                    fill: color!("#00000033"),
+                    stroke: Stroke::NONE,
+                });
+
+                ctx.painter().rect(RectShape {
+                    rect: popup_layout.bounds,
+                    rounding: Rounding::same(2.0),
+                    fill: theme.map(|x| x.menu_fill).unwrap_or(color!("#191919")),
+                    stroke: theme
+                        .map(|x| x.menu_stroke)
+                        .unwrap_or(Stroke::new(1.0, color!("#dddddd"))),
+                });
+
+                popup.contents.widget.draw(ctx, popup_layout);
+
+                // The absolute position of the popups is only known after
+                // layout, so popups going past the edges of the screen are
+                // moved on the next frame.
+                Self::update_popup_offset(ctx, layout, depth);
+
+                let current = popup;
+                let Some(MenuRow::Submenu { popup: submenu, .. }) = open_submenus
+                    .get(depth)
+                    .and_then(|row_idx| current.rows.get_mut(*row_idx))
+                else {
+                    break;
+                };
+                popup = submenu;
+            }
 
             ctx.painter().set_overlay(prev_overlay);
         }
@@ -192,6 +456,52 @@ impl Widget for MenubarButton {
         self.layout_hints
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        _status: &mut EventStatus,
+    ) {
+        // Same as in `on_event`, inner widgets can't consume events
+        let inner_widgets = self.inner_widgets.as_mut().unwrap();
+        inner_widgets.outer_button.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            &mut EventStatus::Ignored,
+        );
+
+        if !Self::is_open(ctx, layout.widget_id) || layout.children.len() <= 1 {
+            return;
+        }
+        let state = ctx.memory.get::<MenubarButtonState>(layout.widget_id);
+        let open_submenus = state.open_submenus.clone();
+        drop(state);
+
+        let mut popup = &mut inner_widgets.menu;
+        for (depth, popup_layout) in layout.children[1..].iter().enumerate() {
+            popup.contents.widget.on_event_capture(
+                ctx,
+                popup_layout,
+                cursor_position,
+                events,
+                &mut EventStatus::Ignored,
+            );
+
+            let current = popup;
+            let Some(MenuRow::Submenu { popup: submenu, .. }) = open_submenus
+                .get(depth)
+                .and_then(|row_idx| current.rows.get_mut(*row_idx))
+            else {
+                break;
+            };
+            popup = submenu;
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -213,52 +523,89 @@ impl Widget for MenubarButton {
             .poll_callback_result(inner_widgets.outer_poll_token)
             .is_some()
         {
-            let mut state = ctx.memory.get_mut::<MenubarButtonState>(layout.widget_id);
-            state.is_open = true;
+            Self::set_open(ctx, layout.widget_id, true);
             status.consume_event();
         }
 
-        if ctx
-            .memory
-            .get::<MenubarButtonState>(layout.widget_id)
-            .is_open
-            && layout.children.len() > 1
-        {
-            inner_widgets.inner_contents.widget.on_event(
-                ctx,
-                &layout.children[1],
-                cursor_position,
-                events,
-                &mut EventStatus::Ignored, // Don't let inner widgets consume events
-            );
-
-            for (idx, tk) in inner_widgets.inner_poll_tokens.iter().copied().enumerate() {
-                if ctx.poll_callback_result(tk).is_some() {
-                    ctx.memory
-                        .get_mut::<MenubarButtonState>(layout.widget_id)
-                        .is_open = false;
-                    if let Some(on_option_selected) = self.on_option_selected.take() {
-                        ctx.dispatch_callback(on_option_selected, idx);
-                        status.consume_event();
+        let is_open = Self::is_open(ctx, layout.widget_id);
+        let state = ctx.memory.get::<MenubarButtonState>(layout.widget_id);
+        let open_submenus = state.open_submenus.clone();
+        drop(state);
+
+        if is_open && layout.children.len() > 1 {
+            let mut selected_item = None;
+            let mut new_open_submenus = None;
+
+            let mut popup = &mut inner_widgets.menu;
+            for (depth, popup_layout) in layout.children[1..].iter().enumerate() {
+                popup.contents.widget.on_event(
+                    ctx,
+                    popup_layout,
+                    cursor_position,
+                    events,
+                    &mut EventStatus::Ignored, // Don't let inner widgets consume events
+                );
+
+                for (row_idx, row) in popup.rows.iter().enumerate() {
+                    let hovered = Self::row_layout(popup_layout, row_idx)
+                        .bounds
+                        .contains(cursor_position);
+                    match row {
+                        MenuRow::Item {
+                            item_idx,
+                            poll_token,
+                        } => {
+                            if ctx.poll_callback_result(*poll_token).is_some() {
+                                selected_item = Some(*item_idx);
+                            }
+                            if hovered {
+                                new_open_submenus = Some(open_submenus[..depth].to_vec());
+                            }
+                        }
+                        MenuRow::Separator => {}
+                        MenuRow::Submenu { poll_token, .. } => {
+                            // Clicking a submenu does the same as hovering it
+                            let clicked = ctx.poll_callback_result(*poll_token).is_some();
+                            if hovered || clicked {
+                                let mut path = open_submenus[..depth].to_vec();
+                                path.push(row_idx);
+                                new_open_submenus = Some(path);
+                            }
+                        }
                     }
                 }
+
+                let current = popup;
+                let Some(MenuRow::Submenu { popup: submenu, .. }) = open_submenus
+                    .get(depth)
+                    .and_then(|row_idx| current.rows.get_mut(*row_idx))
+                else {
+                    break;
+                };
+                popup = submenu;
             }
-        }
 
-        // Dismiss click detection
-        {
-            let mut state = ctx.memory.get_mut::<MenubarButtonState>(layout.widget_id);
-            let mouse_pos = cursor_position;
-            if state.is_open {
-                if ctx
-                    .input_state
-                    .mouse
-                    .button_state
-                    .is_clicked(MouseButton::Primary)
-                    && !layout.children[0].bounds.contains(mouse_pos)
-                    && !layout.children[1].bounds.contains(mouse_pos)
-                {
-                    state.is_open = false;
+            if let Some(item_idx) = selected_item {
+                Self::set_open(ctx, layout.widget_id, false);
+                if let Some(on_option_selected) = self.on_option_selected.take() {
+                    ctx.dispatch_callback(on_option_selected, item_idx);
+                    status.consume_event();
+                }
+            } else if let Some(path) = new_open_submenus {
+                // When moving the cursor from a row to its submenu, it may go
+                // over empty space or other popups. Submenus are only changed
+                // when hovering another row, so they stay open meanwhile.
+                let mut state = ctx.memory.get_mut::<MenubarButtonState>(layout.widget_id);
+                if path != state.open_submenus {
+                    // Keep the offsets for the menu and the submenus that stay
+                    // open
+                    let common = path
+                        .iter()
+                        .zip(&state.open_submenus)
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    state.popup_offsets.truncate(common + 1);
+                    state.open_submenus = path;
                 }
             }
         }
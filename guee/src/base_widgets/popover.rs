@@ -0,0 +1,380 @@
+use epaint::{RectShape, Rounding};
+use guee_derives::Builder;
+use winit::event::VirtualKeyCode;
+
+use crate::{
+    input::MouseButton,
+    popup::{place_popup, Side},
+    prelude::*,
+};
+
+/// A floating panel with arbitrary `contents`, toggled by clicking the
+/// `anchor` widget. It's closed by clicking the anchor again, clicking
+/// outside of it, or pressing Escape. Unlike a modal, the rest of the UI
+/// keeps working while it's open.
+///
+/// The panel is placed on the preferred `side` of the anchor, flipping to
+/// the other side when it doesn't fit in the screen.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Popover {
+    pub id: IdGen,
+    pub anchor: DynWidget,
+    pub contents: DynWidget,
+    #[builder(default)]
+    pub side: Side,
+    /// How the panel is aligned to the anchor, along the side it's placed on.
+    #[builder(default = Align::Start)]
+    pub align: Align,
+    /// When set, a small arrow points from the panel to the anchor.
+    #[builder(default = true)]
+    pub show_arrow: bool,
+    /// When set, opening the popover keeps the other popups open. Needed when
+    /// the popover is itself inside a popup.
+    #[builder(default)]
+    pub nested: bool,
+    /// Fired when the popover gets opened or closed, for any reason.
+    #[builder(strip_option)]
+    pub on_toggled: Option<Callback<bool>>,
+    #[builder(default, strip_option)]
+    pub style_override: Option<PopoverStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct PopoverStyle {
+    #[builder(default = color!("#242424"))]
+    pub fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub stroke: Stroke,
+    #[builder(default = 4.0)]
+    pub corner_radius: f32,
+    /// The space between the panel bounds and the contents.
+    #[builder(default = Vec2::new(8.0, 8.0))]
+    pub padding: Vec2,
+    /// The distance between the anchor and the panel. The arrow is drawn in
+    /// this space.
+    #[builder(default = 6.0)]
+    pub gap: f32,
+    #[builder(default = 6.0)]
+    pub arrow_size: f32,
+    #[builder(default = 6.0)]
+    pub elevation: f32,
+    #[builder(default = color!("#00000060"))]
+    pub shadow_color: Color32,
+}
+
+impl Default for PopoverStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct PopoverState {
+    /// Moves the panel so it fits in the screen. Computed while drawing, and
+    /// applied during the next layout.
+    pub offset: Vec2,
+    /// Used to detect when the popover is closed from the outside.
+    pub was_open: bool,
+}
+
+impl Popover {
+    fn style(&self, ctx: &Context) -> PopoverStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    /// Returns whether the popover with the given `widget_id` is open.
+    pub fn is_open(ctx: &Context, widget_id: WidgetId) -> bool {
+        ctx.is_popup_open(widget_id)
+    }
+
+    /// Opens or closes the popover with the given `widget_id`. Opening it
+    /// closes all the other popups, unless `nested` is set.
+    pub fn set_open(ctx: &Context, widget_id: WidgetId, open: bool, nested: bool) {
+        match (open, nested) {
+            (true, false) => ctx.open_popup(widget_id),
+            (true, true) => ctx.open_nested_popup(widget_id),
+            (false, _) => ctx.close_popup(widget_id),
+        }
+    }
+
+    /// Returns the bounds of the panel, given the layout of its contents.
+    fn panel_rect(contents_layout: &Layout, style: &PopoverStyle) -> Rect {
+        contents_layout.bounds.expand2(style.padding)
+    }
+
+    /// Recomputes the offset of the panel, so it's placed next to the anchor
+    /// and fits in the screen.
+    fn update_offset(&self, ctx: &Context, layout: &Layout, style: &PopoverStyle) {
+        let mut state = ctx
+            .state::<PopoverState>(layout.widget_id)
+            .get_mut_or_default();
+        let panel = Self::panel_rect(&layout.children[1], style).translate(-state.offset);
+        let gap = match self.side {
+            Side::Below | Side::Above => Vec2::new(0.0, style.gap),
+            Side::Left | Side::Right => Vec2::new(style.gap, 0.0),
+        };
+        let anchor = layout.children[0].bounds.expand2(gap);
+        let screen = Rect::from_min_size(Pos2::ZERO, ctx.screen_size());
+        let pos = place_popup(anchor, panel.size(), self.side, self.align, screen);
+
+        let new_offset = pos - panel.min;
+        if (new_offset - state.offset).length() > 0.5 {
+            state.offset = new_offset;
+            ctx.request_repaint();
+        }
+    }
+
+    /// Paints the arrow from the `panel` to the `anchor`, on whatever side of
+    /// the anchor the panel ended up.
+    fn draw_arrow(ctx: &Context, panel: Rect, anchor: Rect, style: &PopoverStyle) {
+        let s = style.arrow_size;
+        let clamp_x = |x: f32| x.clamp(panel.left() + s * 2.0, panel.right() - s * 2.0);
+        let clamp_y = |y: f32| y.clamp(panel.top() + s * 2.0, panel.bottom() - s * 2.0);
+        let (base, tip_dir) = if panel.top() >= anchor.bottom() {
+            (Pos2::new(clamp_x(anchor.center().x), panel.top()), -Vec2::Y)
+        } else if panel.bottom() <= anchor.top() {
+            (
+                Pos2::new(clamp_x(anchor.center().x), panel.bottom()),
+                Vec2::Y,
+            )
+        } else if panel.left() >= anchor.right() {
+            (
+                Pos2::new(panel.left(), clamp_y(anchor.center().y)),
+                -Vec2::X,
+            )
+        } else if panel.right() <= anchor.left() {
+            (
+                Pos2::new(panel.right(), clamp_y(anchor.center().y)),
+                Vec2::X,
+            )
+        } else {
+            // Overlapping the anchor, there's nowhere to point to
+            return;
+        };
+        let across = tip_dir.rot90() * s;
+        // The base goes slightly into the panel, to cover its stroke
+        let base = base - tip_dir * style.stroke.width;
+        let points = vec![
+            base + across,
+            base + tip_dir * (s + style.stroke.width),
+            base - across,
+        ];
+        ctx.painter()
+            .convex_polygon(points, style.fill, Stroke::NONE);
+    }
+}
+
+impl Widget for Popover {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let anchor_layout = self
+            .anchor
+            .widget
+            .layout(ctx, widget_id, available, force_shrink);
+        let anchor_size = anchor_layout.bounds.size();
+        let mut children = vec![anchor_layout];
+
+        if Self::is_open(ctx, widget_id) {
+            let style = self.style(ctx);
+            let offset = ctx.state::<PopoverState>(widget_id).get_or_default().offset;
+            let contents_layout = self.contents.widget.layout(
+                ctx,
+                widget_id.with("contents"),
+                ctx.screen_size() - style.padding * 2.0,
+                true,
+            );
+            // A first guess, below the anchor. The offset moves the panel to
+            // its actual place.
+            let panel_min = Vec2::new(0.0, anchor_size.y + style.gap);
+            children.push(contents_layout.translated(panel_min + style.padding + offset));
+        }
+
+        Layout::with_children(widget_id, anchor_size, children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        self.anchor.widget.draw(ctx, &layout.children[0]);
+
+        if !Self::is_open(ctx, layout.widget_id) {
+            return;
+        }
+        // The popover may have been opened during this frame, before its
+        // contents could be laid out, so the anchor is always registered.
+        ctx.register_popup_area(layout.widget_id, layout.children[0].bounds);
+        let Some(contents_layout) = layout.children.get(1) else {
+            return;
+        };
+
+        let style = self.style(ctx);
+        let panel = Self::panel_rect(contents_layout, &style);
+        ctx.register_popup_area(layout.widget_id, panel);
+
+        let prev_overlay = ctx.painter().set_overlay(true);
+        let rounding = Rounding::same(style.corner_radius);
+        if style.elevation > 0.0 {
+            ctx.painter()
+                .drop_shadow(panel, rounding, style.elevation, style.shadow_color);
+        }
+        ctx.painter().rect(RectShape {
+            rect: panel,
+            rounding,
+            fill: style.fill,
+            stroke: style.stroke,
+        });
+        if self.show_arrow {
+            Self::draw_arrow(ctx, panel, layout.children[0].bounds, &style);
+        }
+        self.contents.widget.draw(ctx, contents_layout);
+        ctx.painter().set_overlay(prev_overlay);
+
+        // The absolute position of the anchor is only known after layout, so
+        // the panel is moved to its place on the next frame.
+        self.update_offset(ctx, layout, &style);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.anchor.widget.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        // Same order as in `on_event`, the panel is on top
+        if let Some(contents_layout) = layout.children.get(1) {
+            self.contents.widget.on_event_capture(
+                ctx,
+                contents_layout,
+                cursor_position,
+                events,
+                status,
+            );
+        }
+        self.anchor.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            &mut EventStatus::Ignored, // The anchor can't keep the popover from toggling
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let widget_id = layout.widget_id;
+        let style = self.style(ctx);
+
+        // The panel is on top, so it gets the events first
+        if let Some(contents_layout) = layout.children.get(1) {
+            self.contents
+                .widget
+                .on_event(ctx, contents_layout, cursor_position, events, status);
+            let pressed = events.iter().any(|ev| matches!(ev, Event::MousePressed(_)));
+            let over_panel = Self::panel_rect(contents_layout, &style).contains(cursor_position);
+            if pressed && over_panel && status.is_ignored() {
+                status.consume_event();
+            }
+        }
+
+        self.anchor.widget.on_event(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            &mut EventStatus::Ignored, // The anchor can't keep the popover from toggling
+        );
+
+        let open = Self::is_open(ctx, widget_id);
+        let anchor_clicked = status.is_ignored()
+            && ctx.is_pointer_over(widget_id, layout.children[0].bounds, cursor_position)
+            && events
+                .iter()
+                .any(|ev| matches!(ev, Event::MousePressed(MouseButton::Primary)));
+        let escape_pressed = events
+            .iter()
+            .any(|ev| matches!(ev, Event::KeyPressed(VirtualKeyCode::Escape)));
+        if anchor_clicked {
+            Self::set_open(ctx, widget_id, !open, self.nested);
+            status.consume_event();
+        } else if open && escape_pressed {
+            Self::set_open(ctx, widget_id, false, self.nested);
+        }
+
+        let open = Self::is_open(ctx, widget_id);
+        let mut state = ctx.state::<PopoverState>(widget_id).get_mut_or_default();
+        if state.was_open != open {
+            state.was_open = open;
+            if !open {
+                state.offset = Vec2::ZERO;
+            }
+            drop(state);
+            if let Some(on_toggled) = self.on_toggled.take() {
+                ctx.dispatch_callback(on_toggled, open);
+            }
+        }
+    }
+}
+
+impl StyledWidget for Popover {
+    type Style = PopoverStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flips_above_near_the_bottom() {
+        let ctx = Context::new(Vec2::new(400.0, 300.0), vec![]);
+        let style = PopoverStyle::new();
+        let mut popover = Popover::new(
+            IdGen::key("popover"),
+            ColoredBox::new(IdGen::key("anchor"))
+                .min_size(Vec2::new(40.0, 20.0))
+                .build(),
+            ColoredBox::new(IdGen::key("contents"))
+                .min_size(Vec2::new(100.0, 80.0))
+                .build(),
+        )
+        .style_override(style.clone());
+        let widget_id = IdGen::key("popover").resolve(WidgetId::null());
+        ctx.open_popup(widget_id);
+
+        let anchor_pos = Vec2::new(50.0, 260.0);
+        let mut layout = popover.layout(&ctx, WidgetId::null(), Vec2::new(400.0, 300.0), false);
+        layout.to_absolute(anchor_pos);
+        popover.update_offset(&ctx, &layout, &style);
+
+        // There's no room below the anchor, so the next layout goes above
+        let mut layout = popover.layout(&ctx, WidgetId::null(), Vec2::new(400.0, 300.0), false);
+        layout.to_absolute(anchor_pos);
+        let panel = Popover::panel_rect(&layout.children[1], &style);
+        assert_eq!(panel.bottom(), layout.children[0].bounds.top() - style.gap);
+        assert_eq!(panel.left(), layout.children[0].bounds.left());
+    }
+}
@@ -0,0 +1,282 @@
+use std::ops::RangeInclusive;
+
+use epaint::{CircleShape, RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::{input::MouseButton, prelude::*};
+
+/// A slider with two handles, selecting a `(low, high)` sub-range of `range`.
+/// Each handle can be dragged up to the other one, and dragging the selected
+/// segment between them moves both at once.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct RangeSlider {
+    pub id: IdGen,
+    pub low: f64,
+    pub high: f64,
+    #[builder(default = 0.0..=1.0)]
+    pub range: RangeInclusive<f64>,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    /// The width of the slider, when not set to fill.
+    #[builder(default = 150.0)]
+    pub width: f32,
+    #[builder(default = 6.0)]
+    pub handle_radius: f32,
+    #[builder(default = 4.0)]
+    pub track_height: f32,
+    #[builder(strip_option)]
+    pub on_changed: Option<Callback<(f64, f64)>>,
+    #[builder(default, strip_option)]
+    pub style_override: Option<RangeSliderStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct RangeSliderStyle {
+    #[builder(default = color!("#303030"))]
+    pub track_color: Color32,
+    /// The color of the selected segment.
+    #[builder(default = color!("#4a7fb5"))]
+    pub accent_color: Color32,
+    #[builder(default = color!("#d0d0d0"))]
+    pub handle_fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub handle_stroke: Stroke,
+}
+
+impl Default for RangeSliderStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The part of the slider being dragged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeSliderPart {
+    Low,
+    High,
+    Segment,
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct RangeSliderState {
+    pub dragged: Option<RangeSliderPart>,
+}
+
+/// Decides which part of the slider a drag at `cursor_x` grabs. When both
+/// handles overlap under the cursor, the direction of the drag decides: This
+/// way handles that met can always be separated again. Returns `None` when the
+/// direction is not known yet.
+fn pick_part(
+    low_x: f32,
+    high_x: f32,
+    cursor_x: f32,
+    delta_x: f32,
+    radius: f32,
+) -> Option<RangeSliderPart> {
+    let on_low = (cursor_x - low_x).abs() <= radius;
+    let on_high = (cursor_x - high_x).abs() <= radius;
+    match (on_low, on_high) {
+        (true, true) if delta_x < 0.0 => Some(RangeSliderPart::Low),
+        (true, true) if delta_x > 0.0 => Some(RangeSliderPart::High),
+        (true, true) => None,
+        (true, false) => Some(RangeSliderPart::Low),
+        (false, true) => Some(RangeSliderPart::High),
+        (false, false) if cursor_x > low_x && cursor_x < high_x => Some(RangeSliderPart::Segment),
+        (false, false) if (cursor_x - low_x).abs() < (cursor_x - high_x).abs() => {
+            Some(RangeSliderPart::Low)
+        }
+        (false, false) => Some(RangeSliderPart::High),
+    }
+}
+
+impl RangeSlider {
+    /// The horizontal extent of the track, where handle centers can be.
+    fn track_x(&self, bounds: Rect) -> RangeInclusive<f32> {
+        bounds.left() + self.handle_radius..=bounds.right() - self.handle_radius
+    }
+
+    fn value_to_x(&self, value: f64, bounds: Rect) -> f32 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        let t = if end > start {
+            ((value - start) / (end - start)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let track = self.track_x(bounds);
+        track.start() + (track.end() - track.start()) * t as f32
+    }
+
+    fn x_to_value(&self, x: f32, bounds: Rect) -> f64 {
+        let track = self.track_x(bounds);
+        let width = track.end() - track.start();
+        let t = if width > 0.0 {
+            ((x - track.start()) / width).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (start, end) = (*self.range.start(), *self.range.end());
+        start + (end - start) * t as f64
+    }
+}
+
+impl Widget for RangeSlider {
+    fn layout(
+        &mut self,
+        _ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => self.width,
+            SizeHint::Fill => available.x,
+        };
+        Layout::leaf(widget_id, Vec2::new(width, self.handle_radius * 2.0))
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+        let bounds = layout.bounds;
+        let center_y = bounds.center().y;
+        let track = self.track_x(bounds);
+        let low_x = self.value_to_x(self.low, bounds);
+        let high_x = self.value_to_x(self.high, bounds);
+        let track_rect = |from: f32, to: f32| {
+            Rect::from_min_max(
+                Pos2::new(from, center_y - self.track_height / 2.0),
+                Pos2::new(to, center_y + self.track_height / 2.0),
+            )
+        };
+
+        let mut painter = ctx.painter();
+        painter.rect(RectShape {
+            rect: track_rect(*track.start(), *track.end()),
+            rounding: Rounding::same(self.track_height / 2.0),
+            fill: style.track_color,
+            stroke: Stroke::NONE,
+        });
+        painter.rect(RectShape {
+            rect: track_rect(low_x, high_x),
+            rounding: Rounding::none(),
+            fill: style.accent_color,
+            stroke: Stroke::NONE,
+        });
+        for x in [low_x, high_x] {
+            painter.circle(CircleShape {
+                center: Pos2::new(x, center_y),
+                radius: self.handle_radius,
+                fill: style.handle_fill,
+                stroke: style.handle_stroke,
+            });
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        _events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if status.is_consumed() {
+            return;
+        }
+        let bounds = layout.bounds;
+        let mut state = ctx
+            .memory
+            .get_mut_or(layout.widget_id, RangeSliderState::default());
+
+        if !ctx.claim_drag_event(layout.widget_id, bounds, MouseButton::Primary) {
+            state.dragged = None;
+            return;
+        }
+        status.consume_event();
+
+        let delta_x = ctx.input_state.mouse.delta().x;
+        if state.dragged.is_none() {
+            state.dragged = pick_part(
+                self.value_to_x(self.low, bounds),
+                self.value_to_x(self.high, bounds),
+                cursor_position.x,
+                delta_x,
+                self.handle_radius,
+            );
+        }
+
+        let (low, high) = match state.dragged {
+            None => return,
+            Some(RangeSliderPart::Low) => {
+                let value = self.x_to_value(cursor_position.x, bounds);
+                (value.min(self.high), self.high)
+            }
+            Some(RangeSliderPart::High) => {
+                let value = self.x_to_value(cursor_position.x, bounds);
+                (self.low, value.max(self.low))
+            }
+            Some(RangeSliderPart::Segment) => {
+                let track = self.track_x(bounds);
+                let span = *self.range.end() - *self.range.start();
+                let delta = delta_x as f64 / (track.end() - track.start()).max(1.0) as f64 * span;
+                // Keep the segment inside the range, without changing its size
+                let delta = delta.clamp(
+                    *self.range.start() - self.low,
+                    *self.range.end() - self.high,
+                );
+                (self.low + delta, self.high + delta)
+            }
+        };
+
+        if (low, high) != (self.low, self.high) {
+            ctx.dispatch_callback_ref(&mut self.on_changed, (low, high));
+        }
+    }
+}
+
+impl StyledWidget for RangeSlider {
+    type Style = RangeSliderStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_part() {
+        use RangeSliderPart::*;
+        assert_eq!(pick_part(10.0, 50.0, 12.0, 0.0, 6.0), Some(Low));
+        assert_eq!(pick_part(10.0, 50.0, 48.0, 0.0, 6.0), Some(High));
+        assert_eq!(pick_part(10.0, 50.0, 30.0, 0.0, 6.0), Some(Segment));
+        assert_eq!(pick_part(10.0, 50.0, 0.0, 0.0, 6.0), Some(Low));
+
+        // When the handles meet, the drag direction decides
+        assert_eq!(pick_part(30.0, 30.0, 30.0, 0.0, 6.0), None);
+        assert_eq!(pick_part(30.0, 30.0, 30.0, -1.0, 6.0), Some(Low));
+        assert_eq!(pick_part(30.0, 30.0, 30.0, 1.0, 6.0), Some(High));
+    }
+
+    #[test]
+    fn test_value_mapping() {
+        let slider = RangeSlider::new(IdGen::key("slider"), 0.2, 0.8).range(0.0..=10.0);
+        let bounds = Rect::from_min_size(Pos2::new(10.0, 0.0), Vec2::new(112.0, 12.0));
+        assert_eq!(slider.value_to_x(0.0, bounds), 16.0);
+        assert_eq!(slider.value_to_x(10.0, bounds), 116.0);
+        assert_eq!(slider.x_to_value(66.0, bounds), 5.0);
+        assert_eq!(slider.x_to_value(0.0, bounds), 0.0);
+    }
+}
@@ -0,0 +1,341 @@
+use epaint::{RectShape, Rounding};
+use guee_derives::Builder;
+use winit::event::VirtualKeyCode;
+
+use crate::{input::MouseButton, prelude::*};
+
+/// Duration of the animation moving rows out of the way, in seconds.
+const ROW_ANIMATION_DURATION: f32 = 0.15;
+
+/// A vertical list of rows that can be reordered by dragging them with the
+/// mouse. While a row is dragged, the other rows move to make space for it,
+/// and a line indicates where it will be dropped.
+///
+/// The list does not reorder its rows by itself: Instead, `on_reordered` is
+/// fired with the `(from, to)` indices, and the application is expected to
+/// move its items accordingly. The `to` index is the position of the item
+/// after removing it from `from`, like in `items.insert(to, items.remove(from))`.
+///
+/// When the list has keyboard focus, the last clicked row can also be moved
+/// using Alt+Up and Alt+Down.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct ReorderableList {
+    pub id: IdGen,
+    pub rows: Vec<DynWidget>,
+    #[builder(strip_option)]
+    pub on_reordered: Option<Callback<(usize, usize)>>,
+    #[builder(default = LayoutHints::fill_horizontal())]
+    pub hints: LayoutHints,
+    /// Vertical space between two rows.
+    #[builder(default)]
+    pub separation: f32,
+    #[builder(default = Stroke::new(2.0, color!("#5a9bd5")))]
+    pub indicator_stroke: Stroke,
+    #[builder(default = color!("#ffffff14"))]
+    pub selected_fill: Color32,
+    /// Where the drop indicator is drawn, relative to the list. Computed
+    /// during layout.
+    #[builder(skip)]
+    pub indicator_y: Option<f32>,
+    /// The row currently being dragged, if any. Computed during layout.
+    #[builder(skip)]
+    pub dragged_row: Option<usize>,
+}
+
+/// The payload carried by the drag and drop channel while dragging a row.
+pub struct ReorderPayload {
+    pub list: WidgetId,
+    pub from: usize,
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct ReorderableListState {
+    /// The index the dragged row would be moved to if dropped now.
+    pub drop_index: Option<usize>,
+    /// The last clicked row, which is moved by keyboard reordering.
+    pub selected: Option<usize>,
+}
+
+impl ReorderableList {
+    fn reorder(&mut self, ctx: &Context, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        if let Some(on_reordered) = self.on_reordered.take() {
+            ctx.dispatch_callback(on_reordered, (from, to));
+        }
+    }
+}
+
+impl Widget for ReorderableList {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        self.dragged_row = ctx
+            .with_drag_payload(|p: &ReorderPayload| (p.list == widget_id).then_some(p.from))
+            .flatten()
+            .filter(|from| *from < self.rows.len());
+        let drop_index = match self.dragged_row {
+            Some(from) => {
+                let state = ctx
+                    .memory
+                    .get_or(widget_id, ReorderableListState::default());
+                Some(state.drop_index.unwrap_or(from))
+            }
+            None => None,
+        };
+
+        let mut children = Vec::with_capacity(self.rows.len());
+        let mut content_width: f32 = 0.0;
+        let mut natural_height = 0.0;
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let row_layout = ctx.with_sibling_index(i, || {
+                row.widget.layout(
+                    ctx,
+                    widget_id,
+                    Vec2::new(available.x, (available.y - natural_height).max(0.0)),
+                    force_shrink,
+                )
+            });
+            natural_height += row_layout.bounds.height() + self.separation;
+            content_width = content_width.max(row_layout.bounds.width());
+            children.push(row_layout);
+        }
+        natural_height = (natural_height - self.separation).max(0.0);
+
+        // The dragged row leaves a gap at the drop index, and the remaining
+        // rows are stacked around it.
+        let gap_height = self
+            .dragged_row
+            .map(|from| children[from].bounds.height() + self.separation)
+            .unwrap_or(0.0);
+        let mut targets = vec![0.0; children.len()];
+        let mut gap_y = None;
+        let mut y = 0.0;
+        let mut ordinal = 0;
+        for (i, child) in children.iter().enumerate() {
+            if Some(i) == self.dragged_row {
+                continue;
+            }
+            if Some(ordinal) == drop_index {
+                gap_y = Some(y);
+                y += gap_height;
+            }
+            targets[i] = y;
+            y += child.bounds.height() + self.separation;
+            ordinal += 1;
+        }
+        if self.dragged_row.is_some() && gap_y.is_none() {
+            gap_y = Some(y);
+        }
+        if let (Some(from), Some(gap_y)) = (self.dragged_row, gap_y) {
+            targets[from] = gap_y;
+        }
+        self.indicator_y = gap_y.map(|y| (y - self.separation / 2.0).max(0.0));
+
+        for (i, child) in children.iter_mut().enumerate() {
+            let y = ctx.animate(widget_id.with(i), targets[i], ROW_ANIMATION_DURATION);
+            child.translate_y(y);
+        }
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => content_width,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => natural_height,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let state = ctx
+            .memory
+            .get_or(layout.widget_id, ReorderableListState::default());
+        let selected = state.selected;
+        drop(state);
+
+        if ctx.is_focused(layout.widget_id) && self.dragged_row.is_none() {
+            if let Some(row_layout) = selected.and_then(|s| layout.children.get(s)) {
+                ctx.painter().rect(RectShape {
+                    rect: Rect::from_min_max(
+                        Pos2::new(layout.bounds.left(), row_layout.bounds.top()),
+                        Pos2::new(layout.bounds.right(), row_layout.bounds.bottom()),
+                    ),
+                    rounding: Rounding::same(2.0),
+                    fill: self.selected_fill,
+                    stroke: Stroke::NONE,
+                });
+            }
+        }
+
+        for (i, (row, row_layout)) in self.rows.iter_mut().zip(&layout.children).enumerate() {
+            // The dragged row is shown next to the cursor instead.
+            if Some(i) != self.dragged_row {
+                row.widget.draw(ctx, row_layout);
+            }
+        }
+
+        if let Some(indicator_y) = self.indicator_y {
+            let y = layout.bounds.top() + indicator_y;
+            ctx.painter().line_segment(
+                [
+                    Pos2::new(layout.bounds.left(), y),
+                    Pos2::new(layout.bounds.right(), y),
+                ],
+                self.indicator_stroke,
+            );
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (i, (row, row_layout)) in self.rows.iter_mut().zip(&layout.children).enumerate() {
+            if Some(i) != self.dragged_row {
+                row.widget
+                    .on_event_capture(ctx, row_layout, cursor_position, events, status);
+            }
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let widget_id = layout.widget_id;
+
+        for (i, (row, row_layout)) in self.rows.iter_mut().zip(&layout.children).enumerate() {
+            if Some(i) != self.dragged_row {
+                row.widget
+                    .on_event(ctx, row_layout, cursor_position, events, status);
+            }
+        }
+
+        if let Some(from) = self.dragged_row {
+            // The drop index is the number of remaining rows above the cursor
+            let drop_index = layout
+                .children
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != from)
+                .filter(|(_, row_layout)| row_layout.bounds.center().y < cursor_position.y)
+                .count();
+            ctx.memory
+                .get_mut_or(widget_id, ReorderableListState::default())
+                .drop_index = Some(drop_index);
+
+            if ctx.accept_drop::<ReorderPayload>(layout.bounds).is_some() {
+                let mut state = ctx
+                    .memory
+                    .get_mut_or(widget_id, ReorderableListState::default());
+                state.drop_index = None;
+                state.selected = Some(drop_index);
+                drop(state);
+                self.reorder(ctx, from, drop_index);
+                status.consume_event();
+                return;
+            }
+        } else {
+            ctx.memory
+                .get_mut_or(widget_id, ReorderableListState::default())
+                .drop_index = None;
+        }
+
+        if status.is_consumed() {
+            return;
+        }
+
+        for (i, row_layout) in layout.children.iter().enumerate() {
+            let row_rect = Rect::from_min_max(
+                Pos2::new(layout.bounds.left(), row_layout.bounds.top()),
+                Pos2::new(layout.bounds.right(), row_layout.bounds.bottom()),
+            );
+            if ctx.claim_drag_event(widget_id.with(i), row_rect, MouseButton::Primary) {
+                if ctx
+                    .input_state
+                    .mouse
+                    .button_state
+                    .dragging_just_started(MouseButton::Primary)
+                {
+                    let preview = ColoredBox::new(IdGen::key("preview"))
+                        .min_size(row_rect.size())
+                        .rounding(Rounding::same(2.0))
+                        .fill(self.selected_fill)
+                        .stroke(self.indicator_stroke)
+                        .build();
+                    ctx.begin_drag(
+                        ReorderPayload {
+                            list: widget_id,
+                            from: i,
+                        },
+                        preview,
+                    );
+                    ctx.request_focus(widget_id);
+                }
+                status.consume_event();
+                return;
+            }
+
+            if row_rect.contains(cursor_position)
+                && events
+                    .iter()
+                    .any(|ev| matches!(ev, Event::MousePressed(MouseButton::Primary)))
+            {
+                ctx.memory
+                    .get_mut_or(widget_id, ReorderableListState::default())
+                    .selected = Some(i);
+                ctx.request_focus(widget_id);
+            }
+        }
+
+        if !ctx.is_focused(widget_id) || !ctx.input_state.modifiers.alt {
+            return;
+        }
+        let Some(selected) = ctx
+            .memory
+            .get_or(widget_id, ReorderableListState::default())
+            .selected
+            .filter(|s| *s < self.rows.len())
+        else {
+            return;
+        };
+        for event in events {
+            let to = match event {
+                Event::KeyPressed(VirtualKeyCode::Up) if selected > 0 => selected - 1,
+                Event::KeyPressed(VirtualKeyCode::Down) if selected + 1 < self.rows.len() => {
+                    selected + 1
+                }
+                _ => continue,
+            };
+            ctx.memory
+                .get_mut_or(widget_id, ReorderableListState::default())
+                .selected = Some(to);
+            self.reorder(ctx, selected, to);
+            status.consume_event();
+            break;
+        }
+    }
+}
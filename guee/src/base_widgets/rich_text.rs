@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use epaint::{
+    text::{LayoutJob, TextFormat},
+    Galley,
+};
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// A run of text with its own formatting, inside a [`RichText`].
+#[derive(Builder, Clone)]
+pub struct TextSpan {
+    pub text: String,
+    #[builder(default = 14.0)]
+    pub font_size: f32,
+    /// The color of the text. Uses the theme's text color when not set.
+    #[builder(skip)]
+    pub color: Option<Color32>,
+    /// A highlight color drawn behind the text, e.g. for search results.
+    #[builder(default = Color32::TRANSPARENT)]
+    pub background: Color32,
+    #[builder(default = Stroke::NONE)]
+    pub underline: Stroke,
+    #[builder(default = Stroke::NONE)]
+    pub strikethrough: Stroke,
+}
+
+impl TextSpan {
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// A block of text made of several [`TextSpan`]s with different formatting.
+/// All spans are laid out together, so text wraps across spans like regular
+/// text does.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct RichText {
+    pub id: IdGen,
+    pub spans: Vec<TextSpan>,
+    #[builder(skip)]
+    pub galley: Option<Arc<Galley>>,
+}
+
+impl RichText {
+    /// Returns the spans for `text`, with all the occurrences of `query` drawn
+    /// over a `highlight` background.
+    pub fn highlight_matches(text: &str, query: &str, highlight: Color32) -> Vec<TextSpan> {
+        if query.is_empty() {
+            return vec![TextSpan::new(text.to_string())];
+        }
+        let mut spans = vec![];
+        let mut rest = text;
+        while let Some(idx) = rest.find(query) {
+            if idx > 0 {
+                spans.push(TextSpan::new(rest[..idx].to_string()));
+            }
+            spans.push(TextSpan::new(query.to_string()).background(highlight));
+            rest = &rest[idx + query.len()..];
+        }
+        if !rest.is_empty() {
+            spans.push(TextSpan::new(rest.to_string()));
+        }
+        spans
+    }
+
+    fn layout_job(&self, default_color: Color32, wrap_width: f32) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        for span in &self.spans {
+            job.append(
+                &span.text,
+                0.0,
+                TextFormat {
+                    font_id: FontId::proportional(span.font_size),
+                    color: span.color.unwrap_or(default_color),
+                    background: span.background,
+                    underline: span.underline,
+                    strikethrough: span.strikethrough,
+                    ..Default::default()
+                },
+            );
+        }
+        job.wrap.max_width = wrap_width;
+        job
+    }
+}
+
+impl Widget for RichText {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        _force_shrink: bool, // ignore, always shrinked
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let text_color = ctx.theme.borrow().text_color;
+        let job = self.layout_job(text_color, available.x);
+        let galley = ctx.painter().layout_job(job);
+        let size = galley.rect.size();
+        self.galley = Some(galley);
+        Layout::leaf(widget_id, size)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let galley = self
+            .galley
+            .clone()
+            .expect("Layout should be called before draw");
+        ctx.painter().rich_text(layout.bounds.left_top(), galley);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        LayoutHints::shrink()
+    }
+
+    fn on_event(
+        &mut self,
+        _ctx: &Context,
+        _layout: &Layout,
+        _cursor_position: Pos2,
+        _events: &[Event],
+        _status: &mut EventStatus,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_matches() {
+        let spans = RichText::highlight_matches("a cat and a cat", "cat", Color32::YELLOW);
+        let texts: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["a ", "cat", " and a ", "cat"]);
+        assert_eq!(spans[1].background, Color32::YELLOW);
+        assert_eq!(spans[2].background, Color32::TRANSPARENT);
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use epaint::{RectShape, Rounding};
 use guee_derives::Builder;
 
@@ -14,14 +16,187 @@ pub struct VScrollContainer {
     pub min_height: f32,
     #[builder(default = 16.0)]
     pub scrollbar_size: f32,
+    /// When set, the scrollbar is drawn on top of the contents instead of
+    /// reserving space next to them. It fades in while scrolling or when the
+    /// cursor is near it, and fades out after a short delay.
+    #[builder(default)]
+    pub overlay_scrollbar: bool,
+    /// When set, wheel input adds velocity to the scroll position instead of
+    /// moving it directly. The velocity decays over time according to
+    /// `friction`, which gives the scrolling a smooth, kinetic feel.
+    #[builder(default)]
+    pub smooth: bool,
+    /// How quickly the scroll velocity decays when `smooth` is set, in units
+    /// of 1/s. Higher values stop sooner.
+    #[builder(default = 8.0)]
+    pub friction: f32,
+    #[builder(default, strip_option)]
+    pub style_override: Option<VScrollContainerStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct VScrollContainerStyle {
+    #[builder(default = color!("#191919"))]
+    pub bar_fill: Color32,
+    #[builder(default = color!("#303030"))]
+    pub handle_fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub handle_stroke: Stroke,
+    #[builder(default = Rounding::same(1.0))]
+    pub handle_rounding: Rounding,
+}
+
+impl Default for VScrollContainerStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How long an overlay scrollbar stays visible after the last interaction.
+const OVERLAY_SCROLLBAR_DELAY: f64 = 1.0;
+
+/// How much a single wheel step moves the scrollbar, as a fraction.
+const WHEEL_STEP: f32 = 0.05;
+
+/// Below this speed (in scrollbar fractions per second), smooth scrolling
+/// comes to a stop.
+const MIN_SCROLL_VELOCITY: f32 = 1e-3;
+
+/// Advances a smooth scroll by `dt` seconds. The velocity decays
+/// exponentially with the given `friction`, so a velocity of `v` travels a
+/// total distance of `v / friction`. Returns the new fraction and velocity.
+pub fn integrate_scroll(frac: f32, velocity: f32, dt: f32, friction: f32) -> (f32, f32) {
+    let friction = friction.max(f32::EPSILON);
+    let decay = (-friction * dt).exp();
+    let mut frac = frac + velocity * (1.0 - decay) / friction;
+    let mut velocity = velocity * decay;
+    if !(0.0..=1.0).contains(&frac) {
+        frac = frac.clamp(0.0, 1.0);
+        velocity = 0.0;
+    }
+    if velocity.abs() < MIN_SCROLL_VELOCITY {
+        velocity = 0.0;
+    }
+    (frac, velocity)
+}
+
+/// Something a [`VScrollContainer`] can be asked to scroll into view.
+#[derive(Clone, Copy, Debug)]
+pub enum ScrollTarget {
+    /// A widget inside the container's contents.
+    Widget(WidgetId),
+    /// A vertical range, measured from the top of the container's contents.
+    Range(f32, f32),
 }
 
+#[derive(Default)]
 pub struct VScrollContainerState {
     // Scrollbar position, between 1 and 0
     pub scrollbar_frac: f32,
+    /// The last time the user scrolled or had the cursor near an overlay
+    /// scrollbar, as given by `Context::time`.
+    pub last_active: Option<f64>,
+    /// Current smooth scrolling speed, in scrollbar fractions per second.
+    pub velocity: f32,
+    /// The last time the smooth scrolling velocity was integrated.
+    pub last_update: Option<f64>,
+    /// A target requested via `VScrollContainer::scroll_to`, and whether the
+    /// scroll should be animated. Resolved on the next event pass.
+    pub scroll_request: Option<(ScrollTarget, bool)>,
 }
 
 impl VScrollContainer {
+    /// The width reserved for the scrollbar next to the contents.
+    pub(crate) fn reserved_width(&self) -> f32 {
+        if self.overlay_scrollbar {
+            0.0
+        } else {
+            self.scrollbar_size
+        }
+    }
+
+    /// Requests the scroll container with the given `id` to scroll so that
+    /// `target` becomes visible. The request is resolved on the next frame,
+    /// once the container knows the layout of its contents. Scrolling is
+    /// kept to the minimum needed to bring the target into view.
+    pub fn scroll_to(ctx: &Context, id: WidgetId, target: ScrollTarget, animated: bool) {
+        ctx.state::<VScrollContainerState>(id)
+            .get_mut_or_default()
+            .scroll_request = Some((target, animated));
+        ctx.request_repaint();
+    }
+
+    /// Resolves a pending `scroll_to` request, if any.
+    fn resolve_scroll_request(&self, ctx: &Context, layout: &Layout) {
+        let mut state = ctx
+            .state::<VScrollContainerState>(layout.widget_id)
+            .get_mut_or_default();
+        let Some((target, animated)) = state.scroll_request.take() else {
+            return;
+        };
+
+        let contents = &layout.children[0];
+        let (top, bottom) = match target {
+            ScrollTarget::Range(top, bottom) => (top, bottom),
+            ScrollTarget::Widget(id) => match contents.find(id) {
+                Some(child) => (
+                    child.bounds.top() - contents.bounds.top(),
+                    child.bounds.bottom() - contents.bounds.top(),
+                ),
+                None => return,
+            },
+        };
+
+        let scrollable = contents.bounds.height() - layout.bounds.height();
+        if scrollable <= 0.0 {
+            return;
+        }
+        let offset = self.y_offset(layout, state.scrollbar_frac);
+        let viewport = layout.bounds.height();
+        let new_offset = if top < offset {
+            top
+        } else if bottom > offset + viewport {
+            // Prefer showing the top of targets taller than the viewport
+            (bottom - viewport).min(top)
+        } else {
+            return;
+        };
+
+        let target_frac = (new_offset / scrollable).clamp(0.0, 1.0);
+        if animated && !ctx.reduced_motion() {
+            // Travels exactly to the target, see `integrate_scroll`
+            state.velocity = (target_frac - state.scrollbar_frac) * self.friction;
+        } else {
+            state.scrollbar_frac = target_frac;
+            state.velocity = 0.0;
+        }
+    }
+
+    /// Integrates the scrolling velocity since the last frame,
+    /// requesting repaints until it comes to a stop.
+    fn update_smooth_scroll(&self, ctx: &Context, widget_id: WidgetId) {
+        let now = ctx.time();
+        let mut state = ctx
+            .state::<VScrollContainerState>(widget_id)
+            .get_mut_or_default();
+        let dt = state.last_update.map(|t| (now - t) as f32).unwrap_or(0.0);
+        state.last_update = Some(now);
+        if state.velocity == 0.0 {
+            return;
+        }
+        let (frac, velocity) =
+            integrate_scroll(state.scrollbar_frac, state.velocity, dt, self.friction);
+        state.scrollbar_frac = frac;
+        state.velocity = velocity;
+        drop(state);
+        ctx.request_repaint();
+    }
+
     pub fn y_offset(&self, layout: &Layout, scrollbar_frac: f32) -> f32 {
         (layout.children[0].bounds.height() - layout.bounds.height()) * scrollbar_frac
     }
@@ -51,10 +226,10 @@ impl Widget for VScrollContainer {
     ) -> Layout {
         let widget_id = self.id.resolve(parent_id);
 
-        let shrink_ch_layout = self.contents.widget.layout(ctx, parent_id, available, true);
+        let shrink_ch_size = self.contents.widget.measure(ctx, parent_id, available);
 
         let width = match self.hints.size_hints.width.or_force(force_shrink) {
-            SizeHint::Shrink => shrink_ch_layout.bounds.width() + self.scrollbar_size,
+            SizeHint::Shrink => shrink_ch_size.x + self.reserved_width(),
             SizeHint::Fill => available.x,
         };
 
@@ -63,14 +238,23 @@ impl Widget for VScrollContainer {
             SizeHint::Fill => available.y,
         };
 
-        let ch_layout = self.contents.widget.layout(
+        let mut ch_layout = self.contents.widget.layout(
             ctx,
             parent_id,
-            Vec2::new(width - self.scrollbar_size, height),
+            Vec2::new(width - self.reserved_width(), height),
             force_shrink,
         );
 
-        let scrollbar_pos = ch_layout.bounds.right_top();
+        // In right to left layouts, the scrollbar goes on the left side
+        let scrollbar_pos = if ctx.direction() == Direction::RightToLeft {
+            let scrollbar_pos = ch_layout.bounds.left_top();
+            ch_layout.translate_x(self.reserved_width());
+            scrollbar_pos
+        } else if self.overlay_scrollbar {
+            Pos2::new(width - self.scrollbar_size, 0.0)
+        } else {
+            ch_layout.bounds.right_top()
+        };
         let scrollbar_size = Vec2::new(self.scrollbar_size, height);
         let scrollbar_layout = Layout::leaf(widget_id.with("scrollbar"), scrollbar_size)
             .translated(scrollbar_pos.to_vec2());
@@ -83,10 +267,12 @@ impl Widget for VScrollContainer {
     }
 
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        // The state is created on the first event pass
         let scrollbar_frac = ctx
-            .memory
-            .get::<VScrollContainerState>(layout.widget_id)
-            .scrollbar_frac;
+            .state::<VScrollContainerState>(layout.widget_id)
+            .try_get()
+            .map(|state| state.scrollbar_frac)
+            .unwrap_or_default();
         let y_offset = self.y_offset(layout, scrollbar_frac);
 
         let old_transform = ctx.painter().transform;
@@ -100,19 +286,53 @@ impl Widget for VScrollContainer {
         ctx.painter().transform = old_transform;
         ctx.painter().clip_rect = old_clip_rect;
 
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+
+        let opacity = if self.overlay_scrollbar {
+            let since_active = ctx
+                .state::<VScrollContainerState>(layout.widget_id)
+                .try_get()
+                .and_then(|state| state.last_active)
+                .map(|t| ctx.time() - t);
+            let visible = match since_active {
+                Some(elapsed) if elapsed < OVERLAY_SCROLLBAR_DELAY => {
+                    // Wake up to fade out the scrollbar
+                    ctx.request_repaint_after(Duration::from_secs_f64(
+                        OVERLAY_SCROLLBAR_DELAY - elapsed,
+                    ));
+                    1.0
+                }
+                _ => 0.0,
+            };
+            ctx.animate(layout.widget_id.with("scrollbar_opacity"), visible, 0.2)
+        } else {
+            1.0
+        };
+        if opacity <= 0.0 {
+            return;
+        }
+
         let scrollbar_rect = layout.children[1].bounds;
-        ctx.painter().rect(RectShape {
-            rect: scrollbar_rect,
-            rounding: Rounding::none(),
-            fill: color!("#191919"), // TODO Theme
-            stroke: Stroke::NONE,
-        });
+        if !self.overlay_scrollbar {
+            ctx.painter().rect(RectShape {
+                rect: scrollbar_rect,
+                rounding: Rounding::none(),
+                fill: style.bar_fill,
+                stroke: Stroke::NONE,
+            });
+        }
 
         ctx.painter().rect(RectShape {
             rect: self.scrollbar_handle_bounds(layout, scrollbar_frac),
-            rounding: Rounding::same(1.0),
-            fill: color!("#303030"),
-            stroke: Stroke::new(1.0, color!("#464646")),
+            rounding: style.handle_rounding,
+            fill: style.handle_fill.linear_multiply(opacity),
+            stroke: Stroke::new(
+                style.handle_stroke.width,
+                style.handle_stroke.color.linear_multiply(opacity),
+            ),
         })
     }
 
@@ -120,7 +340,7 @@ impl Widget for VScrollContainer {
         self.hints
     }
 
-    fn on_event(
+    fn on_event_capture(
         &mut self,
         ctx: &Context,
         layout: &Layout,
@@ -128,14 +348,36 @@ impl Widget for VScrollContainer {
         events: &[Event],
         status: &mut EventStatus,
     ) {
+        self.resolve_scroll_request(ctx, layout);
+
         let scrollbar_frac = ctx
-            .memory
-            .get_or::<VScrollContainerState>(
-                layout.widget_id,
-                VScrollContainerState {
-                    scrollbar_frac: 0.0,
-                },
+            .state::<VScrollContainerState>(layout.widget_id)
+            .get_or_default()
+            .scrollbar_frac;
+        let cursor_transform =
+            TranslateScale::identity().translated(Vec2::Y * self.y_offset(layout, scrollbar_frac));
+        ctx.with_cursor_transform(cursor_transform, || {
+            self.contents.widget.on_event_capture(
+                ctx,
+                &layout.children[0],
+                cursor_transform.transform_point(cursor_position),
+                events,
+                status,
             )
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let scrollbar_frac = ctx
+            .state::<VScrollContainerState>(layout.widget_id)
+            .get_or_default()
             .scrollbar_frac;
 
         // Set cursor transform
@@ -152,18 +394,29 @@ impl Widget for VScrollContainer {
             )
         });
 
+        // Also drives animated `scroll_to` requests when not in smooth mode
+        self.update_smooth_scroll(ctx, layout.widget_id);
+
         if status.is_consumed() {
             return;
         }
 
+        let smooth = self.smooth && !ctx.reduced_motion();
         let mut state = ctx
-            .memory
-            .get_mut::<VScrollContainerState>(layout.widget_id);
+            .state::<VScrollContainerState>(layout.widget_id)
+            .get_mut();
         let mut status = EventStatus::Ignored;
         if layout.bounds.contains(cursor_position) {
             for event in events {
                 if let Event::MouseWheel(delta) = &event {
-                    state.scrollbar_frac = (state.scrollbar_frac - delta.y * 0.05).clamp(0.0, 1.0);
+                    if smooth {
+                        // Adding `step * friction` makes the total travel
+                        // match a discrete wheel step.
+                        state.velocity -= delta.y * WHEEL_STEP * self.friction;
+                    } else {
+                        state.scrollbar_frac =
+                            (state.scrollbar_frac - delta.y * WHEEL_STEP).clamp(0.0, 1.0);
+                    }
                     status.consume_event();
                 }
             }
@@ -175,7 +428,42 @@ impl Widget for VScrollContainer {
             let main_size = layout.bounds.height() - handle_bounds.height();
             state.scrollbar_frac += delta / main_size;
             state.scrollbar_frac = state.scrollbar_frac.clamp(0.00, 1.0);
+            state.velocity = 0.0;
             status.consume_event();
         }
+
+        if self.overlay_scrollbar {
+            let near_scrollbar = layout.children[1]
+                .bounds
+                .expand(self.scrollbar_size)
+                .intersect(layout.bounds)
+                .contains(cursor_position);
+            if status.is_consumed() || near_scrollbar {
+                state.last_active = Some(ctx.time());
+            }
+        }
+    }
+}
+
+impl StyledWidget for VScrollContainer {
+    type Style = VScrollContainerStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integrate_scroll() {
+        // Integrating in small steps travels `velocity / friction` in total.
+        let (mut frac, mut velocity) = (0.5, 0.4);
+        for _ in 0..1000 {
+            (frac, velocity) = integrate_scroll(frac, velocity, 1.0 / 60.0, 8.0);
+        }
+        assert!((frac - 0.55).abs() < 1e-3);
+        assert_eq!(velocity, 0.0);
+
+        // Hitting a bound stops the motion.
+        assert_eq!(integrate_scroll(0.99, 8.0, 1.0, 8.0), (1.0, 0.0));
     }
 }
@@ -0,0 +1,189 @@
+use epaint::Rounding;
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// A row of connected buttons to pick one of a few options. Adjacent segments
+/// share their borders, and only the outer corners are rounded.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct SegmentedControl {
+    pub id: IdGen,
+    pub options: Vec<String>,
+    pub selected: usize,
+    /// Fired with the index of a segment when the user selects it.
+    #[builder(strip_option)]
+    pub on_selected: Option<Callback<usize>>,
+    /// When the width is set to fill, all segments get the same width.
+    #[builder(default)]
+    pub hints: LayoutHints,
+    #[builder(default, strip_option)]
+    pub style_override: Option<SegmentedControlStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// The button for each option. Created during layout.
+    #[builder(skip)]
+    pub segments: Vec<Button>,
+}
+
+#[derive(Builder, Clone)]
+pub struct SegmentedControlStyle {
+    #[builder(default = color!("#303030"))]
+    pub fill: Color32,
+    #[builder(default = color!("#3a3a3a"))]
+    pub hovered_fill: Color32,
+    #[builder(default = color!("#4a7fb5"))]
+    pub selected_fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub stroke: Stroke,
+    /// The rounding of the outer corners.
+    #[builder(default = 3.0)]
+    pub rounding: f32,
+    #[builder(default = Vec2::new(8.0, 3.0))]
+    pub padding: Vec2,
+}
+
+impl Default for SegmentedControlStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SegmentedControl {
+    fn style(&self, ctx: &Context) -> SegmentedControlStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    /// Returns the button style for segment `i`.
+    fn segment_style(&self, style: &SegmentedControlStyle, i: usize) -> ButtonStyle {
+        let r = style.rounding;
+        let first = i == 0;
+        let last = i + 1 == self.options.len();
+        let rounding = Rounding {
+            nw: if first { r } else { 0.0 },
+            sw: if first { r } else { 0.0 },
+            ne: if last { r } else { 0.0 },
+            se: if last { r } else { 0.0 },
+        };
+        let (idle_fill, hovered_fill) = if i == self.selected {
+            (style.selected_fill, style.selected_fill)
+        } else {
+            (style.fill, style.hovered_fill)
+        };
+        ButtonStyle::new(
+            style.selected_fill,
+            style.stroke,
+            hovered_fill,
+            style.stroke,
+            idle_fill,
+            style.stroke,
+        )
+        .rounding(rounding)
+    }
+}
+
+impl Widget for SegmentedControl {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let style = self.style(ctx);
+
+        self.segments = (0..self.options.len())
+            .map(|i| {
+                let mut button = Button::with_label(self.options[i].clone())
+                    .padding(style.padding)
+                    .style_override(self.segment_style(&style, i));
+                button.id = IdGen::key(("segment", i));
+                button
+            })
+            .collect();
+
+        let sizes: Vec<Vec2> = self
+            .segments
+            .iter_mut()
+            .map(|segment| segment.measure(ctx, widget_id, available))
+            .collect();
+        let height = sizes.iter().map(|size| size.y).fold(0.0, f32::max);
+        let fill_width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => None,
+            SizeHint::Fill => Some(available.x / self.segments.len().max(1) as f32),
+        };
+
+        let mut children = Vec::with_capacity(self.segments.len());
+        let mut x = 0.0;
+        for (i, (segment, size)) in self.segments.iter_mut().zip(sizes).enumerate() {
+            let segment_size = Vec2::new(fill_width.unwrap_or(size.x), height);
+            segment.hints = LayoutHints::fill();
+            let segment_layout = ctx
+                .with_sibling_index(i, || segment.layout(ctx, widget_id, segment_size, false))
+                .translated(Vec2::new(x, 0.0));
+            children.push(segment_layout);
+            x += segment_size.x;
+        }
+
+        Layout::with_children(widget_id, Vec2::new(x, height), children)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        // The selected segment goes last, so its borders are drawn on top
+        let order = (0..self.segments.len())
+            .filter(|i| *i != self.selected)
+            .chain((self.selected < self.segments.len()).then_some(self.selected));
+        for i in order {
+            self.segments[i].draw(ctx, &layout.children[i]);
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (segment, segment_layout) in self.segments.iter_mut().zip(&layout.children) {
+            segment.on_event_capture(ctx, segment_layout, cursor_position, events, status);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        for (i, (segment, segment_layout)) in
+            self.segments.iter_mut().zip(&layout.children).enumerate()
+        {
+            let (on_click, tk) = ctx.create_internal_callback();
+            segment.on_click = Some(on_click);
+            segment.on_event(ctx, segment_layout, cursor_position, events, status);
+            if ctx.poll_callback_result(tk).is_some() && i != self.selected {
+                if let Some(on_selected) = self.on_selected.take() {
+                    ctx.dispatch_callback(on_selected, i);
+                }
+            }
+        }
+    }
+}
+
+impl StyledWidget for SegmentedControl {
+    type Style = SegmentedControlStyle;
+}
@@ -0,0 +1,163 @@
+use epaint::{RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::{input::MouseButton, prelude::*};
+
+/// Wraps any widget to make it behave like a row of a list: A background is
+/// drawn while hovered or `selected`, and clicking anywhere on it fires
+/// `on_click`. Widgets inside the row get the events first, so e.g. a button
+/// in the row can still be clicked without selecting it.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct SelectableRow {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    #[builder(default)]
+    pub selected: bool,
+    #[builder(strip_option)]
+    pub on_click: Option<Callback<()>>,
+    #[builder(default = LayoutHints::fill_horizontal())]
+    pub hints: LayoutHints,
+    #[builder(default, strip_option)]
+    pub style_override: Option<SelectableRowStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    #[builder(skip)]
+    pub hovered: bool,
+}
+
+#[derive(Builder, Clone)]
+pub struct SelectableRowStyle {
+    #[builder(default = color!("#2c2c2c"))]
+    pub hovered_fill: Color32,
+    #[builder(default = color!("#4a7fb5"))]
+    pub selected_fill: Color32,
+    #[builder(default = Rounding::none())]
+    pub rounding: Rounding,
+    /// The space between the row bounds and its contents.
+    #[builder(default = Vec2::new(4.0, 2.0))]
+    pub padding: Vec2,
+}
+
+impl Default for SelectableRowStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SelectableRow {
+    fn style(&self, ctx: &Context) -> SelectableRowStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+}
+
+impl Widget for SelectableRow {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let padding = self.style(ctx).padding;
+
+        let mut contents_layout =
+            self.contents
+                .widget
+                .layout(ctx, widget_id, available - padding * 2.0, force_shrink);
+        contents_layout.translate(padding);
+        let contents_size = contents_layout.bounds.size() + padding * 2.0;
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => contents_size.x,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => contents_size.y,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), vec![contents_layout])
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let style = self.style(ctx);
+        let fill = if self.selected {
+            Some(style.selected_fill)
+        } else if self.hovered {
+            Some(style.hovered_fill)
+        } else {
+            None
+        };
+        if let Some(fill) = fill {
+            ctx.painter().rect(RectShape {
+                rect: layout.bounds,
+                rounding: style.rounding,
+                fill,
+                stroke: Stroke::NONE,
+            });
+        }
+
+        self.contents.widget.draw(ctx, &layout.children[0]);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, events, status);
+
+        if !ctx.is_pointer_over(layout.widget_id, layout.bounds, cursor_position) {
+            return;
+        }
+        self.hovered = true;
+        if status.is_consumed() {
+            return;
+        }
+        for event in events {
+            if let Event::MousePressed(MouseButton::Primary) = event {
+                if let Some(on_click) = self.on_click.take() {
+                    ctx.dispatch_callback(on_click, ());
+                }
+                status.consume_event();
+            }
+        }
+    }
+}
+
+impl StyledWidget for SelectableRow {
+    type Style = SelectableRowStyle;
+}
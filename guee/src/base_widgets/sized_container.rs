@@ -31,6 +31,19 @@ impl Widget for SizedContainer {
         self.contents.widget.layout_hints()
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event_capture(ctx, layout, cursor_position, events, status)
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
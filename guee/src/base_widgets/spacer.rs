@@ -6,7 +6,7 @@ use crate::{
     input::{Event, EventStatus},
     layout::{Layout, LayoutHints, SizeHint, SizeHints},
     widget::Widget,
-    widget_id::WidgetId,
+    widget_id::{IdGen, WidgetId},
 };
 
 #[derive(Builder)]
@@ -14,6 +14,14 @@ use crate::{
 pub struct Spacer {
     min_size: Vec2,
     layout_hints: LayoutHints,
+    /// The smallest size the spacer can take on each axis, even when there's
+    /// not enough space available.
+    #[builder(default = Vec2::ZERO)]
+    min: Vec2,
+    /// The largest size the spacer can take on each axis. Useful to make a
+    /// filling spacer grow only up to a point.
+    #[builder(default = Vec2::INFINITY)]
+    max: Vec2,
 }
 
 impl Spacer {
@@ -27,6 +35,8 @@ impl Spacer {
                 },
                 weight,
             },
+            min: Vec2::ZERO,
+            max: Vec2::INFINITY,
         }
     }
 
@@ -40,6 +50,8 @@ impl Spacer {
                 },
                 weight,
             },
+            min: Vec2::ZERO,
+            max: Vec2::INFINITY,
         }
     }
 
@@ -53,6 +65,8 @@ impl Spacer {
                 },
                 weight: 1,
             },
+            min: Vec2::ZERO,
+            max: Vec2::INFINITY,
         }
     }
 
@@ -66,19 +80,30 @@ impl Spacer {
                 },
                 weight: 1,
             },
+            min: Vec2::ZERO,
+            max: Vec2::INFINITY,
         }
     }
 }
 
+impl Spacer {
+    fn clamp_size(&self, size: Vec2) -> Vec2 {
+        // The minimum wins when both bounds conflict
+        size.min(self.max).max(self.min)
+    }
+}
+
 impl Widget for Spacer {
     fn layout(
         &mut self,
-        _ctx: &Context,
+        ctx: &Context,
         parent_id: WidgetId,
         available: Vec2,
         force_shrink: bool,
     ) -> Layout {
-        let widget_id = parent_id.with("spacer");
+        let widget_id = IdGen::index(ctx.sibling_index())
+            .with("spacer")
+            .resolve(parent_id);
         let width = match self.layout_hints.size_hints.width.or_force(force_shrink) {
             SizeHint::Shrink => self.min_size.x,
             SizeHint::Fill => available.x,
@@ -87,7 +112,11 @@ impl Widget for Spacer {
             SizeHint::Shrink => self.min_size.y,
             SizeHint::Fill => available.y,
         };
-        Layout::leaf(widget_id, Vec2::new(width, height))
+        Layout::leaf(widget_id, self.clamp_size(Vec2::new(width, height)))
+    }
+
+    fn measure(&mut self, _ctx: &Context, _parent_id: WidgetId, _available: Vec2) -> Vec2 {
+        self.clamp_size(self.min_size)
     }
 
     fn draw(&mut self, _ctx: &Context, _layout: &Layout) {
@@ -108,3 +137,26 @@ impl Widget for Spacer {
     ) {
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_max_bounds() {
+        let ctx = Context::new(Vec2::new(400.0, 100.0), vec![]);
+        let available = Vec2::new(400.0, 100.0);
+
+        let mut spacer = Spacer::fill_h(1).max(Vec2::new(50.0, f32::INFINITY));
+        let layout = spacer.layout(&ctx, WidgetId::null(), available, false);
+        assert_eq!(layout.bounds.width(), 50.0);
+
+        let mut spacer = Spacer::fill_h(1).min(Vec2::new(500.0, 10.0));
+        let layout = spacer.layout(&ctx, WidgetId::null(), available, false);
+        assert_eq!(layout.bounds.size(), Vec2::new(500.0, 10.0));
+        assert_eq!(
+            spacer.measure(&ctx, WidgetId::null(), available),
+            Vec2::new(500.0, 10.0)
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use std::ops::RangeInclusive;
+
+use guee_derives::Builder;
+use winit::event::VirtualKeyCode;
+
+use crate::{input::MouseButton, prelude::*};
+
+/// A numeric text field with buttons to increase and decrease the value by a
+/// fixed `step`. Holding a button, or the up and down arrow keys while the
+/// text field is focused, keeps stepping the value.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct SpinBox {
+    pub id: IdGen,
+    pub value: f64,
+    #[builder(default = 1.0)]
+    pub step: f64,
+    /// The value is always clamped to this range.
+    #[builder(default = -f64::INFINITY..=f64::INFINITY)]
+    pub range: RangeInclusive<f64>,
+    /// The value is rounded to this number of decimal places.
+    #[builder(default = 2)]
+    pub num_decimals: u32,
+    /// The integer part of the value is padded with zeros up to this number
+    /// of digits, e.g. to show `05` instead of `5`.
+    #[builder(default)]
+    pub min_digits: usize,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    /// The width of the stepper buttons.
+    #[builder(default = 14.0)]
+    pub button_width: f32,
+    #[builder(strip_option)]
+    pub on_changed: Option<Callback<f64>>,
+    /// Validation result shown by the text field. See [`TextEdit::validation`].
+    #[builder(default, strip_option)]
+    pub validation: Option<Result<(), String>>,
+    #[builder(skip)]
+    pub text_edit: Option<TextEdit>,
+    #[builder(skip)]
+    pub up_button: Option<Button>,
+    #[builder(skip)]
+    pub down_button: Option<Button>,
+}
+
+/// Stored in the widget memory.
+pub struct SpinBoxState {
+    /// The text being typed by the user, which may not be a valid number.
+    pub string_contents: String,
+    pub last_focus_state: bool,
+    /// The direction of the steps while a stepper button is held.
+    pub held_direction: Option<f64>,
+}
+
+impl SpinBox {
+    fn format_contents(&self, value: f64) -> String {
+        let decimals = self.num_decimals as usize;
+        let width = self.min_digits + if decimals > 0 { decimals + 1 } else { 0 };
+        format!("{value:0width$.decimals$}")
+    }
+
+    fn clamp_and_round_value(&self, value: f64) -> f64 {
+        let value = value.clamp(*self.range.start(), *self.range.end());
+        let pow = 10.0f64.powi(self.num_decimals as i32);
+        (value * pow).round() / pow
+    }
+
+    fn stepper_button(id: &'static str, size: Vec2) -> Button {
+        Button::new(
+            IdGen::key(id),
+            Spacer::new(Vec2::ZERO, LayoutHints::shrink()).build(),
+        )
+        .padding(Vec2::ZERO)
+        .min_size(size)
+    }
+
+    fn draw_arrow(ctx: &Context, rect: Rect, up: bool) {
+        let rect = Rect::from_center_size(rect.center(), Vec2::new(6.0, 3.0));
+        let points = if up {
+            vec![rect.left_bottom(), rect.center_top(), rect.right_bottom()]
+        } else {
+            vec![rect.left_top(), rect.right_top(), rect.center_bottom()]
+        };
+        let color = ctx.theme.borrow().text_color;
+        ctx.painter().convex_polygon(points, color, Stroke::NONE);
+    }
+}
+
+impl Widget for SpinBox {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let text_edit_id = IdGen::key("text");
+        let focused = ctx.is_focused(text_edit_id.resolve(widget_id));
+
+        let contents = if focused {
+            let formatted = self.format_contents(self.value);
+            ctx.memory
+                .get_or(
+                    widget_id,
+                    SpinBoxState {
+                        string_contents: formatted,
+                        last_focus_state: false,
+                        held_direction: None,
+                    },
+                )
+                .string_contents
+                .clone()
+        } else {
+            self.format_contents(self.value)
+        };
+
+        let text_edit = self.text_edit.insert(
+            TextEdit::new(text_edit_id, contents)
+                .layout_hints(self.hints)
+                .min_width(40.0),
+        );
+        text_edit.validation = self.validation.clone();
+        let text_layout = text_edit.layout(
+            ctx,
+            widget_id,
+            available - Vec2::new(self.button_width, 0.0),
+            force_shrink,
+        );
+        let text_size = text_layout.bounds.size();
+        // The stepper buttons span the field, not the validation message
+        let field_height = text_layout.children[0].bounds.height();
+
+        let button_size = Vec2::new(self.button_width, field_height / 2.0);
+        let up_layout = self
+            .up_button
+            .insert(Self::stepper_button("up", button_size))
+            .layout(ctx, widget_id, button_size, true)
+            .translated(Vec2::new(text_size.x, 0.0));
+        let down_layout = self
+            .down_button
+            .insert(Self::stepper_button("down", button_size))
+            .layout(ctx, widget_id, button_size, true)
+            .translated(Vec2::new(text_size.x, button_size.y));
+
+        Layout::with_children(
+            widget_id,
+            Vec2::new(text_size.x + self.button_width, text_size.y),
+            vec![text_layout, up_layout, down_layout],
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let (Some(text_edit), Some(up), Some(down)) = (
+            &mut self.text_edit,
+            &mut self.up_button,
+            &mut self.down_button,
+        ) else {
+            panic!("Layout should be called before draw");
+        };
+        text_edit.draw(ctx, &layout.children[0]);
+        up.draw(ctx, &layout.children[1]);
+        down.draw(ctx, &layout.children[2]);
+        Self::draw_arrow(ctx, layout.children[1].bounds, true);
+        Self::draw_arrow(ctx, layout.children[2].bounds, false);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let (Some(text_edit), Some(up), Some(down)) = (
+            &mut self.text_edit,
+            &mut self.up_button,
+            &mut self.down_button,
+        ) else {
+            panic!("Layout should be called before on_event_capture");
+        };
+        let [text_layout, up_layout, down_layout] = [0, 1, 2].map(|i| &layout.children[i]);
+        text_edit.on_event_capture(ctx, text_layout, cursor_position, events, status);
+        up.on_event_capture(ctx, up_layout, cursor_position, events, status);
+        down.on_event_capture(ctx, down_layout, cursor_position, events, status);
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if status.is_consumed() {
+            return;
+        }
+        let (Some(text_edit), Some(up), Some(down)) = (
+            &mut self.text_edit,
+            &mut self.up_button,
+            &mut self.down_button,
+        ) else {
+            panic!("Layout should be called before on_event");
+        };
+
+        let (text_cb, text_tk) = ctx.create_internal_callback();
+        let (up_cb, up_tk) = ctx.create_internal_callback();
+        let (down_cb, down_tk) = ctx.create_internal_callback();
+        text_edit.on_changed = Some(text_cb);
+        up.on_click = Some(up_cb);
+        down.on_click = Some(down_cb);
+
+        let [text_layout, up_layout, down_layout] = [0, 1, 2].map(|i| &layout.children[i]);
+        text_edit.on_event(ctx, text_layout, cursor_position, events, status);
+        up.on_event(ctx, up_layout, cursor_position, events, status);
+        down.on_event(ctx, down_layout, cursor_position, events, status);
+
+        let focused = ctx.is_focused(text_layout.widget_id);
+        let formatted = self.format_contents(self.value);
+        let mut state = ctx.memory.get_mut_or(
+            layout.widget_id,
+            SpinBoxState {
+                string_contents: formatted.clone(),
+                last_focus_state: false,
+                held_direction: None,
+            },
+        );
+        if focused && !state.last_focus_state {
+            // Start editing from the value the user was seeing
+            state.string_contents = formatted;
+        }
+        state.last_focus_state = focused;
+
+        let mut new_value = None;
+        if let Some(text) = ctx.poll_callback_result(text_tk) {
+            new_value = text.trim().parse::<f64>().ok();
+            state.string_contents = text;
+        }
+
+        if ctx.poll_callback_result(up_tk).is_some() {
+            state.held_direction = Some(1.0);
+        } else if ctx.poll_callback_result(down_tk).is_some() {
+            state.held_direction = Some(-1.0);
+        }
+        // A stepper is held from the moment it's clicked, until the mouse is
+        // released or leaves it.
+        let mouse_down = ctx
+            .input_state
+            .mouse
+            .button_state
+            .is_down(MouseButton::Primary);
+        if !mouse_down {
+            state.held_direction = None;
+        }
+        let held_direction = state.held_direction;
+        // The repeat state is also kept in the memory
+        drop(state);
+
+        let mut step = 0.0;
+        for (direction, button_layout) in [(1.0, up_layout), (-1.0, down_layout)] {
+            let held =
+                held_direction == Some(direction) && button_layout.bounds.contains(cursor_position);
+            step += direction * ctx.repeat(button_layout.widget_id, held) as f64;
+        }
+
+        if focused {
+            for event in events {
+                match event {
+                    Event::KeyPressed(VirtualKeyCode::Up) => step += 1.0,
+                    Event::KeyPressed(VirtualKeyCode::Down) => step -= 1.0,
+                    _ => {}
+                }
+            }
+        }
+
+        if step != 0.0 {
+            let value = self.clamp_and_round_value(self.value + step * self.step);
+            ctx.memory
+                .get_mut::<SpinBoxState>(layout.widget_id)
+                .string_contents = self.format_contents(value);
+            new_value = Some(value);
+        }
+
+        if let Some(value) = new_value {
+            let value = self.clamp_and_round_value(value);
+            ctx.dispatch_callback_ref(&mut self.on_changed, value);
+            status.consume_event();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_and_round() {
+        let spin_box = SpinBox::new(IdGen::key("spin"), 0.0)
+            .range(0.0..=10.0)
+            .num_decimals(1);
+        assert_eq!(spin_box.clamp_and_round_value(3.14159), 3.1);
+        assert_eq!(spin_box.clamp_and_round_value(-2.0), 0.0);
+        assert_eq!(spin_box.clamp_and_round_value(12.0), 10.0);
+    }
+
+    #[test]
+    fn test_min_digits() {
+        let spin_box = SpinBox::new(IdGen::key("spin"), 0.0)
+            .num_decimals(0)
+            .min_digits(2);
+        assert_eq!(spin_box.format_contents(5.0), "05");
+        assert_eq!(spin_box.format_contents(123.0), "123");
+        let spin_box = spin_box.num_decimals(1);
+        assert_eq!(spin_box.format_contents(5.0), "05.0");
+    }
+
+    #[test]
+    fn test_buttons_stacked_right_of_text() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = SpinBox::new(IdGen::key("spin"), 1.0).build();
+        let layout = ctx.layout_only(&mut widget);
+        let [text, up, down] = [0, 1, 2].map(|i| layout.children[i].bounds);
+        assert_eq!(up.left(), text.right());
+        assert_eq!(down.top(), up.bottom());
+        assert_eq!(down.bottom(), text.bottom());
+        assert_eq!(layout.bounds.width(), text.width() + 14.0);
+    }
+}
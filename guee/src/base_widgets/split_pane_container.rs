@@ -25,6 +25,11 @@ pub struct SplitPaneContainer {
     default_frac: f32,
     #[builder(default = 4.0)]
     handle_width: f32,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    variant: Option<&'static str>,
     #[builder(skip)]
     hovered: bool,
 }
@@ -96,16 +101,19 @@ impl Widget for SplitPaneContainer {
         let available_left = axis.vec2_scale(available, frac, 1.0) - handle;
         let available_right = axis.vec2_scale(available, 1.0 - frac, 1.0) - handle;
 
-        let left_layout = self
-            .left_widget
-            .widget
-            .layout(ctx, widget_id, available_left, false);
+        let left_layout = ctx.with_sibling_index(0, || {
+            self.left_widget
+                .widget
+                .layout(ctx, widget_id, available_left, false)
+        });
 
         let offset = available.main_dir(axis) * frac + self.handle_width;
-        let right_layout = self
-            .right_widget
-            .widget
-            .layout(ctx, widget_id, available_right, false)
+        let right_layout = ctx
+            .with_sibling_index(1, || {
+                self.right_widget
+                    .widget
+                    .layout(ctx, widget_id, available_right, false)
+            })
             .translated(axis.new_vec2(offset, 0.0));
 
         Layout::with_children(widget_id, available, vec![left_layout, right_layout])
@@ -120,16 +128,13 @@ impl Widget for SplitPaneContainer {
             handle_color: Color32::BLACK,
         };
         let theme = ctx.theme.borrow();
-        let style = theme.get_style::<Self>().unwrap_or(&default_style);
+        let style = theme
+            .get_style_variant::<Self>(self.variant)
+            .unwrap_or(&default_style);
 
         if self.hovered {
             let handle_rect = self.resize_handle_visual_rect(frac, layout.bounds);
-            ctx.painter().rect(RectShape {
-                rect: handle_rect,
-                rounding: Rounding::same(2.0),
-                fill: style.handle_color,
-                stroke: Stroke::NONE,
-            });
+            paint_resize_handle(ctx, handle_rect, style.handle_color);
         }
     }
 
@@ -139,6 +144,30 @@ impl Widget for SplitPaneContainer {
         LayoutHints::fill()
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.left_widget.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        );
+        self.right_widget.widget.on_event_capture(
+            ctx,
+            &layout.children[1],
+            cursor_position,
+            events,
+            status,
+        );
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -186,6 +215,17 @@ impl Widget for SplitPaneContainer {
     }
 }
 
+/// Paints the visual part of a draggable resize handle. Shared by all the
+/// widgets with resizable parts, so handles look the same everywhere.
+pub(crate) fn paint_resize_handle(ctx: &Context, rect: Rect, color: Color32) {
+    ctx.painter().rect(RectShape {
+        rect,
+        rounding: Rounding::same(2.0),
+        fill: color,
+        stroke: Stroke::NONE,
+    });
+}
+
 impl StyledWidget for SplitPaneContainer {
     type Style = SplitPaneContainerStyle;
 }
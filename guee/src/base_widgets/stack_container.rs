@@ -1,4 +1,4 @@
-use std::any::type_name;
+use std::{any::type_name, borrow::Cow};
 
 use epaint::{Pos2, Rect, Vec2};
 use guee_derives::Builder;
@@ -17,6 +17,42 @@ use crate::{
 pub struct StackContainer {
     id: IdGen,
     contents: Vec<(Vec2, DynWidget)>,
+    /// By default, events are sent to every child, front to back, so clicks
+    /// on a child that ignores them fall through to the ones behind it. When
+    /// `layered` is set, mouse presses and scrolling only go to the front-most
+    /// child under the cursor, like a modal backdrop blocking the UI behind
+    /// it. The other children are told the cursor is far away.
+    #[builder(default)]
+    layered: bool,
+}
+
+impl StackContainer {
+    /// Returns the index of the front-most child under the cursor.
+    fn front_most_hit(layout: &Layout, cursor_position: Pos2) -> Option<usize> {
+        layout
+            .children
+            .iter()
+            .rposition(|ch| ch.bounds.contains(cursor_position))
+    }
+
+    /// Returns the cursor position and events seen by the child at `index`.
+    fn child_events<'a>(
+        &self,
+        index: usize,
+        hit: Option<usize>,
+        cursor_position: Pos2,
+        events: &'a [Event],
+    ) -> (Pos2, Cow<'a, [Event]>) {
+        if !self.layered || hit == Some(index) {
+            return (cursor_position, Cow::Borrowed(events));
+        }
+        let events = events
+            .iter()
+            .filter(|ev| !matches!(ev, Event::MousePressed(_) | Event::MouseWheel(_)))
+            .cloned()
+            .collect();
+        (Pos2::new(f32::INFINITY, f32::INFINITY), Cow::Owned(events))
+    }
 }
 
 impl Widget for StackContainer {
@@ -36,11 +72,10 @@ impl Widget for StackContainer {
         let mut children_layouts = Vec::new();
         let mut current_rect = Rect::from_min_max(Pos2::ZERO, Pos2::ZERO);
 
-        for (ch_offs, ch) in &mut self.contents {
+        for (i, (ch_offs, ch)) in self.contents.iter_mut().enumerate() {
             let available = available - *ch_offs;
-            let ch_layout = ch
-                .widget
-                .layout(ctx, widget_id, available, false)
+            let ch_layout = ctx
+                .with_sibling_index(i, || ch.widget.layout(ctx, widget_id, available, false))
                 .translated(*ch_offs);
             current_rect = current_rect.union(ch_layout.bounds);
             children_layouts.push(ch_layout);
@@ -59,6 +94,27 @@ impl Widget for StackContainer {
         LayoutHints::fill()
     }
 
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let hit = Self::front_most_hit(layout, cursor_position);
+        for i in (0..self.contents.len().min(layout.children.len())).rev() {
+            let (cursor_position, events) = self.child_events(i, hit, cursor_position, events);
+            self.contents[i].1.widget.on_event_capture(
+                ctx,
+                &layout.children[i],
+                cursor_position,
+                &events,
+                status,
+            );
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -67,9 +123,48 @@ impl Widget for StackContainer {
         events: &[Event],
         status: &mut EventStatus,
     ) {
-        for ((_, ch), ch_layout) in self.contents.iter_mut().zip(&layout.children).rev() {
-            ch.widget
-                .on_event(ctx, ch_layout, cursor_position, events, status);
+        let hit = Self::front_most_hit(layout, cursor_position);
+        for i in (0..self.contents.len().min(layout.children.len())).rev() {
+            let (cursor_position, events) = self.child_events(i, hit, cursor_position, events);
+            self.contents[i].1.widget.on_event(
+                ctx,
+                &layout.children[i],
+                cursor_position,
+                &events,
+                status,
+            );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::StackContainer;
+
+    #[test]
+    fn test_front_most_hit() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = StackContainer::new(
+            IdGen::key("stack"),
+            vec![
+                (Vec2::ZERO, ColoredBox::background(Color32::RED).build()),
+                (
+                    Vec2::new(10.0, 10.0),
+                    ColoredBox::new(IdGen::key("front"))
+                        .min_size(Vec2::splat(20.0))
+                        .build(),
+                ),
+            ],
+        )
+        .layered(true)
+        .build();
+
+        let layout = ctx.layout_only(&mut widget);
+        let hit = |x, y| StackContainer::front_most_hit(&layout, Pos2::new(x, y));
+        assert_eq!(hit(15.0, 15.0), Some(1));
+        assert_eq!(hit(50.0, 50.0), Some(0));
+        assert_eq!(hit(500.0, 50.0), None);
+    }
+}
@@ -0,0 +1,172 @@
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// A header pinned at the top of a scrollable body, like the header row of a
+/// table. Only the body is scrolled, while the header is always visible.
+///
+/// The header is laid out with the same width as the body's viewport, that is,
+/// leaving room for the scrollbar unless it's an overlay one, so columns in the
+/// header stay aligned with the ones in the body.
+#[derive(Builder)]
+#[builder(widget, rename_new = "with_scroll")]
+pub struct StickyHeaderContainer {
+    pub id: IdGen,
+    pub header: DynWidget,
+    pub scroll: VScrollContainer,
+    #[builder(default = LayoutHints::fill())]
+    pub hints: LayoutHints,
+}
+
+impl StickyHeaderContainer {
+    /// Creates a container with the given `header`, and the `body` wrapped in
+    /// a [`VScrollContainer`] filling the remaining space.
+    pub fn new(id: IdGen, header: DynWidget, body: DynWidget) -> Self {
+        Self::with_scroll(
+            id,
+            header,
+            VScrollContainer::new(IdGen::key("body"), body).hints(LayoutHints::fill()),
+        )
+    }
+}
+
+impl Widget for StickyHeaderContainer {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let reserved_width = self.scroll.reserved_width();
+
+        let mut header_layout = ctx.with_sibling_index(0, || {
+            self.header.widget.layout(
+                ctx,
+                widget_id,
+                Vec2::new((available.x - reserved_width).max(0.0), available.y),
+                force_shrink,
+            )
+        });
+        // Same as the body, the header is moved past the scrollbar
+        if ctx.direction() == Direction::RightToLeft {
+            header_layout.translate_x(reserved_width);
+        }
+        let header_height = header_layout.bounds.height();
+
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => header_layout.bounds.width() + reserved_width,
+            SizeHint::Fill => available.x,
+        };
+
+        let scroll_layout = ctx
+            .with_sibling_index(1, || {
+                self.scroll.layout(
+                    ctx,
+                    widget_id,
+                    Vec2::new(width, (available.y - header_height).max(0.0)),
+                    force_shrink,
+                )
+            })
+            .translated(Vec2::new(0.0, header_height));
+
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => header_height + scroll_layout.bounds.height(),
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(
+            widget_id,
+            Vec2::new(width, height),
+            vec![header_layout, scroll_layout],
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        // The header is drawn outside the scroll transform, so it stays put.
+        self.header.widget.draw(ctx, &layout.children[0]);
+        self.scroll.draw(ctx, &layout.children[1]);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.header.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        );
+        self.scroll
+            .on_event_capture(ctx, &layout.children[1], cursor_position, events, status);
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.header
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, events, status);
+        self.scroll
+            .on_event(ctx, &layout.children[1], cursor_position, events, status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn row(id: &str) -> DynWidget {
+        ColoredBox::new(IdGen::key(id))
+            .min_size(Vec2::new(0.0, 20.0))
+            .hints(LayoutHints::fill_horizontal())
+            .build()
+    }
+
+    #[test]
+    fn test_header_matches_body_with_overlay_scrollbar() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let scroll = VScrollContainer::new(IdGen::key("body"), row("body"))
+            .hints(LayoutHints::fill())
+            .overlay_scrollbar(true);
+        let mut widget =
+            StickyHeaderContainer::with_scroll(IdGen::key("sticky"), row("header"), scroll).build();
+
+        let layout = ctx.layout_only(&mut widget);
+        let header = &layout.children[0];
+        let body = &layout.children[1].children[0];
+        assert_eq!(header.bounds.width(), 200.0);
+        assert_eq!(header.bounds.width(), body.bounds.width());
+    }
+
+    #[test]
+    fn test_header_matches_body_right_to_left() {
+        let mut ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        ctx.set_direction(Direction::RightToLeft);
+        let mut widget =
+            StickyHeaderContainer::new(IdGen::key("sticky"), row("header"), row("body")).build();
+
+        let layout = ctx.layout_only(&mut widget);
+        let header = &layout.children[0];
+        let body = &layout.children[1].children[0];
+        assert_eq!(header.bounds.left(), 16.0);
+        assert_eq!(header.bounds.left(), body.bounds.left());
+        assert_eq!(header.bounds.width(), body.bounds.width());
+    }
+}
@@ -0,0 +1,381 @@
+use epaint::{RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::{input::MouseButton, prelude::*};
+
+/// The direction a [`Table`] is sorted in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDir {
+    Ascending,
+    Descending,
+}
+
+impl SortDir {
+    pub fn toggled(self) -> Self {
+        match self {
+            SortDir::Ascending => SortDir::Descending,
+            SortDir::Descending => SortDir::Ascending,
+        }
+    }
+}
+
+/// A column of a [`Table`].
+#[derive(Builder)]
+pub struct TableColumn {
+    pub title: String,
+    /// Creates the widget for the cell of this column in the given row.
+    pub render: Box<dyn Fn(usize) -> DynWidget>,
+    /// When set, clicking the header of this column sorts the table by it.
+    #[builder(default = true)]
+    pub sortable: bool,
+}
+
+/// A table of rows with a header. Clicking a column header sorts by that
+/// column, and clicking a row selects it. Columns can be resized by dragging
+/// the boundaries between headers.
+///
+/// The table doesn't own the data: It fires `on_sort` and `on_row_selected`,
+/// and the app is in charge of passing the rows in the right order. The
+/// column widths, sort state and selected row are kept in the widget memory.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Table {
+    pub id: IdGen,
+    pub columns: Vec<TableColumn>,
+    pub num_rows: usize,
+    /// Fired with the column and direction when the user sorts the table.
+    #[builder(strip_option)]
+    pub on_sort: Option<Callback<(usize, SortDir)>>,
+    /// Fired with the index of a row when the user selects it.
+    #[builder(strip_option)]
+    pub on_row_selected: Option<Callback<usize>>,
+    #[builder(default = true)]
+    pub resizable_columns: bool,
+    #[builder(default = LayoutHints::fill_horizontal())]
+    pub hints: LayoutHints,
+    #[builder(default, strip_option)]
+    pub style_override: Option<TableStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// The grid laying out the headers and cells. Created during layout.
+    #[builder(skip)]
+    pub grid: Option<GridContainer>,
+    /// The row under the cursor, if any. Set during `on_event`.
+    #[builder(skip)]
+    pub hovered_row: Option<usize>,
+}
+
+#[derive(Builder, Clone)]
+pub struct TableStyle {
+    #[builder(default = color!("#303030"))]
+    pub header_fill: Color32,
+    #[builder(default = color!("#1e1e1e"))]
+    pub stripe_even: Color32,
+    #[builder(default = color!("#242424"))]
+    pub stripe_odd: Color32,
+    #[builder(default = color!("#2c2c2c"))]
+    pub hovered_fill: Color32,
+    #[builder(default = color!("#4a7fb5"))]
+    pub selected_fill: Color32,
+    #[builder(default = color!("#c0c0c0"))]
+    pub sort_indicator_color: Color32,
+    /// The space around the contents of each cell.
+    #[builder(default = Vec2::new(8.0, 4.0))]
+    pub cell_padding: Vec2,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct TableState {
+    pub sort: Option<(usize, SortDir)>,
+    pub selected_row: Option<usize>,
+}
+
+/// The size of the sort indicator, and the space reserved for it in headers.
+const SORT_INDICATOR_SIZE: f32 = 8.0;
+
+impl Table {
+    fn style(&self, ctx: &Context) -> TableStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    /// Returns the bounds of the header cell for column `col`.
+    fn header_rect(&self, grid_layout: &Layout, col: usize) -> Rect {
+        grid_layout.children[col].bounds
+    }
+
+    /// Returns the bounds of the header row, or of a row, spanning the whole
+    /// width of the table. Index 0 is the header, and data rows start at 1.
+    fn grid_row_rect(&self, layout: &Layout, grid_row: usize) -> Rect {
+        let grid_layout = &layout.children[0];
+        let columns = self.columns.len();
+        let cells = &grid_layout.children[grid_row * columns..(grid_row + 1) * columns];
+        let top = cells
+            .iter()
+            .map(|c| c.bounds.top())
+            .fold(f32::INFINITY, f32::min);
+        // Rows extend down to the next one, so there are no gaps between them
+        let bottom = match grid_layout.children.get((grid_row + 1) * columns) {
+            Some(next) => next.bounds.top(),
+            None => cells
+                .iter()
+                .map(|c| c.bounds.bottom())
+                .fold(f32::NEG_INFINITY, f32::max),
+        };
+        Rect::from_x_y_ranges(layout.bounds.x_range(), top..=bottom)
+    }
+
+    /// Returns the data row containing `pos`, if any.
+    fn row_at(&self, layout: &Layout, pos: Pos2) -> Option<usize> {
+        (0..self.num_rows).find(|row| self.grid_row_rect(layout, row + 1).contains(pos))
+    }
+
+    fn draw_sort_indicator(ctx: &Context, rect: Rect, dir: SortDir, color: Color32) {
+        let center = Pos2::new(rect.right() - SORT_INDICATOR_SIZE, rect.center().y);
+        let rect = Rect::from_center_size(
+            center,
+            Vec2::new(SORT_INDICATOR_SIZE, SORT_INDICATOR_SIZE / 2.0),
+        );
+        let points = match dir {
+            SortDir::Ascending => vec![rect.left_bottom(), rect.center_top(), rect.right_bottom()],
+            SortDir::Descending => vec![rect.left_top(), rect.right_top(), rect.center_bottom()],
+        };
+        ctx.painter().convex_polygon(points, color, Stroke::NONE);
+    }
+}
+
+impl Widget for Table {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let style = self.style(ctx);
+        let columns = self.columns.len();
+
+        let mut cells = Vec::with_capacity(columns * (self.num_rows + 1));
+        for (col, column) in self.columns.iter().enumerate() {
+            let header = BoxContainer::horizontal(
+                IdGen::key("title"),
+                vec![
+                    Text::new(column.title.clone()).build(),
+                    // Leaves room for the sort indicator
+                    Spacer::new(
+                        Vec2::new(SORT_INDICATOR_SIZE * 2.0, 0.0),
+                        LayoutHints::shrink(),
+                    )
+                    .build(),
+                ],
+            )
+            .separation(0.0)
+            .build();
+            cells.push(
+                MarginContainer::new(IdGen::key(("header", col)), header)
+                    .margin(style.cell_padding * 2.0)
+                    .build(),
+            );
+        }
+        for row in 0..self.num_rows {
+            for (col, column) in self.columns.iter().enumerate() {
+                cells.push(
+                    MarginContainer::new(IdGen::key(("cell", row, col)), (column.render)(row))
+                        .margin(style.cell_padding * 2.0)
+                        .build(),
+                );
+            }
+        }
+
+        let grid = self.grid.insert(
+            GridContainer::new(IdGen::key("grid"), columns, cells)
+                .spacing(Vec2::ZERO)
+                .resizable_columns(self.resizable_columns),
+        );
+        let grid_layout = grid.layout(ctx, widget_id, available, true);
+
+        let size = grid_layout.bounds.size();
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => size.x,
+            SizeHint::Fill => available.x.max(size.x),
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => size.y,
+            SizeHint::Fill => available.y,
+        };
+
+        Layout::with_children(widget_id, Vec2::new(width, height), vec![grid_layout])
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        if self.columns.is_empty() {
+            return;
+        }
+        let style = self.style(ctx);
+        let state = ctx.memory.get_or_default::<TableState>(layout.widget_id);
+        let (sort, selected_row) = (state.sort, state.selected_row);
+        drop(state);
+
+        let fill_row = |grid_row: usize, fill: Color32| {
+            ctx.painter().rect(RectShape {
+                rect: self.grid_row_rect(layout, grid_row),
+                rounding: Rounding::none(),
+                fill,
+                stroke: Stroke::NONE,
+            });
+        };
+        fill_row(0, style.header_fill);
+        for row in 0..self.num_rows {
+            let fill = if selected_row == Some(row) {
+                style.selected_fill
+            } else if self.hovered_row == Some(row) {
+                style.hovered_fill
+            } else if row % 2 == 0 {
+                style.stripe_even
+            } else {
+                style.stripe_odd
+            };
+            fill_row(row + 1, fill);
+        }
+
+        let Some(grid) = &mut self.grid else {
+            panic!("Layout should be called before draw");
+        };
+        grid.draw(ctx, &layout.children[0]);
+
+        if let Some((col, dir)) = sort {
+            if col < self.columns.len() {
+                let rect = self.header_rect(&layout.children[0], col);
+                Self::draw_sort_indicator(ctx, rect, dir, style.sort_indicator_color);
+            }
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let Some(grid) = &mut self.grid else {
+            panic!("Layout should be called before on_event_capture");
+        };
+        grid.on_event_capture(ctx, &layout.children[0], cursor_position, events, status);
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let Some(grid) = &mut self.grid else {
+            panic!("Layout should be called before on_event");
+        };
+        grid.on_event(ctx, &layout.children[0], cursor_position, events, status);
+        if self.columns.is_empty() {
+            return;
+        }
+
+        self.hovered_row = if layout.bounds.contains(cursor_position) {
+            self.row_at(layout, cursor_position)
+        } else {
+            None
+        };
+
+        if status.is_consumed() || !layout.bounds.contains(cursor_position) {
+            return;
+        }
+        let pressed = events
+            .iter()
+            .any(|ev| matches!(ev, Event::MousePressed(MouseButton::Primary)));
+        if !pressed {
+            return;
+        }
+
+        let header_col = (0..self.columns.len()).find(|col| {
+            self.header_rect(&layout.children[0], *col)
+                .contains(cursor_position)
+        });
+        let mut state = ctx
+            .memory
+            .get_mut_or_default::<TableState>(layout.widget_id);
+        if let Some(col) = header_col {
+            if self.columns[col].sortable {
+                let dir = match state.sort {
+                    Some((sorted_col, dir)) if sorted_col == col => dir.toggled(),
+                    _ => SortDir::Ascending,
+                };
+                state.sort = Some((col, dir));
+                if let Some(on_sort) = self.on_sort.take() {
+                    ctx.dispatch_callback(on_sort, (col, dir));
+                }
+                status.consume_event();
+            }
+        } else if let Some(row) = self.hovered_row {
+            state.selected_row = Some(row);
+            if let Some(on_row_selected) = self.on_row_selected.take() {
+                ctx.dispatch_callback(on_row_selected, row);
+            }
+            status.consume_event();
+        }
+    }
+}
+
+impl StyledWidget for Table {
+    type Style = TableStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_have_no_gaps() {
+        let ctx = Context::new(Vec2::new(400.0, 300.0), vec![]);
+        let mut table = Table::new(
+            IdGen::key("table"),
+            vec![
+                TableColumn::new(
+                    "Name".to_string(),
+                    Box::new(|row| Text::new(format!("Item {row}")).build()),
+                ),
+                TableColumn::new(
+                    "Size".to_string(),
+                    Box::new(|row| Text::new(row.to_string()).build()),
+                ),
+            ],
+            3,
+        );
+
+        let mut layout = table.layout(&ctx, WidgetId::null(), Vec2::new(400.0, 300.0), false);
+        layout.to_absolute(Vec2::ZERO);
+        let rows: Vec<Rect> = (0..4)
+            .map(|row| table.grid_row_rect(&layout, row))
+            .collect();
+        for pair in rows.windows(2) {
+            assert_eq!(pair[0].bottom(), pair[1].top());
+        }
+        assert_eq!(rows[0].width(), layout.bounds.width());
+    }
+}
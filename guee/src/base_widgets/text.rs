@@ -3,10 +3,11 @@ use crate::{
     input::{Event, EventStatus},
     layout::{Layout, LayoutHints, SizeHint, SizeHints},
     painter::{GueeGalley, GueeTextShape},
+    text_spacing::TextSpacing,
     widget::Widget,
-    widget_id::WidgetId,
+    widget_id::{IdGen, WidgetId},
 };
-use epaint::{Color32, FontId, Pos2, Stroke, Vec2};
+use epaint::{text::cursor::CCursor, Color32, FontId, Pos2, Stroke, Vec2};
 use guee_derives::Builder;
 
 #[derive(Clone, Builder)]
@@ -19,18 +20,37 @@ pub struct Text {
     color_override: Option<Color32>,
     #[builder(default = 14.0)]
     font_size: f32,
+    /// The index (in chars) of the letter of an access key, underlined while
+    /// Alt is held. See [`access_keys`](crate::access_keys).
+    #[builder(default, strip_option)]
+    access_key_index: Option<usize>,
+    /// The distance between lines, as a multiple of the font size. Uses the
+    /// font's default when not set.
+    #[builder(default, strip_option)]
+    line_height: Option<f32>,
+    /// Extra space between letters, in points.
+    #[builder(default)]
+    letter_spacing: f32,
 }
 
 impl Text {
     pub fn ensure_galley(&mut self, ctx: &Context, wrap_width: f32) -> GueeGalley {
-        let galley = ctx.painter().galley(
+        let galley = ctx.painter().galley_with_spacing(
             self.contents.clone(),
             FontId::proportional(self.font_size),
             wrap_width,
+            self.spacing(),
         );
         self.last_galley = Some(galley.clone());
         galley
     }
+
+    fn spacing(&self) -> TextSpacing {
+        TextSpacing {
+            line_height: self.line_height,
+            letter_spacing: self.letter_spacing,
+        }
+    }
 }
 
 impl Widget for Text {
@@ -42,7 +62,23 @@ impl Widget for Text {
         _force_shrink: bool, // ignore, always shrinked
     ) -> Layout {
         let galley = self.ensure_galley(ctx, available.x);
-        Layout::leaf(parent_id.with(&self.contents), galley.bounds().size())
+        let widget_id = IdGen::index(ctx.sibling_index())
+            .with("text")
+            .resolve(parent_id);
+        Layout::leaf(widget_id, galley.bounds().size())
+    }
+
+    fn measure(&mut self, ctx: &Context, _parent_id: WidgetId, available: Vec2) -> Vec2 {
+        let font_id = FontId::proportional(self.font_size);
+        if self.spacing().is_default() {
+            return ctx
+                .painter()
+                .measure_text(self.contents.clone(), font_id, available.x);
+        }
+        ctx.painter()
+            .galley_with_spacing(self.contents.clone(), font_id, available.x, self.spacing())
+            .bounds()
+            .size()
     }
 
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
@@ -50,9 +86,28 @@ impl Widget for Text {
             .last_galley
             .clone()
             .expect("Layout should be called before draw");
+        let pos = layout.bounds.left_top();
+
+        if let Some(idx) = self.access_key_index {
+            if ctx.input_state.modifiers.alt {
+                let epaint_galley = &galley.epaint_galley;
+                let start = epaint_galley.pos_from_ccursor(CCursor::new(idx));
+                let end = epaint_galley.pos_from_ccursor(CCursor::new(idx + 1));
+                let mut painter = ctx.painter();
+                let stroke = Stroke::new(1.0, painter.text_color);
+                painter.line_segment(
+                    [
+                        pos + Vec2::new(start.left(), start.bottom()),
+                        pos + Vec2::new(end.left(), start.bottom()),
+                    ],
+                    stroke,
+                );
+            }
+        }
+
         ctx.painter().text_with_galley(GueeTextShape {
             galley,
-            pos: layout.bounds.left_top(),
+            pos,
             underline: Stroke::NONE,
             angle: 0.0,
         });
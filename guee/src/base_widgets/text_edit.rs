@@ -1,19 +1,31 @@
-use epaint::{text::cursor::Cursor, Color32, FontId, Pos2, RectShape, Rounding, Stroke, Vec2};
+use std::time::Duration;
+
+use epaint::{
+    text::cursor::{CCursor, Cursor},
+    Color32, FontId, Pos2, Rect, RectShape, Rounding, Stroke, Vec2,
+};
 use guee_derives::Builder;
 use winit::event::VirtualKeyCode;
 
+use self::{
+    text_buffer::TextBuffer,
+    undo::{Edit, UndoHistory},
+};
 use crate::{
     callback::Callback,
     context::Context,
     input::{Event, EventStatus, MouseButton},
     layout::{Layout, LayoutHints, SizeHint},
     painter::{GueeGalley, GueeTextShape},
+    theme::StyledWidget,
     widget::Widget,
     widget_id::{IdGen, WidgetId},
 };
 
 pub mod text_buffer;
 
+pub mod undo;
+
 #[derive(Builder)]
 #[builder(widget)]
 pub struct TextEdit {
@@ -27,13 +39,134 @@ pub struct TextEdit {
     pub galley: Option<GueeGalley>,
     #[builder(strip_option)]
     pub on_changed: Option<Callback<String>>,
+    /// Fired with the current contents when the user submits the text. For
+    /// single-line text edits, this happens when pressing Enter. For multiline
+    /// ones, Enter inserts a newline and submitting requires Ctrl+Enter.
+    #[builder(strip_option)]
+    pub on_submit: Option<Callback<String>>,
+    /// Fired with the current contents when the text edit loses focus.
+    #[builder(strip_option)]
+    pub on_blur: Option<Callback<String>>,
+    /// When set, the text edit loses focus after being submitted.
+    #[builder(default)]
+    pub release_focus_on_submit: bool,
+    #[builder(default)]
+    pub multiline: bool,
     #[builder(default = 60.0)]
     pub min_width: f32,
+    /// The time, in seconds, the cursor stays visible (and then hidden) when
+    /// blinking. Set to zero to disable blinking.
+    #[builder(default = 0.53)]
+    pub cursor_blink_interval: f64,
+    /// The result of validating the current contents, set by the app. When
+    /// it's an error, the field is outlined with the theme's error color and
+    /// the message is shown below it. Unvalidated fields look normal.
+    #[builder(default, strip_option)]
+    pub validation: Option<Result<(), String>>,
+    #[builder(skip)]
+    pub error_galley: Option<GueeGalley>,
+    #[builder(default, strip_option)]
+    pub style_override: Option<TextEditStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+    /// Orders this widget in the Tab traversal, see
+    /// [`focus_order`](crate::focus_order). Widgets without an index come
+    /// after the ones with one, in layout order.
+    #[builder(default, strip_option)]
+    pub tab_index: Option<i32>,
+    /// When set, Tab skips this widget, but it can still be focused with the
+    /// mouse.
+    #[builder(default)]
+    pub skip_tab: bool,
+}
+
+/// Space between the field and the validation error message below it.
+const ERROR_MESSAGE_SPACING: f32 = 2.0;
+
+#[derive(Builder, Clone)]
+pub struct TextEditStyle {
+    #[builder(default = Color32::from_rgb(40, 40, 40))]
+    pub fill: Color32,
+    #[builder(default = Stroke::new(2.0, Color32::from_rgb(80, 80, 80)))]
+    pub stroke: Stroke,
+    #[builder(default = Rounding::same(1.0))]
+    pub rounding: Rounding,
+    #[builder(default = Color32::WHITE)]
+    pub cursor_color: Color32,
+}
+
+impl Default for TextEditStyle {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Default)]
 pub struct TextEditUiState {
+    /// The position of the caret. Only the char index is kept up to date, the
+    /// other coordinates are recomputed from the galley when needed.
     cursor: Cursor,
+    /// The time of the last user interaction with the text edit, as given by
+    /// `Context::time`. The blink cycle starts from here, so the cursor is
+    /// always visible while typing.
+    last_activity: f64,
+    undo_history: UndoHistory,
+    /// Horizontal scroll of the text, in pixels. Single-line text edits
+    /// scroll their contents so the cursor is always visible.
+    scroll_x: f32,
+    /// Set when a click places the cursor, so gaining focus from that click
+    /// doesn't move it to the end.
+    placed_by_click: bool,
+}
+
+/// Returns a cursor at the given char `index`.
+fn cursor_at(index: usize) -> Cursor {
+    Cursor {
+        ccursor: CCursor::new(index),
+        ..Default::default()
+    }
+}
+
+/// Returns the horizontal scroll that keeps `caret_x` within a text region of
+/// `visible_width`, scrolling as little as possible from `scroll`. The text,
+/// `content_width` pixels wide, never scrolls past its end.
+fn scroll_to_caret(scroll: f32, caret_x: f32, visible_width: f32, content_width: f32) -> f32 {
+    let max_scroll = (content_width - visible_width).max(0.0);
+    let scroll = if caret_x < scroll {
+        caret_x
+    } else if caret_x > scroll + visible_width {
+        caret_x - visible_width
+    } else {
+        scroll
+    };
+    scroll.clamp(0.0, max_scroll)
+}
+
+impl TextEdit {
+    /// The message to show below the field, if validation failed.
+    fn error_message(&self) -> Option<&str> {
+        match &self.validation {
+            Some(Err(message)) => Some(message.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The bounds of the text field itself, excluding the error message.
+    pub(crate) fn field_bounds(layout: &Layout) -> Rect {
+        layout.children[0].bounds
+    }
+
+    /// Sends the new `contents` to the `on_changed` callback, if any. The
+    /// contents of the text edit are owned by the app, so they only change
+    /// when the app reacts to the callback.
+    fn dispatch_changed(&mut self, ctx: &Context, contents: String) {
+        if let Some(on_changed) = self.on_changed.take() {
+            ctx.dispatch_callback(on_changed, contents);
+        }
+    }
 }
 
 impl Widget for TextEdit {
@@ -45,6 +178,9 @@ impl Widget for TextEdit {
         force_shrink: bool,
     ) -> Layout {
         let widget_id = self.id.resolve(parent_id);
+        if ctx.is_focused(widget_id) {
+            ctx.claim_text_input();
+        }
         let padding = self.padding;
 
         let size_hints = self.layout_hints.size_hints;
@@ -61,49 +197,139 @@ impl Widget for TextEdit {
         );
         self.galley = Some(galley.clone());
 
+        self.error_galley = self.error_message().map(|message| {
+            ctx.painter()
+                .galley(message.to_string(), FontId::proportional(12.0), width)
+        });
+        let error_height = self
+            .error_galley
+            .as_ref()
+            .map(|galley| galley.bounds().height() + ERROR_MESSAGE_SPACING)
+            .unwrap_or(0.0);
+
         let height = match size_hints.height {
             SizeHint::Shrink => galley.bounds().height() + 2.0 * padding.y,
-            SizeHint::Fill => available.y,
+            SizeHint::Fill => available.y - error_height,
         };
 
-        Layout::leaf(widget_id, Vec2::new(width, height))
+        let mut children = vec![Layout::leaf(widget_id.with("field"), Vec2::new(width, height))];
+        if self.error_galley.is_some() {
+            let message_size = Vec2::new(width, error_height - ERROR_MESSAGE_SPACING);
+            children.push(
+                Layout::leaf(widget_id.with("error"), message_size)
+                    .translated(Vec2::new(0.0, height + ERROR_MESSAGE_SPACING)),
+            );
+        }
+        Layout::with_children(widget_id, Vec2::new(width, height + error_height), children)
     }
 
     fn draw(&mut self, ctx: &Context, layout: &Layout) {
-        let ui_state = ctx
+        let focus_transition = ctx.focus_transition(layout.widget_id);
+        if focus_transition.lost() {
+            if let Some(on_blur) = self.on_blur.take() {
+                ctx.dispatch_callback(on_blur, self.contents.clone());
+            }
+        }
+
+        let mut ui_state = ctx
             .memory
             .get_mut_or(layout.widget_id, TextEditUiState::default());
         let focused = ctx.is_focused(layout.widget_id);
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
 
+        let field = Self::field_bounds(layout);
+        let error_color = ctx.theme.borrow().error_color;
+        let stroke = if self.error_galley.is_some() {
+            Stroke::new(style.stroke.width, error_color)
+        } else {
+            style.stroke
+        };
         ctx.painter().rect(RectShape {
-            rect: layout.bounds,
-            rounding: Rounding::same(1.0),
-            fill: Color32::from_rgb(40, 40, 40),
-            stroke: Stroke::new(2.0, Color32::from_rgb(80, 80, 80)),
+            rect: field,
+            rounding: style.rounding,
+            fill: style.fill,
+            stroke,
         });
+        if focused {
+            let focus_ring = ctx.theme.borrow().focus_ring.clone();
+            ctx.painter().focus_ring(field, &focus_ring);
+        }
+        if let Some(error_galley) = self.error_galley.clone() {
+            let mut painter = ctx.painter();
+            let old_color = painter.text_color;
+            painter.text_color = error_color;
+            painter.text_with_galley(GueeTextShape {
+                pos: layout.children[1].bounds.left_top(),
+                galley: error_galley,
+                underline: Stroke::NONE,
+                angle: 0.0,
+            });
+            painter.text_color = old_color;
+        }
 
-        let text_bounds = layout.bounds.shrink2(self.padding);
+        let text_bounds = field.shrink2(self.padding);
 
         let galley = self.galley.clone().unwrap();
-        ctx.painter().text_with_galley(GueeTextShape {
-            pos: text_bounds.left_top(),
-            galley: galley.clone(),
-            underline: Stroke::NONE,
-            angle: 0.0,
+        // The contents may have changed since the cursor was placed
+        let num_chars = galley.epaint_galley.job.text.chars().count();
+        if focus_transition.gained() && !ui_state.placed_by_click {
+            // Focused with the keyboard, continue typing at the end
+            ui_state.cursor = cursor_at(num_chars);
+        }
+        ui_state.placed_by_click = false;
+        let index = ui_state.cursor.ccursor.index.min(num_chars);
+        let cursor = galley.epaint_galley.from_ccursor(CCursor::new(index));
+        let caret_rect = galley.epaint_galley.pos_from_cursor(&cursor);
+
+        if self.multiline {
+            ui_state.scroll_x = 0.0;
+        } else {
+            ui_state.scroll_x = scroll_to_caret(
+                ui_state.scroll_x,
+                caret_rect.center().x,
+                text_bounds.width(),
+                galley.bounds().width(),
+            );
+        }
+        let text_pos = text_bounds.left_top() - Vec2::new(ui_state.scroll_x, 0.0);
+
+        // Leave room for the caret when it sits right at the edges
+        let clip_rect = text_bounds.expand2(Vec2::new(1.0, 0.0));
+        ctx.with_clip_rect(clip_rect, || {
+            ctx.painter().text_with_galley(GueeTextShape {
+                pos: text_pos,
+                galley: galley.clone(),
+                underline: Stroke::NONE,
+                angle: 0.0,
+            });
         });
 
-        if focused {
-            let cursor = galley.epaint_galley.cursor_end_of_row(&ui_state.cursor);
-            let cursor_rect = galley
-                .epaint_galley
-                .pos_from_cursor(&cursor)
+        let cursor_visible = if self.cursor_blink_interval > 0.0 && !ctx.reduced_motion() {
+            let elapsed = ctx.time() - ui_state.last_activity;
+            let phase = elapsed / self.cursor_blink_interval;
+            if focused {
+                let next_toggle = (phase.floor() + 1.0 - phase) * self.cursor_blink_interval;
+                ctx.request_repaint_after(Duration::from_secs_f64(next_toggle));
+            }
+            (phase as u64) % 2 == 0
+        } else {
+            true
+        };
+
+        if focused && cursor_visible {
+            let cursor_rect = caret_rect
                 .expand2(Vec2::new(1.0, 0.0))
-                .translate(text_bounds.left_top().to_vec2());
-            ctx.painter().rect(RectShape {
-                rect: cursor_rect,
-                rounding: Rounding::none(),
-                fill: Color32::WHITE,
-                stroke: Stroke::NONE,
+                .translate(text_pos.to_vec2());
+            ctx.with_clip_rect(clip_rect, || {
+                ctx.painter().rect(RectShape {
+                    rect: cursor_rect,
+                    rounding: Rounding::none(),
+                    fill: style.cursor_color,
+                    stroke: Stroke::NONE,
+                });
             });
         }
     }
@@ -120,38 +346,125 @@ impl Widget for TextEdit {
         events: &[Event],
         status: &mut EventStatus
     ) {
+        if !self.skip_tab {
+            ctx.register_focusable(layout.widget_id, Self::field_bounds(layout), self.tab_index);
+        }
         if status.is_consumed() {
             return;
         }
 
-        let mut _ui_state = ctx
+        let mut ui_state = ctx
             .memory
             .get_mut_or(layout.widget_id, TextEditUiState::default());
         let is_focused = ctx.is_focused(layout.widget_id);
-        let cursor_in_bounds = layout.bounds.contains(cursor_position);
-        let _galley = self.galley.as_ref().unwrap();
+        let field = Self::field_bounds(layout);
+        let cursor_in_bounds = field.contains(cursor_position);
+        let galley = self.galley.clone().unwrap();
+        // Edits are applied to a copy of the contents, and sent to the app at
+        // the end, so several edits in the same frame add up.
+        let mut contents = self.contents.clone();
+        let mut changed = false;
 
         for event in events {
+            if is_focused && matches!(event, Event::Text(_) | Event::KeyPressed(_)) {
+                ui_state.last_activity = ctx.time();
+            }
+            let num_chars = contents.chars().count();
+            let index = ui_state.cursor.ccursor.index.min(num_chars);
+            let cursor = cursor_at(index);
             match event {
                 Event::MousePressed(MouseButton::Primary) if cursor_in_bounds => {
+                    let text_pos = field.left_top() + self.padding
+                        - Vec2::new(ui_state.scroll_x, 0.0);
+                    ui_state.cursor = galley.cursor_from_pos(text_pos, cursor_position);
+                    ui_state.placed_by_click = true;
+                    ui_state.undo_history.break_group();
                     ctx.request_focus(layout.widget_id);
+                    ui_state.last_activity = ctx.time();
                     status.consume_event();
                 }
-                Event::Text(ch) if is_focused => {
-                    let mut contents = self.contents.clone();
-                    contents.push(*ch);
-                    if let Some(on_changed) = self.on_changed.take() {
-                        ctx.dispatch_callback(on_changed, contents);
+                Event::KeyPressed(VirtualKeyCode::Z | VirtualKeyCode::Y)
+                    if is_focused && ctx.input_state.modifiers.ctrl_or_command =>
+                {
+                    let redo = matches!(event, Event::KeyPressed(VirtualKeyCode::Y))
+                        || ctx.input_state.modifiers.shift;
+                    let restored = if redo {
+                        ui_state.undo_history.redo(&contents, cursor)
+                    } else {
+                        ui_state.undo_history.undo(&contents, cursor)
+                    };
+                    if let Some(entry) = restored {
+                        ui_state.cursor = entry.cursor;
+                        contents = entry.text;
+                        changed = true;
                     }
                     status.consume_event();
                 }
+                Event::Text(ch) if is_focused => {
+                    ui_state
+                        .undo_history
+                        .record(&contents, cursor, Edit::Insert(*ch));
+                    contents.insert_text(&ch.to_string(), index);
+                    ui_state.cursor = cursor_at(index + 1);
+                    changed = true;
+                    status.consume_event();
+                }
                 Event::KeyPressed(VirtualKeyCode::Back) if is_focused => {
-                    if !self.contents.is_empty() {
-                        let mut contents = self.contents.clone();
-                        contents.drain(self.contents.len() - 1..);
-                        if let Some(on_changed) = self.on_changed.take() {
-                            ctx.dispatch_callback(on_changed, contents);
+                    if index > 0 {
+                        ui_state
+                            .undo_history
+                            .record(&contents, cursor, Edit::Delete);
+                        contents.delete_char_range(index - 1..index);
+                        ui_state.cursor = cursor_at(index - 1);
+                        changed = true;
+                    }
+                    status.consume_event();
+                }
+                Event::KeyPressed(VirtualKeyCode::Delete) if is_focused => {
+                    if index < num_chars {
+                        ui_state
+                            .undo_history
+                            .record(&contents, cursor, Edit::Delete);
+                        contents.delete_char_range(index..index + 1);
+                        changed = true;
+                    }
+                    status.consume_event();
+                }
+                Event::KeyPressed(
+                    key @ (VirtualKeyCode::Left
+                    | VirtualKeyCode::Right
+                    | VirtualKeyCode::Home
+                    | VirtualKeyCode::End),
+                ) if is_focused =>
+                {
+                    let new_index = match key {
+                        VirtualKeyCode::Left => index.saturating_sub(1),
+                        VirtualKeyCode::Right => (index + 1).min(num_chars),
+                        VirtualKeyCode::Home => 0,
+                        _ => num_chars,
+                    };
+                    ui_state.cursor = cursor_at(new_index);
+                    ui_state.undo_history.break_group();
+                    status.consume_event();
+                }
+                Event::KeyPressed(VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter)
+                    if is_focused =>
+                {
+                    let submit = !self.multiline || ctx.input_state.modifiers.ctrl_or_command;
+                    if submit {
+                        if let Some(on_submit) = self.on_submit.take() {
+                            ctx.dispatch_callback(on_submit, contents.clone());
+                        }
+                        if self.release_focus_on_submit {
+                            ctx.release_focus(layout.widget_id);
                         }
+                    } else {
+                        ui_state
+                            .undo_history
+                            .record(&contents, cursor, Edit::Insert('\n'));
+                        contents.insert_text("\n", index);
+                        ui_state.cursor = cursor_at(index + 1);
+                        changed = true;
                     }
                     status.consume_event();
                 }
@@ -161,5 +474,61 @@ impl Widget for TextEdit {
                 _ => {}
             }
         }
+        drop(ui_state);
+
+        if changed {
+            self.dispatch_changed(ctx, contents);
+        }
+    }
+}
+
+impl StyledWidget for TextEdit {
+    type Style = TextEditStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_to_caret() {
+        // Text that fits never scrolls
+        assert_eq!(scroll_to_caret(0.0, 40.0, 100.0, 50.0), 0.0);
+        // Caret past the right edge scrolls just enough to show it
+        assert_eq!(scroll_to_caret(0.0, 150.0, 100.0, 200.0), 50.0);
+        // Caret before the left edge scrolls back
+        assert_eq!(scroll_to_caret(80.0, 30.0, 100.0, 200.0), 30.0);
+        // Shrinking text never leaves the scroll past the end
+        assert_eq!(scroll_to_caret(80.0, 60.0, 100.0, 120.0), 20.0);
+    }
+
+    #[test]
+    fn test_click_mid_string_then_type() {
+        let ctx = Context::new(Vec2::new(400.0, 100.0), vec![]);
+        let run_frame = |contents: &str, pos: Pos2, events: &[Event]| {
+            let (on_changed, tk) = ctx.create_internal_callback();
+            let mut text_edit =
+                TextEdit::new(IdGen::key("text"), contents.to_string()).on_changed(on_changed);
+            let mut layout =
+                text_edit.layout(&ctx, WidgetId::null(), Vec2::new(400.0, 100.0), false);
+            layout.to_absolute(Vec2::ZERO);
+            text_edit.on_event(&ctx, &layout, pos, events, &mut EventStatus::Ignored);
+            text_edit.draw(&ctx, &layout);
+            (text_edit, layout, ctx.poll_callback_result(tk))
+        };
+
+        // Click between the "b" and the "c"
+        let (text_edit, layout, _) = run_frame("abcd", Pos2::ZERO, &[]);
+        let galley = text_edit.galley.unwrap().epaint_galley;
+        let text_pos = TextEdit::field_bounds(&layout).left_top() + text_edit.padding;
+        let between = galley.pos_from_ccursor(CCursor::new(2)).center() + text_pos.to_vec2();
+        run_frame("abcd", between, &[Event::MousePressed(MouseButton::Primary)]);
+
+        let (_, _, changed) = run_frame("abcd", between, &[Event::Text('X')]);
+        assert_eq!(changed.as_deref(), Some("abXcd"));
+        // The caret moved past the inserted letter
+        let backspace = Event::KeyPressed(VirtualKeyCode::Back);
+        let (_, _, changed) = run_frame("abXcd", between, &[backspace]);
+        assert_eq!(changed.as_deref(), Some("abcd"));
     }
 }
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use epaint::text::cursor::Cursor;
+
+/// The maximum number of undo steps kept for each text edit. Older steps are
+/// forgotten.
+pub const MAX_UNDO_STEPS: usize = 100;
+
+/// The contents of a text edit at some point in time.
+#[derive(Clone, Debug)]
+pub struct UndoEntry {
+    pub text: String,
+    pub cursor: Cursor,
+}
+
+/// The kind of change made to the text, used to group changes into undo steps.
+#[derive(Clone, Copy, Debug)]
+pub enum Edit {
+    Insert(char),
+    Delete,
+}
+
+/// The undo and redo stacks of a text edit.
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: VecDeque<UndoEntry>,
+    redo: Vec<UndoEntry>,
+    /// Whether the last change was a character insertion that following
+    /// insertions can be merged with.
+    coalescing: bool,
+}
+
+impl UndoHistory {
+    /// Must be called right before making an `edit`, with the text and cursor
+    /// before the change. Consecutive insertions of non-whitespace characters
+    /// are merged into a single undo step.
+    pub fn record(&mut self, text: &str, cursor: Cursor, edit: Edit) {
+        self.redo.clear();
+        let merge = matches!(edit, Edit::Insert(ch) if !ch.is_whitespace());
+        if !(merge && self.coalescing) {
+            if self.undo.len() >= MAX_UNDO_STEPS {
+                self.undo.pop_front();
+            }
+            self.undo.push_back(UndoEntry {
+                text: text.to_string(),
+                cursor,
+            });
+        }
+        self.coalescing = merge;
+    }
+
+    /// Ends the current group of merged insertions, e.g. when the cursor is
+    /// moved somewhere else.
+    pub fn break_group(&mut self) {
+        self.coalescing = false;
+    }
+
+    /// Returns the state to restore to undo the last step, given the current
+    /// `text` and `cursor`, or `None` if there's nothing to undo.
+    pub fn undo(&mut self, text: &str, cursor: Cursor) -> Option<UndoEntry> {
+        let entry = self.undo.pop_back()?;
+        self.redo.push(UndoEntry {
+            text: text.to_string(),
+            cursor,
+        });
+        self.coalescing = false;
+        Some(entry)
+    }
+
+    /// Returns the state to restore to redo the last undone step, or `None` if
+    /// there's nothing to redo.
+    pub fn redo(&mut self, text: &str, cursor: Cursor) -> Option<UndoEntry> {
+        let entry = self.redo.pop()?;
+        self.undo.push_back(UndoEntry {
+            text: text.to_string(),
+            cursor,
+        });
+        self.coalescing = false;
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Types `text` at the end of `contents`, recording the edits.
+    fn type_text(history: &mut UndoHistory, contents: &mut String, text: &str) {
+        for ch in text.chars() {
+            history.record(contents, Cursor::default(), Edit::Insert(ch));
+            contents.push(ch);
+        }
+    }
+
+    #[test]
+    fn test_words_are_undone_together() {
+        let mut history = UndoHistory::default();
+        let mut contents = String::new();
+        type_text(&mut history, &mut contents, "hello world");
+
+        let mut undo = |contents: &mut String| {
+            *contents = history.undo(contents, Cursor::default()).unwrap().text;
+        };
+        undo(&mut contents);
+        assert_eq!(contents, "hello ");
+        undo(&mut contents);
+        assert_eq!(contents, "hello");
+        undo(&mut contents);
+        assert_eq!(contents, "");
+    }
+
+    #[test]
+    fn test_redo() {
+        let mut history = UndoHistory::default();
+        let mut contents = String::new();
+        type_text(&mut history, &mut contents, "abc");
+        history.record(&contents, Cursor::default(), Edit::Delete);
+        contents.pop();
+
+        contents = history.undo(&contents, Cursor::default()).unwrap().text;
+        assert_eq!(contents, "abc");
+        contents = history.redo(&contents, Cursor::default()).unwrap().text;
+        assert_eq!(contents, "ab");
+        assert!(history.redo(&contents, Cursor::default()).is_none());
+
+        // New edits clear the redo stack
+        history.undo(&contents, Cursor::default());
+        history.record(&contents, Cursor::default(), Edit::Insert('x'));
+        assert!(history.redo(&contents, Cursor::default()).is_none());
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = UndoHistory::default();
+        for i in 0..MAX_UNDO_STEPS * 2 {
+            history.record(&i.to_string(), Cursor::default(), Edit::Delete);
+        }
+        let mut steps = 0;
+        while history.undo("", Cursor::default()).is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_UNDO_STEPS);
+    }
+}
@@ -0,0 +1,105 @@
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// Uses a different [`Theme`] for its contents than for the rest of the UI,
+/// e.g. for a toolbar with its own look. The container itself has the same
+/// size as its contents.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct ThemedContainer {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    pub theme: Theme,
+}
+
+impl Widget for ThemedContainer {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let contents = &mut self.contents;
+        let contents_layout = ctx.with_theme(&mut self.theme, || {
+            contents
+                .widget
+                .layout(ctx, widget_id, available, force_shrink)
+        });
+        Layout::with_children(
+            widget_id,
+            contents_layout.bounds.size(),
+            vec![contents_layout],
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let contents = &mut self.contents;
+        ctx.with_theme(&mut self.theme, || {
+            contents.widget.draw(ctx, &layout.children[0])
+        });
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.contents.widget.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let contents = &mut self.contents;
+        ctx.with_theme(&mut self.theme, || {
+            contents.widget.on_event_capture(
+                ctx,
+                &layout.children[0],
+                cursor_position,
+                events,
+                status,
+            )
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let contents = &mut self.contents;
+        ctx.with_theme(&mut self.theme, || {
+            contents
+                .widget
+                .on_event(ctx, &layout.children[0], cursor_position, events, status)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_theme_restored() {
+        let mut ctx = Context::new(Vec2::new(100.0, 100.0), vec![]);
+        let mut theme = Theme::new_empty();
+        theme.text_color = Color32::WHITE;
+        ctx.set_theme(theme);
+
+        let mut local_theme = Theme::new_empty();
+        local_theme.text_color = Color32::RED;
+        ctx.with_theme(&mut local_theme, || {
+            assert_eq!(ctx.theme.borrow().text_color, Color32::RED);
+        });
+        assert_eq!(ctx.theme.borrow().text_color, Color32::WHITE);
+        assert_eq!(local_theme.text_color, Color32::RED);
+    }
+}
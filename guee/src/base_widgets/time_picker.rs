@@ -0,0 +1,281 @@
+use guee_derives::Builder;
+
+use crate::{callback::PollToken, prelude::*};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// How the hours are shown in a [`TimePicker`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClockMode {
+    /// Hours from 0 to 23.
+    #[default]
+    H24,
+    /// Hours from 1 to 12, plus a button to switch between AM and PM.
+    H12,
+}
+
+/// One of the fields of a [`TimePicker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeField {
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+/// An input for a time of the day, made of linked [`SpinBox`] fields for the
+/// hours, minutes and (optionally) seconds.
+///
+/// The fields wrap around: Stepping the minutes past 59 moves to the next
+/// hour, and stepping the hours past the end of the day goes back to the
+/// start.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct TimePicker {
+    pub id: IdGen,
+    /// The hours, minutes and seconds, with the hours from 0 to 23.
+    pub time: (u32, u32, u32),
+    /// Fired with the new `(hours, minutes, seconds)` when the user changes
+    /// any of the fields. The hours are always from 0 to 23.
+    #[builder(strip_option)]
+    pub on_changed: Option<Callback<(u32, u32, u32)>>,
+    #[builder(default)]
+    pub mode: ClockMode,
+    #[builder(default)]
+    pub show_seconds: bool,
+    /// Shown between the fields.
+    #[builder(default = ":".to_string())]
+    pub separator: String,
+    /// When set, the fields are padded to two digits, e.g. `09:05`.
+    #[builder(default = true)]
+    pub zero_pad: bool,
+    /// The labels of the AM / PM button, in [`ClockMode::H12`].
+    #[builder(default = ("AM".to_string(), "PM".to_string()))]
+    pub am_pm_labels: (String, String),
+    /// Created during layout.
+    #[builder(skip)]
+    pub contents: Option<DynWidget>,
+    /// The tokens for the fields and the AM / PM button. Created during
+    /// layout.
+    #[builder(skip)]
+    pub tokens: Option<TimePickerTokens>,
+}
+
+pub struct TimePickerTokens {
+    pub fields: Vec<(TimeField, PollToken<f64>)>,
+    pub am_pm: Option<PollToken<()>>,
+}
+
+/// Turns a number of seconds, which may be negative or longer than a day, into
+/// a time of the day.
+pub fn wrap_time(total_seconds: i64) -> (u32, u32, u32) {
+    let secs = total_seconds.rem_euclid(SECONDS_PER_DAY);
+    (
+        (secs / 3600) as u32,
+        (secs / 60 % 60) as u32,
+        (secs % 60) as u32,
+    )
+}
+
+/// Returns the hour shown in [`ClockMode::H12`], from 1 to 12.
+pub fn to_12h(hours: u32) -> u32 {
+    (hours + 11) % 12 + 1
+}
+
+impl TimePicker {
+    /// Creates a time picker for a `chrono` time.
+    #[cfg(feature = "dates")]
+    pub fn from_naive_time(id: IdGen, time: chrono::NaiveTime) -> Self {
+        use chrono::Timelike;
+        Self::new(id, (time.hour(), time.minute(), time.second()))
+    }
+
+    fn total_seconds(&self) -> i64 {
+        let (h, m, s) = self.time;
+        h as i64 * 3600 + m as i64 * 60 + s as i64
+    }
+
+    /// Returns the value shown by the given field.
+    fn field_value(&self, field: TimeField) -> u32 {
+        let (h, m, s) = self.time;
+        match field {
+            TimeField::Hours if self.mode == ClockMode::H12 => to_12h(h),
+            TimeField::Hours => h,
+            TimeField::Minutes => m,
+            TimeField::Seconds => s,
+        }
+    }
+
+    /// Returns the new time after a field is set to `value`. Values out of
+    /// the range of the field carry over to the next one.
+    pub fn set_field(&self, field: TimeField, value: f64) -> (u32, u32, u32) {
+        let value = value.round() as i64;
+        let delta = value - self.field_value(field) as i64;
+        let delta_seconds = match field {
+            TimeField::Hours if self.mode == ClockMode::H12 && delta.abs() > 1 => {
+                // Anything but a single step is a typed hour, which stays on
+                // the same half of the day.
+                let hours = self.time.0 as i64;
+                (hours - hours % 12 + value.rem_euclid(12) - hours) * 3600
+            }
+            TimeField::Hours => delta * 3600,
+            TimeField::Minutes => delta * 60,
+            TimeField::Seconds => delta,
+        };
+        wrap_time(self.total_seconds() + delta_seconds)
+    }
+
+    fn spin_box(&self, field: TimeField, on_changed: Callback<f64>) -> DynWidget {
+        let key = match field {
+            TimeField::Hours => "hours",
+            TimeField::Minutes => "minutes",
+            TimeField::Seconds => "seconds",
+        };
+        SpinBox::new(IdGen::key(key), self.field_value(field) as f64)
+            .num_decimals(0)
+            .min_digits(if self.zero_pad { 2 } else { 0 })
+            .on_changed(on_changed)
+            .build()
+    }
+}
+
+impl Widget for TimePicker {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let mut fields = vec![TimeField::Hours, TimeField::Minutes];
+        if self.show_seconds {
+            fields.push(TimeField::Seconds);
+        }
+
+        let mut children = Vec::new();
+        let mut field_tokens = Vec::new();
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                children.push(Text::new(self.separator.clone()).build());
+            }
+            let (cb, tk) = ctx.create_internal_callback();
+            children.push(self.spin_box(field, cb));
+            field_tokens.push((field, tk));
+        }
+
+        let mut am_pm = None;
+        if self.mode == ClockMode::H12 {
+            let (cb, tk) = ctx.create_internal_callback();
+            let (am, pm) = &self.am_pm_labels;
+            let label = if self.time.0 < 12 { am } else { pm };
+            let mut button = Button::with_label(label).padding(Vec2::new(6.0, 3.0));
+            button.id = IdGen::key("am_pm");
+            children.push(button.on_click(cb).build());
+            am_pm = Some(tk);
+        }
+        self.tokens = Some(TimePickerTokens {
+            fields: field_tokens,
+            am_pm,
+        });
+
+        let contents = self.contents.insert(
+            BoxContainer::horizontal(self.id, children)
+                .cross_align(Align::Center)
+                .build(),
+        );
+        contents
+            .widget
+            .layout(ctx, parent_id, available, force_shrink)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        if let Some(contents) = &mut self.contents {
+            contents.widget.draw(ctx, layout);
+        }
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        LayoutHints::shrink()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if let Some(contents) = &mut self.contents {
+            contents
+                .widget
+                .on_event_capture(ctx, layout, cursor_position, events, status);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if let Some(contents) = &mut self.contents {
+            contents
+                .widget
+                .on_event(ctx, layout, cursor_position, events, status);
+        }
+        let Some(tokens) = self.tokens.take() else {
+            return;
+        };
+
+        let mut new_time = None;
+        for (field, tk) in tokens.fields {
+            if let Some(value) = ctx.poll_callback_result(tk) {
+                new_time = Some(self.set_field(field, value));
+            }
+        }
+        if let Some(tk) = tokens.am_pm {
+            if ctx.poll_callback_result(tk).is_some() {
+                new_time = Some(wrap_time(self.total_seconds() + SECONDS_PER_DAY / 2));
+            }
+        }
+
+        if let Some(time) = new_time.filter(|time| *time != self.time) {
+            if let Some(on_changed) = self.on_changed.take() {
+                ctx.dispatch_callback(on_changed, time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_wrap_around() {
+        let picker = TimePicker::new(IdGen::key("time"), (10, 59, 30));
+        assert_eq!(picker.set_field(TimeField::Minutes, 60.0), (11, 0, 30));
+        assert_eq!(picker.set_field(TimeField::Seconds, 60.0), (11, 0, 0));
+
+        let picker = TimePicker::new(IdGen::key("time"), (0, 0, 0));
+        assert_eq!(picker.set_field(TimeField::Minutes, -1.0), (23, 59, 0));
+        assert_eq!(picker.set_field(TimeField::Hours, -1.0), (23, 0, 0));
+        assert_eq!(picker.set_field(TimeField::Hours, 24.0), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_12h_hours() {
+        // 11 PM, stepping up goes to midnight
+        let picker = TimePicker::new(IdGen::key("time"), (23, 0, 0)).mode(ClockMode::H12);
+        assert_eq!(picker.set_field(TimeField::Hours, 12.0), (0, 0, 0));
+
+        // 1 PM, typing 12 keeps it in the afternoon
+        let picker = TimePicker::new(IdGen::key("time"), (13, 0, 0)).mode(ClockMode::H12);
+        assert_eq!(picker.set_field(TimeField::Hours, 12.0), (12, 0, 0));
+        assert_eq!(picker.set_field(TimeField::Hours, 5.0), (17, 0, 0));
+        // Stepping down from 1 PM
+        assert_eq!(picker.set_field(TimeField::Hours, 0.0), (12, 0, 0));
+    }
+}
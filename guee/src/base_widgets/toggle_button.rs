@@ -0,0 +1,90 @@
+use guee_derives::Builder;
+
+use crate::prelude::*;
+
+/// A button that stays pressed while its `value` is on, like the bold or grid
+/// toggles in a toolbar. Clicking it fires `on_changed` with the negated
+/// value.
+///
+/// It's drawn using a [`ButtonStyle`], which can be themed separately from
+/// regular buttons by setting a style for `ToggleButton`. The `variant` of
+/// the inner button selects the `ToggleButton` style variant.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct ToggleButton {
+    /// The button that is drawn. Its `on_click` callback is overwritten on
+    /// every event pass, so a user-supplied `on_click` is ignored. Use
+    /// `on_changed` instead.
+    pub button: Button,
+    pub value: bool,
+    #[builder(strip_option)]
+    pub on_changed: Option<Callback<bool>>,
+}
+
+impl ToggleButton {
+    pub fn with_label(label: impl Into<String>, value: bool) -> Self {
+        Self::new(Button::with_label(label), value)
+    }
+}
+
+impl Widget for ToggleButton {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        self.button.layout(ctx, parent_id, available, force_shrink)
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        if self.button.style_override.is_none() {
+            self.button.style_override = ctx
+                .theme
+                .borrow()
+                .get_style_variant::<Self>(self.button.variant)
+                .cloned();
+        }
+        self.button.pressed |= self.value;
+        self.button.draw(ctx, layout);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.button.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.button
+            .on_event_capture(ctx, layout, cursor_position, events, status);
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        let (on_click, tk) = ctx.create_internal_callback();
+        self.button.on_click = Some(on_click);
+        self.button
+            .on_event(ctx, layout, cursor_position, events, status);
+
+        if ctx.poll_callback_result(tk).is_some() {
+            ctx.dispatch_callback_ref(&mut self.on_changed, !self.value);
+        }
+    }
+}
+
+impl StyledWidget for ToggleButton {
+    type Style = ButtonStyle;
+}
@@ -0,0 +1,269 @@
+use std::time::Duration;
+
+use epaint::RectShape;
+use guee_derives::Builder;
+
+use crate::{
+    popup::{place_popup, Side},
+    prelude::*,
+};
+
+/// Where a [`Tooltip`] bubble is shown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TooltipMode {
+    /// Next to the cursor, moving along with it.
+    FollowCursor,
+    /// On the given side of the widget, when it fits there.
+    Anchored(Side),
+}
+
+/// Shows a bubble with the `tip` widget while the pointer rests over the
+/// `contents`. The tip can be any widget, see [`Tooltip::text`] for the usual
+/// case of a short text. It's drawn on the overlay layer and doesn't get any
+/// events.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct Tooltip {
+    pub id: IdGen,
+    pub contents: DynWidget,
+    pub tip: DynWidget,
+    #[builder(default = TooltipMode::Anchored(Side::Below))]
+    pub mode: TooltipMode,
+    /// How long the pointer has to rest over the contents, in seconds.
+    #[builder(default = 0.5)]
+    pub show_delay: f64,
+    /// How long the tip stays after the pointer leaves, in seconds.
+    #[builder(default = 0.1)]
+    pub hide_delay: f64,
+    /// The tip is laid out with this width available, so long texts wrap.
+    #[builder(default = 300.0)]
+    pub max_width: f32,
+    #[builder(default, strip_option)]
+    pub style_override: Option<TooltipStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct TooltipStyle {
+    #[builder(default = color!("#1b1b1b"))]
+    pub fill: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#464646")))]
+    pub stroke: Stroke,
+    #[builder(default = epaint::Rounding::same(3.0))]
+    pub rounding: epaint::Rounding,
+    /// The space between the bubble and the tip.
+    #[builder(default = Vec2::new(6.0, 4.0))]
+    pub padding: Vec2,
+    /// In follow cursor mode, the distance from the cursor to the bubble, so
+    /// the cursor doesn't cover it.
+    #[builder(default = Vec2::new(12.0, 16.0))]
+    pub cursor_offset: Vec2,
+}
+
+impl Default for TooltipStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stored in the widget memory.
+#[derive(Default)]
+pub struct TooltipState {
+    /// When the pointer started resting over the contents.
+    pub hover_start: Option<f64>,
+    /// The last time the pointer was over the contents.
+    pub last_hovered: Option<f64>,
+    pub visible: bool,
+    /// The last cursor position, for follow cursor mode.
+    pub cursor: Pos2,
+}
+
+impl Tooltip {
+    /// A tooltip showing `text`.
+    pub fn text(id: IdGen, contents: DynWidget, text: impl Into<String>) -> Self {
+        Self::new(id, contents, Text::new(text.into()).build())
+    }
+
+    fn style(&self, ctx: &Context) -> TooltipStyle {
+        ctx.theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant)
+    }
+
+    /// Returns the bounds of the bubble around the tip, kept inside the
+    /// screen.
+    fn bubble_rect(&self, ctx: &Context, layout: &Layout, style: &TooltipStyle) -> Rect {
+        let tip_size = layout.children[1].bounds.size();
+        let size = tip_size + style.padding * 2.0;
+        let screen = Rect::from_min_size(Pos2::ZERO, ctx.screen_size());
+        let pos = match self.mode {
+            TooltipMode::FollowCursor => {
+                let cursor = ctx
+                    .state::<TooltipState>(layout.widget_id)
+                    .get_or_default()
+                    .cursor;
+                // The bubble goes below and to the right of the cursor, or
+                // above it when there's no room below
+                let offset = style.cursor_offset;
+                let anchor = Rect::from_min_max(cursor + Vec2::new(offset.x, 0.0), cursor + offset);
+                place_popup(anchor, size, Side::Below, Align::Start, screen)
+            }
+            TooltipMode::Anchored(side) => {
+                place_popup(layout.bounds, size, side, Align::Center, screen)
+            }
+        };
+        Rect::from_min_size(pos, size)
+    }
+}
+
+impl Widget for Tooltip {
+    fn layout(
+        &mut self,
+        ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let contents_layout = self
+            .contents
+            .widget
+            .layout(ctx, widget_id, available, force_shrink);
+        // The tip is moved to its place when drawn
+        let tip_available = Vec2::new(self.max_width, ctx.screen_size().y);
+        let tip_layout = self
+            .tip
+            .widget
+            .layout(ctx, widget_id.with("tip"), tip_available, true);
+
+        Layout::with_children(
+            widget_id,
+            contents_layout.bounds.size(),
+            vec![contents_layout, tip_layout],
+        )
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        self.contents.widget.draw(ctx, &layout.children[0]);
+
+        let visible = ctx
+            .state::<TooltipState>(layout.widget_id)
+            .try_get()
+            .map_or(false, |state| state.visible);
+        if !visible {
+            return;
+        }
+
+        let style = self.style(ctx);
+        let bubble = self.bubble_rect(ctx, layout, &style);
+        let tip_layout = &layout.children[1];
+        let tip_layout = tip_layout
+            .clone()
+            .translated(bubble.min + style.padding - tip_layout.bounds.min);
+
+        let prev_overlay = ctx.painter().set_overlay(true);
+        ctx.painter().rect(RectShape {
+            rect: bubble,
+            rounding: style.rounding,
+            fill: style.fill,
+            stroke: style.stroke,
+        });
+        self.tip.widget.draw(ctx, &tip_layout);
+        ctx.painter().set_overlay(prev_overlay);
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.contents.widget.layout_hints()
+    }
+
+    fn on_event_capture(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents.widget.on_event_capture(
+            ctx,
+            &layout.children[0],
+            cursor_position,
+            events,
+            status,
+        )
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        self.contents
+            .widget
+            .on_event(ctx, &layout.children[0], cursor_position, events, status);
+
+        let now = ctx.time();
+        let hovered = ctx.is_pointer_over(layout.widget_id, layout.bounds, cursor_position);
+        let mut state = ctx
+            .state::<TooltipState>(layout.widget_id)
+            .get_mut_or_default();
+        state.cursor = cursor_position;
+
+        let wait = if hovered {
+            let start = *state.hover_start.get_or_insert(now);
+            state.last_hovered = Some(now);
+            let elapsed = now - start;
+            state.visible = elapsed >= self.show_delay;
+            (!state.visible).then_some(self.show_delay - elapsed)
+        } else {
+            state.hover_start = None;
+            let since_hovered = state.last_hovered.map_or(f64::INFINITY, |t| now - t);
+            state.visible &= since_hovered < self.hide_delay;
+            state.visible.then_some(self.hide_delay - since_hovered)
+        };
+        // Wake up when the tip has to be shown or hidden, even if the pointer
+        // doesn't move
+        if let Some(wait) = wait {
+            ctx.request_repaint_after(Duration::from_secs_f64(wait.max(0.0)));
+        }
+    }
+}
+
+impl StyledWidget for Tooltip {
+    type Style = TooltipStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchored_bubble_placement() {
+        let ctx = Context::new(Vec2::new(400.0, 300.0), vec![]);
+        let style = TooltipStyle::new().padding(Vec2::new(5.0, 5.0));
+        let mut tooltip = Tooltip::new(
+            IdGen::key("tooltip"),
+            ColoredBox::new(IdGen::key("contents"))
+                .min_size(Vec2::new(40.0, 20.0))
+                .build(),
+            ColoredBox::new(IdGen::key("tip"))
+                .min_size(Vec2::new(100.0, 30.0))
+                .build(),
+        )
+        .mode(TooltipMode::Anchored(Side::Below));
+
+        let mut layout = tooltip.layout(&ctx, WidgetId::null(), Vec2::new(400.0, 300.0), false);
+        layout.to_absolute(Vec2::new(100.0, 100.0));
+        let bubble = tooltip.bubble_rect(&ctx, &layout, &style);
+        assert_eq!(bubble.size(), Vec2::new(110.0, 40.0));
+        assert_eq!(bubble.top(), layout.bounds.bottom());
+        assert_eq!(bubble.center().x, layout.bounds.center().x);
+    }
+}
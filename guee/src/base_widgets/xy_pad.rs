@@ -0,0 +1,232 @@
+use std::ops::RangeInclusive;
+
+use epaint::{CircleShape, RectShape, Rounding};
+use guee_derives::Builder;
+
+use crate::{input::MouseButton, prelude::*, snap::snap_unless_alt};
+
+/// A 2D area where the user drags a crosshair to edit two values at once.
+/// Values along the `y` axis grow upwards. Clicking anywhere in the pad moves
+/// the crosshair to that point.
+#[derive(Builder)]
+#[builder(widget)]
+pub struct XYPad {
+    pub id: IdGen,
+    pub x: f64,
+    pub y: f64,
+    #[builder(default = -1.0..=1.0)]
+    pub x_range: RangeInclusive<f64>,
+    #[builder(default = -1.0..=1.0)]
+    pub y_range: RangeInclusive<f64>,
+    #[builder(default)]
+    pub hints: LayoutHints,
+    /// The size of the pad along the axes not set to fill.
+    #[builder(default = Vec2::splat(120.0))]
+    pub size: Vec2,
+    /// The number of cells the gridlines divide each axis into.
+    #[builder(default = 4)]
+    pub grid_divisions: u32,
+    /// When set, the crosshair snaps to a grid with cells of this size, in
+    /// points, aligned with the top left corner of the pad. Holding Alt
+    /// while dragging disables snapping.
+    #[builder(default, strip_option)]
+    pub snap: Option<Vec2>,
+    #[builder(strip_option)]
+    pub on_changed: Option<Callback<(f64, f64)>>,
+    #[builder(default, strip_option)]
+    pub style_override: Option<XYPadStyle>,
+    /// The named theme style variant to use, see [`Theme::set_style_variant`].
+    ///
+    /// [`Theme::set_style_variant`]: crate::theme::Theme::set_style_variant
+    #[builder(default, strip_option)]
+    pub variant: Option<&'static str>,
+}
+
+#[derive(Builder, Clone)]
+pub struct XYPadStyle {
+    #[builder(default = color!("#212121"))]
+    pub background: Color32,
+    #[builder(default = Stroke::new(1.0, color!("#303030")))]
+    pub grid_stroke: Stroke,
+    #[builder(default = Stroke::new(1.0, color!("#4a7fb5")))]
+    pub crosshair_stroke: Stroke,
+    #[builder(default = color!("#d0d0d0"))]
+    pub handle_fill: Color32,
+    #[builder(default = 4.0)]
+    pub handle_radius: f32,
+}
+
+impl Default for XYPadStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_fraction(value: f64, range: &RangeInclusive<f64>) -> f32 {
+    let (start, end) = (*range.start(), *range.end());
+    if end > start {
+        ((value - start) / (end - start)).clamp(0.0, 1.0) as f32
+    } else {
+        0.0
+    }
+}
+
+fn from_fraction(t: f32, range: &RangeInclusive<f64>) -> f64 {
+    let (start, end) = (*range.start(), *range.end());
+    start + (end - start) * t.clamp(0.0, 1.0) as f64
+}
+
+impl XYPad {
+    /// Returns the position of the crosshair for the given values.
+    fn values_to_pos(&self, x: f64, y: f64, bounds: Rect) -> Pos2 {
+        Pos2::new(
+            bounds.left() + bounds.width() * to_fraction(x, &self.x_range),
+            bounds.bottom() - bounds.height() * to_fraction(y, &self.y_range),
+        )
+    }
+
+    /// Returns the values for a crosshair at `pos`, clamped to the pad.
+    fn pos_to_values(&self, pos: Pos2, bounds: Rect) -> (f64, f64) {
+        let tx = (pos.x - bounds.left()) / bounds.width().max(1.0);
+        let ty = (bounds.bottom() - pos.y) / bounds.height().max(1.0);
+        (
+            from_fraction(tx, &self.x_range),
+            from_fraction(ty, &self.y_range),
+        )
+    }
+}
+
+impl Widget for XYPad {
+    fn layout(
+        &mut self,
+        _ctx: &Context,
+        parent_id: WidgetId,
+        available: Vec2,
+        force_shrink: bool,
+    ) -> Layout {
+        let widget_id = self.id.resolve(parent_id);
+        let width = match self.hints.size_hints.width.or_force(force_shrink) {
+            SizeHint::Shrink => self.size.x,
+            SizeHint::Fill => available.x,
+        };
+        let height = match self.hints.size_hints.height.or_force(force_shrink) {
+            SizeHint::Shrink => self.size.y,
+            SizeHint::Fill => available.y,
+        };
+        Layout::leaf(widget_id, Vec2::new(width, height))
+    }
+
+    fn draw(&mut self, ctx: &Context, layout: &Layout) {
+        let style = ctx
+            .theme
+            .borrow()
+            .resolve_style::<Self>(self.style_override.as_ref(), self.variant);
+        let bounds = layout.bounds;
+        let mut painter = ctx.painter();
+        painter.rect(RectShape {
+            rect: bounds,
+            rounding: Rounding::none(),
+            fill: style.background,
+            stroke: style.grid_stroke,
+        });
+
+        for i in 1..self.grid_divisions {
+            let t = i as f32 / self.grid_divisions as f32;
+            let x = bounds.left() + bounds.width() * t;
+            let y = bounds.top() + bounds.height() * t;
+            painter.line_segment(
+                [Pos2::new(x, bounds.top()), Pos2::new(x, bounds.bottom())],
+                style.grid_stroke,
+            );
+            painter.line_segment(
+                [Pos2::new(bounds.left(), y), Pos2::new(bounds.right(), y)],
+                style.grid_stroke,
+            );
+        }
+
+        let pos = self.values_to_pos(self.x, self.y, bounds);
+        painter.line_segment(
+            [
+                Pos2::new(pos.x, bounds.top()),
+                Pos2::new(pos.x, bounds.bottom()),
+            ],
+            style.crosshair_stroke,
+        );
+        painter.line_segment(
+            [
+                Pos2::new(bounds.left(), pos.y),
+                Pos2::new(bounds.right(), pos.y),
+            ],
+            style.crosshair_stroke,
+        );
+        painter.circle(CircleShape {
+            center: pos,
+            radius: style.handle_radius,
+            fill: style.handle_fill,
+            stroke: style.crosshair_stroke,
+        });
+    }
+
+    fn layout_hints(&self) -> LayoutHints {
+        self.hints
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &Context,
+        layout: &Layout,
+        cursor_position: Pos2,
+        events: &[Event],
+        status: &mut EventStatus,
+    ) {
+        if status.is_consumed() {
+            return;
+        }
+        let bounds = layout.bounds;
+        let clicked = bounds.contains(cursor_position)
+            && events
+                .iter()
+                .any(|ev| matches!(ev, Event::MousePressed(MouseButton::Primary)));
+        let dragging = ctx.claim_drag_event(layout.widget_id, bounds, MouseButton::Primary);
+
+        if clicked || dragging {
+            status.consume_event();
+            let pos = match self.snap {
+                Some(grid) => snap_unless_alt(
+                    cursor_position,
+                    grid,
+                    bounds.min.to_vec2(),
+                    &ctx.input_state.modifiers,
+                ),
+                None => cursor_position,
+            };
+            let values = self.pos_to_values(pos, bounds);
+            if values != (self.x, self.y) {
+                ctx.dispatch_callback_ref(&mut self.on_changed, values);
+            }
+        }
+    }
+}
+
+impl StyledWidget for XYPad {
+    type Style = XYPadStyle;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pos_to_values() {
+        let pad = XYPad::new(IdGen::key("pad"), 0.0, 0.0).x_range(0.0..=10.0);
+        let bounds = Rect::from_min_size(Pos2::new(10.0, 10.0), Vec2::splat(100.0));
+
+        assert_eq!(pad.pos_to_values(Pos2::new(60.0, 35.0), bounds), (5.0, 0.5));
+        assert_eq!(pad.values_to_pos(5.0, 0.5, bounds), Pos2::new(60.0, 35.0));
+        // Positions outside the pad are clamped
+        assert_eq!(
+            pad.pos_to_values(Pos2::new(-50.0, 500.0), bounds),
+            (0.0, -1.0)
+        );
+    }
+}
@@ -1,25 +1,35 @@
 use epaint::ahash::HashMap;
 use std::{
     any::{Any, TypeId},
+    cell::RefCell,
     marker::PhantomData,
+    rc::Rc,
 };
 
+use crate::callback_accessor::AccessorRegistry;
+
 /// A `PollToken` is returned when creating an internal callback. The same token
 /// can then be reused to try fetch the result of the individual callback once
 /// it runs.
 ///
 /// The token is a cheaply copyable handle and can be freely shared or stored
-/// around, but one should be careful to store them across frames because
-/// callback data is removed from previous frames.
+/// around, but it's only valid during the frame it was created in, because
+/// callback data is removed from previous frames. Tokens are stamped with the
+/// frame they belong to, so polling a stale token returns `None` instead of
+/// the payload of an unrelated callback.
 // #[derive(Copy, Clone)] <- see below
 pub struct PollToken<T> {
     token: usize,
+    generation: u64,
     _phantom: PhantomData<T>,
 }
 
 impl<P> PollToken<P> {
     pub fn as_raw(&self) -> RawPollToken {
-        RawPollToken { token: self.token }
+        RawPollToken {
+            token: self.token,
+            generation: self.generation,
+        }
     }
 }
 
@@ -27,19 +37,53 @@ impl<P> PollToken<P> {
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub struct RawPollToken {
     token: usize,
+    generation: u64,
 }
 
 /// An external callback. See [`Callback`]
 pub struct ExternalCallback<P> {
-    pub input_type: TypeId,
+    /// The type of the state this callback takes as input. When `None`, the
+    /// callback takes the root state. Otherwise, the input is obtained from
+    /// the root state through the [`AccessorRegistry`].
+    pub input_type: Option<TypeId>,
     #[allow(clippy::type_complexity)]
     pub f: Box<dyn FnOnce(&mut dyn Any, P)>,
 }
 
+/// An external callback that can be dispatched any number of times. See
+/// [`Callback::from_fn_mut`].
+pub struct SharedCallback<P> {
+    /// See [`ExternalCallback::input_type`].
+    pub input_type: Option<TypeId>,
+    #[allow(clippy::type_complexity)]
+    pub f: Rc<RefCell<dyn FnMut(&mut dyn Any, P)>>,
+}
+
+impl<P: 'static> SharedCallback<P> {
+    /// Returns a one-shot external callback that runs this shared function.
+    fn to_external(&self) -> ExternalCallback<P> {
+        let f = self.f.clone();
+        ExternalCallback {
+            input_type: self.input_type,
+            f: Box::new(move |input: &mut dyn Any, p: P| (*f.borrow_mut())(input, p)),
+        }
+    }
+}
+
 /// A type-erased callback function. Can be internal or external. Most users
 /// will want to use external callbacks. Widget authors might want to use
 /// internal callbacks to connect child widgets to their parents. See the docs
 /// on each individual variant for more details.
+///
+/// Widgets own their callbacks, and dispatching one with
+/// [`Context::dispatch_callback`] consumes it. That's why widgets typically
+/// `take()` their callback fields when firing them: An external callback made
+/// from a `FnOnce` can only run once. Widgets that may fire a callback several
+/// times during the same frame should use [`Context::dispatch_callback_ref`]
+/// instead, which keeps the callback around whenever it can be reused.
+///
+/// [`Context::dispatch_callback`]: crate::context::Context::dispatch_callback
+/// [`Context::dispatch_callback_ref`]: crate::context::Context::dispatch_callback_ref
 pub enum Callback<P> {
     /// An external callback is provided by end-user, and its invocation is
     /// deferred until the end of each frame.
@@ -55,19 +99,132 @@ pub enum Callback<P> {
     /// is stored internally so the parent widget who set up the callback can
     /// fetch it back via its corresponding [`PollToken`]
     Internal { token: PollToken<P> },
+    /// Like an external callback, but made from a `FnMut`, so it can be
+    /// dispatched multiple times in the same frame, and every dispatch runs.
+    Shared(SharedCallback<P>),
+    /// A callback built from other callbacks, see [`Callback::and_then`] and
+    /// [`Callback::map`]. When dispatched, it dispatches its parts.
+    #[allow(clippy::type_complexity)]
+    Combined(Box<dyn FnOnce(&mut DispatchedCallbackStorage, P)>),
 }
 
 impl<P> Callback<P> {
+    /// Creates an external callback from a function taking some state `T`.
+    /// When `T` is not the app's root state, an accessor for it must have been
+    /// registered in the [`AccessorRegistry`], or invoking the callback will
+    /// panic.
+    pub fn from_fn<T: 'static>(f: impl FnOnce(&mut T, P) + 'static) -> Self {
+        let closure = move |input: &mut dyn Any, p: P| {
+            f(input.downcast_mut().expect("Failed downcast"), p);
+        };
+        Callback::External(ExternalCallback {
+            input_type: Some(TypeId::of::<T>()),
+            f: Box::new(closure),
+        })
+    }
+
+    /// Like [`Callback::from_fn`], but takes a `FnMut`. The callback is not
+    /// used up when dispatched, see [`Callback::try_clone`].
+    pub fn from_fn_mut<T: 'static>(mut f: impl FnMut(&mut T, P) + 'static) -> Self {
+        let closure = move |input: &mut dyn Any, p: P| {
+            f(input.downcast_mut().expect("Failed downcast"), p);
+        };
+        Callback::Shared(SharedCallback {
+            input_type: Some(TypeId::of::<T>()),
+            f: Rc::new(RefCell::new(closure)),
+        })
+    }
+
+    /// Clones the callback, if possible. Internal and shared callbacks are
+    /// cheap to clone, but external callbacks made from a `FnOnce` can't be
+    /// cloned, and return `None`.
+    pub fn try_clone(&self) -> Option<Callback<P>> {
+        match self {
+            Callback::External(_) | Callback::Combined(_) => None,
+            Callback::Internal { token } => Some(Callback::Internal { token: *token }),
+            Callback::Shared(shared) => Some(Callback::Shared(SharedCallback {
+                input_type: shared.input_type,
+                f: shared.f.clone(),
+            })),
+        }
+    }
+
+    /// Returns a callback that runs `self` and then `other`, both with the
+    /// same payload.
+    pub fn and_then(self, other: Callback<P>) -> Callback<P>
+    where
+        P: Clone + 'static,
+    {
+        Callback::all(vec![self, other])
+    }
+
+    /// Returns a callback that runs all the given `callbacks` in order, with
+    /// the same payload.
+    pub fn all(callbacks: Vec<Callback<P>>) -> Callback<P>
+    where
+        P: Clone + 'static,
+    {
+        Callback::Combined(Box::new(
+            move |storage: &mut DispatchedCallbackStorage, p: P| {
+                for callback in callbacks {
+                    storage.dispatch_callback(callback, p.clone());
+                }
+            },
+        ))
+    }
+
+    /// Adapts this callback to take a different payload, which is turned into
+    /// the payload of this one by `f`. Shared callbacks stay reusable.
+    pub fn map<Q: 'static>(self, f: impl Fn(Q) -> P + 'static) -> Callback<Q>
+    where
+        P: 'static,
+    {
+        match self {
+            Callback::External(ext) => {
+                let inner = ext.f;
+                Callback::External(ExternalCallback {
+                    input_type: ext.input_type,
+                    f: Box::new(move |input: &mut dyn Any, q: Q| inner(input, f(q))),
+                })
+            }
+            Callback::Shared(shared) => {
+                let inner = shared.f;
+                let closure = move |input: &mut dyn Any, q: Q| (*inner.borrow_mut())(input, f(q));
+                Callback::Shared(SharedCallback {
+                    input_type: shared.input_type,
+                    f: Rc::new(RefCell::new(closure)),
+                })
+            }
+            other => Callback::Combined(Box::new(
+                move |storage: &mut DispatchedCallbackStorage, q: Q| {
+                    storage.dispatch_callback(other, f(q));
+                },
+            )),
+        }
+    }
+
+    /// Returns the `PollToken` of an internal callback, or `None` for external
+    /// callbacks. Widgets can use this to read back the response to an
+    /// internal callback after dispatching it.
+    pub fn poll_token(&self) -> Option<PollToken<P>> {
+        match self {
+            Callback::External(_) | Callback::Shared(_) | Callback::Combined(_) => None,
+            Callback::Internal { token } => Some(*token),
+        }
+    }
+
     /// If self is an internal callback, clones it. Cloning an internal callback
     /// is a very cheap operation, hence the 'copy' naming. External callbacks
     /// store closures and can't be cloned.
     ///
     /// # Panics
     ///
-    /// This function panic when given an external callback.
+    /// This function panic when given any other kind of callback.
     pub fn copy_internal(&self) -> Callback<P> {
         match self {
-            Callback::External(_) => panic!("Called clone_internal with an external callback"),
+            Callback::External(_) | Callback::Shared(_) | Callback::Combined(_) => {
+                panic!("Called clone_internal with an external callback")
+            }
             Callback::Internal { token } => Callback::Internal { token: *token },
         }
     }
@@ -81,6 +238,8 @@ impl<P> Callback<P> {
 /// on_event, to enqueue some things to be called later, when the app state can
 /// be accessed.
 pub struct DispatchedExternalCallback {
+    // The input type of the callback. See `ExternalCallback::input_type`
+    input_type: Option<TypeId>,
     // The type-erased external callback
     callback: Box<dyn Any>,
     // The stored payload to call the callback with
@@ -99,6 +258,7 @@ impl DispatchedExternalCallback {
             (cb.f)(input, p);
         };
         DispatchedExternalCallback {
+            input_type: c.input_type,
             callback: Box::new(c),
             payload: Box::new(payload),
             invoker: Box::new(closure),
@@ -118,9 +278,16 @@ pub struct DispatchedCallbackStorage {
     /// Maps poll tokens to the corresponding (type-erased) payload data
     /// returned by the function. Cleared at the end of the frame.
     pub internal: HashMap<RawPollToken, Box<dyn Any>>,
+    /// Maps poll tokens to the (type-erased) responses written by the widget
+    /// that polled an internal callback. Cleared at the end of the frame. See
+    /// [`DispatchedCallbackStorage::respond`].
+    pub responses: HashMap<RawPollToken, Box<dyn Any>>,
     /// The integer id for the next PollToken to be returned. Reset at the end
     /// of the frame.
     pub next_token: usize,
+    /// Counts the frames, so tokens from previous frames can be told apart.
+    /// Bumped at the end of the frame.
+    pub generation: u64,
 }
 
 impl DispatchedCallbackStorage {
@@ -130,19 +297,73 @@ impl DispatchedCallbackStorage {
                 .external
                 .push(DispatchedExternalCallback::new(ext, payload)),
             Callback::Internal { token } => {
+                if self.is_stale(token.as_raw()) {
+                    return;
+                }
                 self.internal.insert(token.as_raw(), Box::new(payload));
             }
+            Callback::Shared(shared) => self.external.push(DispatchedExternalCallback::new(
+                shared.to_external(),
+                payload,
+            )),
+            Callback::Combined(f) => f(self, payload),
         }
     }
 
+    /// Dispatches the callback in `c`, if any, leaving it in place when it can
+    /// be dispatched again. External callbacks made from a `FnOnce` can only
+    /// run once, so they are taken out of `c`.
+    pub fn dispatch_callback_ref<P: 'static>(&mut self, c: &mut Option<Callback<P>>, payload: P) {
+        let callback = match c.as_ref().and_then(Callback::try_clone) {
+            Some(callback) => callback,
+            None => match c.take() {
+                Some(callback) => callback,
+                None => return,
+            },
+        };
+        self.dispatch_callback(callback, payload);
+    }
+
     /// Call at the end of the frame to run any pending external callbacks and
-    /// clean up callback storage for the next frame.
-    pub fn end_frame(&mut self, state: &mut dyn Any) {
+    /// clean up callback storage for the next frame. The `registry` is used to
+    /// find the input for callbacks that take a portion of the root `state`.
+    /// Returns whether any external callback ran, i.e. whether the `state`
+    /// may have changed.
+    ///
+    /// # Panics
+    ///
+    /// When a callback's input type has no accessor in the `registry`.
+    pub fn end_frame(&mut self, state: &mut dyn Any, registry: &AccessorRegistry) -> bool {
         self.internal.clear();
+        self.responses.clear();
+        let any_dispatched = !self.external.is_empty();
         for callback in self.external.drain(..) {
-            callback.invoke(state);
+            match callback.input_type {
+                None => callback.invoke(state),
+                Some(input_type) => {
+                    let input = registry.access(state, input_type).expect(
+                        "No accessor registered for the input type of a callback. \
+                         Use `Context::register_accessor` to register one.",
+                    );
+                    callback.invoke(input);
+                }
+            }
         }
         self.next_token = 0;
+        self.generation = self.generation.wrapping_add(1);
+        any_dispatched
+    }
+
+    /// Returns whether `tk` belongs to a previous frame, logging a warning
+    /// when it does. Stale tokens are ignored.
+    fn is_stale(&self, tk: RawPollToken) -> bool {
+        let stale = tk.generation != self.generation;
+        if stale {
+            log::warn!(
+                "Used a PollToken from a previous frame. Tokens are only valid for one frame."
+            );
+        }
+        stale
     }
 
     /// Creates an internal callback, to be dispatched later via
@@ -151,6 +372,7 @@ impl DispatchedCallbackStorage {
     pub fn create_internal_callback<P: 'static>(&mut self) -> (Callback<P>, PollToken<P>) {
         let token = PollToken::<P> {
             token: self.next_token,
+            generation: self.generation,
             _phantom: Default::default(),
         };
         self.next_token += 1;
@@ -162,11 +384,47 @@ impl DispatchedCallbackStorage {
     ///
     /// Note that calling this function will remove the polled value from
     /// storage, and subsequent calls will return None.
+    ///
+    /// Tokens from previous frames always return `None`.
     pub fn poll_callback_result<P: 'static>(&mut self, tk: PollToken<P>) -> Option<P> {
+        if self.is_stale(tk.as_raw()) {
+            return None;
+        }
         self.internal
             .remove(&tk.as_raw())
             .map(|x| *x.downcast::<P>().expect("Failed downcast"))
     }
+
+    /// Writes a `response` for the internal callback identified by `tk`. This
+    /// allows internal callbacks to be used as a two-way channel: A child
+    /// widget dispatches a request through the callback, the parent polls it
+    /// and answers, and the child later reads the answer back using
+    /// [`DispatchedCallbackStorage::poll_response`].
+    ///
+    /// All of this happens during the same frame, so the ordering matters:
+    /// - The child must dispatch the callback before the parent polls it.
+    /// - The parent must respond before the child tries to read the response.
+    ///
+    /// Since parents typically forward events to their children before
+    /// polling, a natural place to do this is to dispatch in the child's
+    /// `on_event`, respond in the parent's `on_event`, after the children have
+    /// run, and read the response in the child's `draw`.
+    pub fn respond<P, R: 'static>(&mut self, tk: PollToken<P>, response: R) {
+        self.responses.insert(tk.as_raw(), Box::new(response));
+    }
+
+    /// Fetches the response written via [`DispatchedCallbackStorage::respond`]
+    /// for the internal callback identified by `tk`, if any.
+    ///
+    /// Like with `poll_callback_result`, the response is removed from storage.
+    pub fn poll_response<P, R: 'static>(&mut self, tk: PollToken<P>) -> Option<R> {
+        if self.is_stale(tk.as_raw()) {
+            return None;
+        }
+        self.responses
+            .remove(&tk.as_raw())
+            .map(|x| *x.downcast::<R>().expect("Failed downcast"))
+    }
 }
 
 #[cfg(test)]
@@ -213,12 +471,97 @@ mod tests {
 
         storage.dispatch_callback(bar_cb, ());
         storage.dispatch_callback(baz_cb, ());
-        storage.end_frame(&mut state);
+        storage.end_frame(&mut state, &AccessorRegistry::default());
 
         assert_eq!(state.bar.x, 123.4);
         assert_eq!(state.foo.baz.y, 432.1);
     }
 
+    #[test]
+    fn test_registered_accessors() {
+        #[derive(Default)]
+        struct State {
+            foo: Foo,
+            bar: Bar,
+            w: f32,
+        }
+        #[derive(Default)]
+        struct Foo {
+            baz: Baz,
+        }
+        #[derive(Default)]
+        struct Bar {
+            x: f32,
+        }
+        #[derive(Default)]
+        struct Baz {
+            y: f32,
+        }
+
+        let mut state = State::default();
+        let mut registry = AccessorRegistry::default();
+        registry.register(|state: &mut State| &mut state.bar);
+        registry.register(|state: &mut State| &mut state.foo.baz);
+
+        let mut storage = DispatchedCallbackStorage::default();
+        storage.dispatch_callback(Callback::from_fn(|bar: &mut Bar, x| bar.x = x), 123.4);
+        storage.dispatch_callback(Callback::from_fn(|baz: &mut Baz, y| baz.y = y), 432.1);
+        storage.dispatch_callback(Callback::from_fn(|state: &mut State, w| state.w = w), 1.0);
+        storage.end_frame(&mut state, &registry);
+
+        assert_eq!(state.bar.x, 123.4);
+        assert_eq!(state.foo.baz.y, 432.1);
+        assert_eq!(state.w, 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_missing_accessor() {
+        let mut state = 0u32;
+        let mut storage = DispatchedCallbackStorage::default();
+        storage.dispatch_callback(Callback::from_fn(|x: &mut f32, _| *x = 1.0), ());
+        storage.end_frame(&mut state, &AccessorRegistry::default());
+    }
+
+    #[test]
+    fn test_shared_callbacks() {
+        let mut state = 0u32;
+        let mut storage = DispatchedCallbackStorage::default();
+        let mut cb = Some(Callback::from_fn_mut(|x: &mut u32, y| *x += y));
+        storage.dispatch_callback_ref(&mut cb, 1);
+        storage.dispatch_callback_ref(&mut cb, 2);
+        assert!(cb.is_some());
+
+        let mut once = Some(Callback::from_fn(|x: &mut u32, y| *x += y));
+        storage.dispatch_callback_ref(&mut once, 10);
+        storage.dispatch_callback_ref(&mut once, 20);
+        assert!(once.is_none());
+
+        assert!(storage.end_frame(&mut state, &AccessorRegistry::default()));
+        assert_eq!(state, 13);
+        assert!(!storage.end_frame(&mut state, &AccessorRegistry::default()));
+    }
+
+    #[test]
+    fn test_callback_combinators() {
+        let mut state: Vec<String> = vec![];
+        let mut storage = DispatchedCallbackStorage::default();
+
+        let push = |prefix: &'static str| {
+            Callback::from_fn(move |log: &mut Vec<String>, x: i32| log.push(format!("{prefix}{x}")))
+        };
+        storage.dispatch_callback(push("a").and_then(push("b")), 1);
+        storage.dispatch_callback(push("c").map(|s: &str| s.len() as i32), "four");
+
+        // Mapping an internal callback goes through an adapter
+        let (cb, tk) = storage.create_internal_callback::<i32>();
+        storage.dispatch_callback(cb.map(|x: i32| x * 2), 21);
+        assert_eq!(storage.poll_callback_result(tk), Some(42));
+
+        storage.end_frame(&mut state, &AccessorRegistry::default());
+        assert_eq!(state, vec!["a1", "b1", "c4"]);
+    }
+
     #[test]
     fn test_internal_callbacks() {
         let mut storage = DispatchedCallbackStorage::default();
@@ -227,6 +570,38 @@ mod tests {
         storage.dispatch_callback(cb, "TestString".to_string());
         assert_eq!(storage.poll_callback_result(tk).unwrap(), "TestString");
     }
+
+    #[test]
+    fn test_stale_poll_token() {
+        let mut storage = DispatchedCallbackStorage::default();
+        let (_, stale_tk) = storage.create_internal_callback::<i32>();
+        storage.end_frame(&mut (), &AccessorRegistry::default());
+
+        // The new token reuses the same id as the stale one
+        let (cb, tk) = storage.create_internal_callback::<i32>();
+        storage.dispatch_callback(cb, 42);
+        assert_eq!(storage.poll_callback_result(stale_tk), None);
+        assert_eq!(storage.poll_callback_result(tk), Some(42));
+    }
+
+    #[test]
+    fn test_internal_callback_responses() {
+        let mut storage = DispatchedCallbackStorage::default();
+        let (cb, tk) = storage.create_internal_callback::<i32>();
+
+        // Child side: Dispatch the request, keeping the token around
+        let child_tk = cb.poll_token().unwrap();
+        storage.dispatch_callback(cb, 42);
+        assert_eq!(storage.poll_response::<_, bool>(child_tk), None);
+
+        // Parent side: Poll the request and respond
+        let value = storage.poll_callback_result(tk).unwrap();
+        storage.respond(tk, value > 0);
+
+        // Child side: Read the response back
+        assert_eq!(storage.poll_response::<_, bool>(child_tk), Some(true));
+        assert_eq!(storage.poll_response::<_, bool>(child_tk), None);
+    }
 }
 
 // Boilerplate: Rust doesn't allow derives with PhantomData
@@ -235,6 +610,7 @@ impl<P> Clone for PollToken<P> {
     fn clone(&self) -> Self {
         Self {
             token: self.token,
+            generation: self.generation,
             _phantom: self._phantom,
         }
     }
@@ -3,6 +3,8 @@ use std::{
     marker::PhantomData,
 };
 
+use epaint::ahash::HashMap;
+
 use dyn_clone::{clone_trait_object, DynClone};
 
 use crate::{callback::ExternalCallback, prelude::Callback};
@@ -101,12 +103,59 @@ where
             f(t, p);
         };
         Callback::External(ExternalCallback {
-            input_type: TypeId::of::<T>(),
+            // The closure navigates from the root state on its own
+            input_type: None,
             f: Box::new(closure),
         })
     }
 }
 
+/// An `AccessorRegistry` stores, for a given root state type, a set of
+/// functions to access some of its fields by type. This allows callbacks
+/// created via [`Callback::from_fn`] to take a portion of the app state as
+/// input, as long as there is an accessor registered for that type.
+///
+/// Unlike a [`CallbackAccessor`], which is built when constructing widgets,
+/// the registry is set up once, typically via
+/// [`Context::register_accessor`](crate::context::Context::register_accessor).
+#[derive(Default)]
+pub struct AccessorRegistry {
+    /// Maps the `TypeId` of an accessed type to a function that takes the
+    /// type-erased root state and returns a value of that type.
+    accessors: HashMap<TypeId, Box<dyn AccessorFn>>,
+}
+
+impl AccessorRegistry {
+    /// Registers a function `f` that, given the root state `S`, returns a value
+    /// of type `T`. Any previous accessor for `T` gets replaced.
+    pub fn register<S: 'static, T: 'static>(
+        &mut self,
+        f: impl Fn(&mut S) -> &mut T + 'static + Clone,
+    ) {
+        let closure = ({
+            fn funnel<Closure>(f: Closure) -> Closure
+            where
+                Closure: for<'a> Fn(&'a mut dyn Any) -> &'a mut dyn Any,
+            {
+                f
+            }
+            funnel::<_>
+        })(move |s_any| f(s_any.downcast_mut().expect("Failed downcast")));
+        self.accessors.insert(TypeId::of::<T>(), Box::new(closure));
+    }
+
+    /// Returns the value of type `input_type` inside the given `root` state.
+    /// The root state itself is returned when `input_type` is its own type.
+    /// Returns `None` when no accessor was registered for `input_type`.
+    pub fn access<'a>(&self, root: &'a mut dyn Any, input_type: TypeId) -> Option<&'a mut dyn Any> {
+        if (*root).type_id() == input_type {
+            Some(root)
+        } else {
+            self.accessors.get(&input_type).map(|f| f.call(root))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
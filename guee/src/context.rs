@@ -1,17 +1,64 @@
-use std::{any::Any, borrow::BorrowMut, cell::RefCell, ops::DerefMut};
+use std::{
+    any::Any,
+    borrow::BorrowMut,
+    cell::{Cell, RefCell},
+    ops::DerefMut,
+    time::{Duration, Instant},
+};
 
-use epaint::{ClippedPrimitive, Pos2, Rect, TessellationOptions, Vec2};
+use epaint::{
+    ahash::HashMap, textures::TexturesDelta, ClippedPrimitive, FontId, Pos2, Primitive, Rect,
+    TessellationOptions, Vec2,
+};
+use winit::event::VirtualKeyCode;
 
 use crate::{
+    access_keys,
+    animation::AnimationState,
+    base_widgets::scroll_container::VScrollContainerState,
     callback::{Callback, DispatchedCallbackStorage, PollToken},
-    input::{InputState, InputWidgetState, MouseButton},
-    memory::Memory,
+    callback_accessor::{AccessorRegistry, CallbackAccessor},
+    drag_and_drop::DragAndDrop,
+    extension_traits::Color32Ext,
+    focus_order::{FocusEntry, FocusOrder},
+    input::{
+        CursorRequests, Event, FocusTransition, InputState, InputWidgetState, LastFocusState,
+        MouseButton,
+    },
+    layout::{Direction, Layout},
+    memory::{Memory, WidgetState},
     painter::{ExtraFont, Painter, TranslateScale},
+    popup::PopupManager,
+    repeat::RepeatState,
+    shortcuts::{KeyCombo, Shortcut},
+    surface::Surfaces,
     theme::Theme,
+    toast::{paint_toast, text_width, Toast, ToastQueue},
     widget::DynWidget,
     widget_id::WidgetId, prelude::EventStatus,
 };
 
+#[cfg(feature = "images")]
+use crate::image_loading::{decode_image, ImageLoadError};
+#[cfg(feature = "images")]
+use epaint::TextureId;
+#[cfg(feature = "images")]
+use std::sync::Arc;
+#[cfg(feature = "testing")]
+use crate::software_renderer::SoftwareRasterizer;
+
+/// An image loaded with [`Context::load_image_bytes`].
+#[cfg(feature = "images")]
+pub struct CachedImage {
+    /// The encoded bytes. Keeping them alive means the same allocation is
+    /// never reused for other bytes, so comparing pointers is enough to tell
+    /// they're unchanged.
+    pub bytes: Arc<[u8]>,
+    /// A hash of the encoded bytes, to notice when they change.
+    pub bytes_hash: u64,
+    pub result: Result<TextureId, ImageLoadError>,
+}
+
 pub struct Context {
     pub painter: RefCell<Painter>,
     pub input_state: InputState,
@@ -19,9 +66,87 @@ pub struct Context {
     pub dispatched_callbacks: RefCell<DispatchedCallbackStorage>,
     pub memory: Memory,
     pub theme: RefCell<Theme>,
+    /// Used to route callbacks that take a portion of the app state. See
+    /// [`Context::register_accessor`].
+    pub accessor_registry: AccessorRegistry,
+    /// The layout computed during the last call to [`Context::run`], in
+    /// absolute coordinates. Replaced at the start of every frame.
+    pub last_layout: Option<Layout>,
+    /// The instant the context was created. Frame times are measured from it.
+    pub start_time: Instant,
+    /// The time at the start of the current frame, in seconds since
+    /// `start_time`. See [`Context::time`].
+    pub frame_time: f64,
+    /// The earliest time from now at which a widget asked for a new frame to
+    /// be drawn. See [`Context::request_repaint_after`].
+    pub repaint_after: Cell<Option<Duration>>,
+    /// The access keys registered by widgets during the current frame. See
+    /// [`Context::register_access_key`].
+    pub access_keys: RefCell<HashMap<char, WidgetId>>,
+    /// The shortcuts registered for the current frame. See
+    /// [`Context::register_shortcut`].
+    pub shortcuts: RefCell<Vec<Shortcut>>,
+    /// Whether a text field has keyboard focus during the current frame. See
+    /// [`Context::claim_text_input`].
+    pub text_input_focused: Cell<bool>,
+    /// The ongoing drag and drop operation, if any. Lasts until the primary
+    /// mouse button is released.
+    pub drag_and_drop: RefCell<Option<DragAndDrop>>,
+    /// The new screen size, when the window was resized and the renderer has
+    /// not been told yet. See [`Context::take_resize`].
+    pub pending_resize: Option<Vec2>,
+    /// The options used by [`Context::tessellate`]. See
+    /// [`Context::set_tessellation_options`].
+    pub tessellation_options: TessellationOptions,
+    /// Set by [`Context::request_relayout`], and cleared at the start of every
+    /// frame.
+    pub relayout_requested: Cell<bool>,
+    /// When the integration should run the next frame, as scheduled by the
+    /// last call to [`Context::take_repaint_request`].
+    pub next_frame_at: Option<Instant>,
+    /// The time a control has to be held before it starts repeating, in
+    /// seconds. See [`Context::repeat`].
+    pub repeat_delay: f64,
+    /// The time between repeats of a held control, in seconds.
+    pub repeat_interval: f64,
+    /// See [`Context::set_direction`].
+    pub direction: Direction,
+    /// See [`Context::set_high_contrast`].
+    pub high_contrast: bool,
+    /// See [`Context::set_reduced_motion`].
+    pub reduced_motion: bool,
+    /// See [`Context::set_status`].
+    pub status: RefCell<Option<String>>,
+    /// The fonts given to [`Context::new`], used to create the painters of new
+    /// surfaces.
+    pub extra_fonts: Vec<ExtraFont>,
+    /// See [`Context::run_surface`].
+    pub surfaces: Surfaces,
+    /// See [`Context::open_popup`].
+    pub popups: RefCell<PopupManager>,
+    /// See [`Context::sibling_index`].
+    pub sibling_index: Cell<usize>,
+    /// See [`Context::set_cursor_hidden`] and [`Context::warp_cursor`].
+    pub cursor_requests: Cell<CursorRequests>,
+    /// See [`Context::register_focusable`].
+    pub focus_order: RefCell<FocusOrder>,
+    /// See [`Context::claim_tab_key`].
+    pub tab_claimed: Cell<bool>,
+    /// See [`Context::toast`].
+    pub toasts: RefCell<ToastQueue>,
+    /// Caches the results of [`Context::load_image_bytes`], so images are only
+    /// decoded once.
+    #[cfg(feature = "images")]
+    pub image_cache: RefCell<HashMap<WidgetId, CachedImage>>,
+    /// Keeps the textures used by [`Context::render_to_image`] across frames.
+    #[cfg(feature = "testing")]
+    pub rasterizer: RefCell<SoftwareRasterizer>,
 }
 
 impl Context {
+    /// The available size given to widgets by [`Context::measure`].
+    pub const MEASURE_AVAILABLE_SIZE: f32 = 100_000.0;
+
     /// Creates a new [`Context`]. The context object holds all the necessary
     /// state to render a UI using `guee`.o
     ///
@@ -29,67 +154,605 @@ impl Context {
     /// methods will modify its internal state.
     pub fn new(screen_size: Vec2, extra_fonts: Vec<ExtraFont>) -> Self {
         Self {
-            painter: RefCell::new(Painter::new(extra_fonts)),
+            painter: RefCell::new(Painter::new(extra_fonts.clone())),
             input_state: InputState::new(screen_size),
             dispatched_callbacks: Default::default(),
             memory: Default::default(),
             input_widget_state: Default::default(),
             theme: RefCell::new(Theme::new_empty()),
+            accessor_registry: Default::default(),
+            last_layout: None,
+            start_time: Instant::now(),
+            frame_time: 0.0,
+            repaint_after: Cell::new(None),
+            access_keys: Default::default(),
+            shortcuts: Default::default(),
+            text_input_focused: Cell::new(false),
+            drag_and_drop: RefCell::new(None),
+            pending_resize: None,
+            tessellation_options: TessellationOptions::default(),
+            relayout_requested: Cell::new(false),
+            next_frame_at: None,
+            repeat_delay: 0.4,
+            repeat_interval: 0.05,
+            direction: Direction::LeftToRight,
+            high_contrast: false,
+            reduced_motion: false,
+            status: RefCell::new(None),
+            extra_fonts,
+            surfaces: Surfaces::default(),
+            popups: Default::default(),
+            sibling_index: Cell::new(0),
+            cursor_requests: Cell::new(CursorRequests::default()),
+            focus_order: Default::default(),
+            tab_claimed: Cell::new(false),
+            toasts: Default::default(),
+            #[cfg(feature = "images")]
+            image_cache: Default::default(),
+            #[cfg(feature = "testing")]
+            rasterizer: Default::default(),
         }
     }
 
-    /// Draws the provided `widget` tree. To get the results, call
-    /// [`Context::tessellate`]
+    /// Draws the provided `widget` tree on the active surface. To get the
+    /// results, call [`Context::tessellate`]
     pub fn run(&mut self, widget: &mut DynWidget, state: &mut dyn Any) {
+        self.frame_time = self.start_time.elapsed().as_secs_f64();
+        self.relayout_requested.set(false);
+        self.next_frame_at = None;
+        self.access_keys.get_mut().clear();
+        self.focus_order.get_mut().clear();
+        self.tab_claimed.set(false);
+        self.text_input_focused.set(false);
+        *self.status.get_mut() = None;
+
         // Initialize a fresh painter
         self.painter.borrow_mut().prepare(
             Rect::from_min_size(Pos2::ZERO, self.input_state.screen_size),
             self.theme.borrow().text_color,
         );
 
+        self.last_layout = None;
         let mut layout = widget.widget.layout(
             self,
-            WidgetId::new("__ROOT__"),
+            self.surfaces.active.root_widget_id(),
             self.input_state.screen_size,
             false,
         );
         layout.to_absolute(Vec2::ZERO);
+        self.last_layout = Some(layout);
+        let layout = self.last_layout.as_ref().unwrap();
+
         let events = std::mem::take(&mut self.input_state.ev_buffer);
+        // Shortcuts take priority over regular event handling
+        let events = self.dispatch_shortcuts(events);
+        let events = self.dismiss_clicked_toasts(events);
+        if self
+            .input_state
+            .mouse
+            .button_state
+            .is_pressed(MouseButton::Primary)
+        {
+            self.popups
+                .get_mut()
+                .on_mouse_pressed(self.input_state.mouse.position);
+        }
+        let mut status = EventStatus::Ignored;
+        widget.widget.on_event_capture(
+            self,
+            layout,
+            self.input_state.mouse.position,
+            &events,
+            &mut status,
+        );
         widget
             .widget
             // Pass list of events to on_event
             .on_event(
                 self,
-                &layout,
+                layout,
                 self.input_state.mouse.position,
                 &events,
-                &mut EventStatus::Ignored,
+                &mut status,
             );
-        widget.widget.draw(self, &layout);
-        self.dispatched_callbacks.borrow_mut().end_frame(state);
+        let tab_pressed = events
+            .iter()
+            .any(|ev| matches!(ev, Event::KeyPressed(VirtualKeyCode::Tab)));
+        if tab_pressed && !self.tab_claimed.get() {
+            self.move_focus(self.input_state.modifiers.shift);
+        }
+        widget.widget.draw(self, layout);
+        self.popups.get_mut().end_frame();
+        self.draw_toasts();
+        self.draw_drag_preview();
+        if !self
+            .input_state
+            .mouse
+            .button_state
+            .is_down(MouseButton::Primary)
+        {
+            // Drops not accepted by any widget are simply discarded
+            *self.drag_and_drop.get_mut() = None;
+        }
+        let state_changed = self
+            .dispatched_callbacks
+            .borrow_mut()
+            .end_frame(state, &self.accessor_registry);
+        if state_changed {
+            // The frame was built from the state before the callbacks ran
+            self.request_relayout();
+        }
         self.input_state
             .end_frame(&mut self.input_widget_state.borrow_mut());
     }
 
+    /// Dispatches the callbacks for the registered shortcuts triggered by the
+    /// given `events`, and returns the remaining events. Key presses that
+    /// trigger a shortcut are removed, so no widget sees them.
+    fn dispatch_shortcuts(&self, events: Vec<Event>) -> Vec<Event> {
+        let mut shortcuts: Vec<Option<Shortcut>> =
+            self.shortcuts.borrow_mut().drain(..).map(Some).collect();
+        if shortcuts.is_empty() {
+            return events;
+        }
+
+        let text_input_focused = self.text_input_focused.get();
+        events
+            .into_iter()
+            .filter(|event| {
+                let Event::KeyPressed(key) = event else {
+                    return true;
+                };
+                let triggered = shortcuts.iter_mut().find(|s| {
+                    s.as_ref().map_or(false, |s| {
+                        (s.over_text_input || !text_input_focused)
+                            && s.combo.matches(*key, &self.input_state.modifiers)
+                    })
+                });
+                match triggered.and_then(Option::take) {
+                    Some(shortcut) => {
+                        self.dispatch_callback(shortcut.callback, ());
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Dismisses the toasts clicked during this frame, and returns the
+    /// remaining events. Clicks on a toast are removed, so the widgets below
+    /// it don't see them.
+    fn dismiss_clicked_toasts(&self, events: Vec<Event>) -> Vec<Event> {
+        let position = self.input_state.mouse.position;
+        let mut toasts = self.toasts.borrow_mut();
+        events
+            .into_iter()
+            .filter(|event| {
+                !(matches!(event, Event::MousePressed(MouseButton::Primary))
+                    && toasts.dismiss_at(position))
+            })
+            .collect()
+    }
+
+    /// Same as [`Context::run`], but builds the widget tree by calling `view`
+    /// with the current `state` and the root [`CallbackAccessor`] for it.
+    ///
+    /// This is the recommended entry point for typed apps: Callbacks for
+    /// widgets can be created with `cba.callback(|state, payload| ...)`, or
+    /// for a portion of the state with `cba.drill_down(...)`.
+    pub fn run_view<S: 'static>(
+        &mut self,
+        state: &mut S,
+        view: impl FnOnce(&S, &CallbackAccessor<S>) -> DynWidget,
+    ) {
+        let mut widget = view(state, &self.root_accessor());
+        self.run(&mut widget, state);
+    }
+
+    /// Returns a [`CallbackAccessor`] for the root app state `S`. The returned
+    /// accessor is only valid when `S` is the type of the state passed to
+    /// [`Context::run`], otherwise invoking its callbacks will panic.
+    pub fn root_accessor<S: 'static>(&self) -> CallbackAccessor<S> {
+        CallbackAccessor::root()
+    }
+
+    /// Registers a function to access a portion `T` of the app's root state
+    /// `S`. Callbacks created with [`Callback::from_fn`] taking a `T` as input
+    /// will then receive the value returned by `f`.
+    ///
+    /// ```ignore
+    /// ctx.register_accessor(|state: &mut AppState| &mut state.settings);
+    /// let cb = Callback::from_fn(|settings: &mut Settings, new| settings.name = new);
+    /// ```
+    pub fn register_accessor<S: 'static, T: 'static>(
+        &mut self,
+        f: impl Fn(&mut S) -> &mut T + 'static + Clone,
+    ) {
+        self.accessor_registry.register(f);
+    }
+
+    /// Returns the time at the start of the current frame, in seconds since
+    /// the context was created. The value stays the same during the whole
+    /// frame, so all widgets see a consistent clock for animations.
+    pub fn time(&self) -> f64 {
+        self.frame_time
+    }
+
+    /// Smoothly animates a value towards `target` over `duration` seconds, and
+    /// returns the value for the current frame. In reduced motion mode, the
+    /// value snaps to `target` right away. The animation state is stored
+    /// under the given `id`, which should be unique for each animated value.
+    ///
+    /// When `target` changes, a new animation starts from the current value,
+    /// so animations can be interrupted at any time. The first time this is
+    /// called for an `id`, the value starts at `target` without animating. A
+    /// repaint is requested while the animation is running.
+    pub fn animate(&self, id: WidgetId, target: f32, duration: f32) -> f32 {
+        let duration = if self.reduced_motion { 0.0 } else { duration };
+        let now = self.time();
+        let mut state = self.memory.get_mut_or(
+            id,
+            AnimationState {
+                from: target,
+                to: target,
+                start_time: now,
+            },
+        );
+        if state.to != target {
+            *state = AnimationState {
+                from: state.value_at(now, duration),
+                to: target,
+                start_time: now,
+            };
+        }
+        if state.progress(now, duration) < 1.0 {
+            self.request_repaint();
+        }
+        state.value_at(now, duration)
+    }
+
+    /// Returns how many times a held control, like a stepper button, should
+    /// fire its action during this frame. `held` must be true while the user
+    /// holds the control with the given `id`.
+    ///
+    /// The action fires once when the control is pressed. After
+    /// [`Context::repeat_delay`] seconds, it keeps firing every
+    /// [`Context::repeat_interval`] seconds, and repaints are scheduled for
+    /// that. When frames are slow, the missed repeats are returned together.
+    pub fn repeat(&self, id: WidgetId, held: bool) -> u32 {
+        let (count, next_in) = self.memory.get_mut_or_default::<RepeatState>(id).advance(
+            self.time(),
+            held,
+            self.repeat_delay,
+            self.repeat_interval,
+        );
+        if held {
+            self.request_repaint_after(Duration::from_secs_f64(next_in));
+        }
+        count
+    }
+
+    /// Sets the initial delay and the interval between repeats, in seconds,
+    /// used by [`Context::repeat`].
+    pub fn set_repeat_timing(&mut self, delay: f64, interval: f64) {
+        self.repeat_delay = delay;
+        self.repeat_interval = interval;
+    }
+
+    /// Runs `f` on the payload of the ongoing drag and drop operation, if
+    /// there is one of type `T`, and returns the result. Unlike
+    /// [`Context::accept_drop`], the payload is not taken.
+    pub fn with_drag_payload<T: 'static, R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.drag_and_drop
+            .borrow()
+            .as_ref()
+            .and_then(|dnd| dnd.payload.as_ref())
+            .and_then(|payload| payload.downcast_ref::<T>())
+            .map(f)
+    }
+
+    /// Asks for a new frame to be drawn as soon as possible, even if no input
+    /// events arrive. See [`Context::request_repaint_after`].
+    pub fn request_repaint(&self) {
+        self.request_repaint_after(Duration::ZERO)
+    }
+
+    /// Asks for a new frame to be drawn after `delay`, even if no input events
+    /// arrive. Widgets showing animations should call this every frame while
+    /// the animation is running. When called multiple times during a frame,
+    /// the shortest delay wins.
+    pub fn request_repaint_after(&self, delay: Duration) {
+        let earliest = match self.repaint_after.get() {
+            Some(current) => current.min(delay),
+            None => delay,
+        };
+        self.repaint_after.set(Some(earliest));
+    }
+
+    /// Returns the repaint request made by widgets during the last frame, if
+    /// any, and clears it. Integrations should call this after
+    /// [`Context::run`] and schedule the next frame accordingly, e.g. by
+    /// using `ControlFlow::WaitUntil` in winit.
+    pub fn take_repaint_request(&mut self) -> Option<Duration> {
+        let repaint_after = self.repaint_after.take();
+        self.next_frame_at = repaint_after.map(|delay| Instant::now() + delay);
+        repaint_after
+    }
+
+    /// Returns whether calling [`Context::run`] now could produce a different
+    /// frame than the last one. When this returns false, integrations can skip
+    /// running, tessellating and rendering the frame altogether, which saves a
+    /// lot of work for mostly static UIs.
+    ///
+    /// A new frame is needed when:
+    /// - No frame has been laid out yet, or the window was resized.
+    /// - There are input events waiting to be processed.
+    /// - A widget asked for a repaint, e.g. to run an animation, and the
+    ///   requested time has arrived.
+    /// - [`Context::request_relayout`] was called.
+    /// - Widget callbacks ran at the end of the last frame. They change the
+    ///   app state after the frame was built, so it shows the old state.
+    ///
+    /// Changes to the app state that don't come from widget callbacks are not
+    /// tracked. When the app state is modified from elsewhere, e.g. a
+    /// background thread, a relayout must be requested explicitly.
+    pub fn needs_run(&self) -> bool {
+        self.last_layout.is_none()
+            || self.relayout_requested.get()
+            || !self.input_state.ev_buffer.is_empty()
+            // Delayed repaints wait for `next_frame_at`, set when the
+            // request is taken, so only immediate ones count here.
+            || self.repaint_after.get() == Some(Duration::ZERO)
+            || self
+                .next_frame_at
+                .map_or(false, |next_frame_at| Instant::now() >= next_frame_at)
+    }
+
+    /// Forces the next frame to be fully laid out and drawn, even if
+    /// [`Context::needs_run`] found nothing changed.
+    pub fn request_relayout(&self) {
+        self.relayout_requested.set(true);
+    }
+
+    /// Returns the layout computed during the last call to [`Context::run`],
+    /// in absolute coordinates. When called from widget code, this is the
+    /// layout for the current frame.
+    pub fn last_layout(&self) -> Option<&Layout> {
+        self.last_layout.as_ref()
+    }
+
+    /// Computes the layout for the given `widget` tree against the current
+    /// screen size, and returns it in absolute coordinates. Nothing is drawn,
+    /// and no events or callbacks are processed.
+    ///
+    /// This does not need a window or a GPU: Text is measured on the CPU using
+    /// the fonts bundled with epaint, so results are deterministic. This makes
+    /// it useful to test layout algorithms.
+    ///
+    /// Note that there is no stub font metric: Text is laid out with the real
+    /// fonts, because widgets like [`TextEdit`] need real epaint galleys to
+    /// place the caret. Text sizes change whenever the bundled fonts do, so
+    /// tests should compare them with each other, or with
+    /// [`Painter::measure_text`], rather than hardcode them.
+    ///
+    /// [`TextEdit`]: crate::base_widgets::text_edit::TextEdit
+    /// [`Painter::measure_text`]: crate::painter::Painter::measure_text
+    pub fn layout_only(&self, widget: &mut DynWidget) -> Layout {
+        let mut layout = widget.widget.layout(
+            self,
+            self.surfaces.active.root_widget_id(),
+            self.input_state.screen_size,
+            false,
+        );
+        layout.to_absolute(Vec2::ZERO);
+        layout
+    }
+
+    /// Returns the natural size of the given `widget` tree, that is, the
+    /// smallest size it can be laid out at. Nothing is drawn, and no events or
+    /// callbacks are processed.
+    ///
+    /// This is useful to size a window or popup to fit its contents, e.g. by
+    /// creating a winit window with the returned size.
+    ///
+    /// The widget is laid out with a very large available size in shrink mode.
+    /// Note that some widgets ignore `force_shrink` and always fill the
+    /// available space, like [`SplitPaneContainer`] and [`StackContainer`].
+    /// Trees containing them will report a size as large as the available
+    /// size, which is [`Context::MEASURE_AVAILABLE_SIZE`] in both axes.
+    ///
+    /// [`SplitPaneContainer`]: crate::base_widgets::split_pane_container::SplitPaneContainer
+    /// [`StackContainer`]: crate::base_widgets::stack_container::StackContainer
+    pub fn measure(&self, widget: &mut DynWidget) -> Vec2 {
+        widget.widget.measure(
+            self,
+            self.surfaces.active.root_widget_id(),
+            Vec2::splat(Self::MEASURE_AVAILABLE_SIZE),
+        )
+    }
+
     /// Returns a list of [`ClippedPrimitive`], suitable for rendering with an
     /// egui-compatible renderer.
     pub fn tessellate(&mut self) -> Vec<ClippedPrimitive> {
         let mut painter = self.painter.borrow_mut();
 
-        epaint::tessellate_shapes(
+        let mut primitives = epaint::tessellate_shapes(
             1.0,
-            TessellationOptions::default(),
+            self.tessellation_options,
             painter.fonts.font_image_size(),
             vec![],
             painter.borrow_mut().take_shapes(),
+        );
+        if self.high_contrast {
+            for primitive in &mut primitives {
+                if let Primitive::Mesh(mesh) = &mut primitive.primitive {
+                    for vertex in &mut mesh.vertices {
+                        vertex.color = vertex.color.high_contrast();
+                    }
+                }
+            }
+        }
+        primitives
+    }
+
+    /// Runs a frame for the given `widget` tree with the given screen `size`,
+    /// and rasterizes the result on the CPU. The background of the returned
+    /// image is transparent.
+    ///
+    /// The output won't match the GPU renderers pixel by pixel, but it is good
+    /// enough to write snapshot tests of widget layout and drawing without a
+    /// window. Texture changes are consumed by this function, so it should not
+    /// be mixed with a regular renderer on the same context.
+    #[cfg(feature = "testing")]
+    pub fn render_to_image(
+        &mut self,
+        size: Vec2,
+        widget: &mut DynWidget,
+        state: &mut dyn Any,
+    ) -> epaint::ColorImage {
+        self.input_state.screen_size = size;
+        self.run(widget, state);
+        let primitives = self.tessellate();
+        let textures_delta = self.take_textures_delta();
+
+        let rasterizer = self.rasterizer.get_mut();
+        rasterizer.update_textures(&textures_delta);
+        rasterizer.rasterize(
+            &primitives,
+            [size.x.ceil() as usize, size.y.ceil() as usize],
+            1.0,
+            epaint::Color32::TRANSPARENT,
         )
     }
 
+    /// Returns all the texture changes since the last call to this function.
+    /// Renderers should apply the delta before drawing the tessellated
+    /// primitives.
+    pub fn take_textures_delta(&mut self) -> TexturesDelta {
+        self.painter.get_mut().take_textures_delta()
+    }
+
+    /// Decodes the given PNG or JPEG `bytes` and uploads them as a texture.
+    ///
+    /// The result is cached under the given `key`, so subsequent calls with
+    /// the same key and bytes will return the same `TextureId` without
+    /// decoding the image again. Errors are cached too. When the bytes for a
+    /// key change, the old texture is freed and the new bytes are decoded.
+    ///
+    /// Passing the same `Arc` every frame is cheap. Other bytes are hashed to
+    /// check whether they changed, which is slow for large images.
+    #[cfg(feature = "images")]
+    pub fn load_image_bytes(
+        &self,
+        key: impl std::hash::Hash,
+        bytes: &Arc<[u8]>,
+    ) -> Result<TextureId, ImageLoadError> {
+        let key = WidgetId::new(key);
+        let hash_bytes = || {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut bytes_hash = None;
+        if let Some(cached) = self.image_cache.borrow_mut().get_mut(&key) {
+            if std::ptr::eq(cached.bytes.as_ptr(), bytes.as_ptr())
+                && cached.bytes.len() == bytes.len()
+            {
+                return cached.result.clone();
+            }
+            let hash = *bytes_hash.insert(hash_bytes());
+            if cached.bytes_hash == hash {
+                // Same contents in a new allocation, remember it for next time
+                cached.bytes = bytes.clone();
+                return cached.result.clone();
+            }
+        }
+        self.free_cached_image(key);
+
+        let result = decode_image(bytes).map(|image| {
+            self.painter()
+                .load_texture(format!("guee_image_{}", key.short_debug_format()), image)
+        });
+        if let Err(err) = &result {
+            log::error!("Could not load image: {err}");
+        }
+        let cached = CachedImage {
+            bytes: bytes.clone(),
+            bytes_hash: bytes_hash.unwrap_or_else(hash_bytes),
+            result: result.clone(),
+        };
+        self.image_cache.borrow_mut().insert(key, cached);
+        result
+    }
+
+    /// Frees the texture loaded by [`Context::load_image_bytes`] under the
+    /// given `key`, if any. Call this when an image won't be shown again.
+    #[cfg(feature = "images")]
+    pub fn free_image(&self, key: impl std::hash::Hash) {
+        self.free_cached_image(WidgetId::new(key));
+    }
+
+    #[cfg(feature = "images")]
+    fn free_cached_image(&self, key: WidgetId) {
+        let removed = self.image_cache.borrow_mut().remove(&key);
+        if let Some(CachedImage {
+            result: Ok(texture_id),
+            ..
+        }) = removed
+        {
+            self.painter().free_texture(texture_id);
+        }
+    }
+
     pub fn on_winit_event(&mut self, event: &winit::event::WindowEvent) {
+        match event {
+            winit::event::WindowEvent::Resized(new_size) => {
+                self.on_resize(Vec2::new(new_size.width as f32, new_size.height as f32));
+            }
+            // These change the UI without generating any events, e.g. access
+            // keys are underlined while Alt is held.
+            winit::event::WindowEvent::ModifiersChanged(_)
+            | winit::event::WindowEvent::Focused(_) => self.request_relayout(),
+            _ => (),
+        }
         self.input_state
             .on_winit_event(self.input_widget_state.get_mut(), event);
     }
 
+    /// Should be called when the window is resized. This is done
+    /// automatically by [`Context::on_winit_event`].
+    ///
+    /// Updates the screen size, so the next [`Context::run`] lays out the UI
+    /// against it, and asks for a repaint. The renderer is not owned by the
+    /// context, so integrations should reconfigure it after checking
+    /// [`Context::take_resize`].
+    pub fn on_resize(&mut self, new_size: Vec2) {
+        self.input_state.screen_size = new_size;
+        let painter = self.painter.get_mut();
+        painter.clip_rect = Rect::from_min_size(Pos2::ZERO, new_size);
+        painter.screen_rect = painter.clip_rect;
+        // The old layout was computed for the old size, and is no longer valid
+        self.last_layout = None;
+        self.pending_resize = Some(new_size);
+        self.request_repaint();
+    }
+
+    /// Returns the new screen size if the window was resized since the last
+    /// call, and clears it. Integrations should call this before rendering
+    /// and pass the size to [`Renderer::resize`].
+    ///
+    /// [`Renderer::resize`]: crate::renderer::Renderer::resize
+    pub fn take_resize(&mut self) -> Option<Vec2> {
+        self.pending_resize.take()
+    }
+
+    /// Returns the current size of the screen, as used for layout.
+    pub fn screen_size(&self) -> Vec2 {
+        self.input_state.screen_size
+    }
+
     /// Typically called from within widget code. Signals that the given
     /// callback `c` has been fired.
     pub fn dispatch_callback<P: 'static>(&self, c: Callback<P>, payload: P) {
@@ -98,6 +761,18 @@ impl Context {
             .dispatch_callback(c, payload);
     }
 
+    /// Like [`Context::dispatch_callback`], but for widgets that may fire the
+    /// same callback several times in a frame. The callback is left in `c`
+    /// when it can be dispatched again, which is the case for internal
+    /// callbacks and the ones made with [`Callback::from_fn_mut`]. Callbacks
+    /// made from a `FnOnce` are taken out of `c`, so only the first dispatch
+    /// runs.
+    pub fn dispatch_callback_ref<P: 'static>(&self, c: &mut Option<Callback<P>>, payload: P) {
+        self.dispatched_callbacks
+            .borrow_mut()
+            .dispatch_callback_ref(c, payload);
+    }
+
     /// Typically called from within widget code. Allocates a new polling-based
     /// internal callback and returns it, together with its `PollToken`. See
     /// documentation on `Callback` for an explanation on internal callbacks.
@@ -120,6 +795,20 @@ impl Context {
             .poll_callback_result(tk)
     }
 
+    /// Writes a response for an internal callback, to be read back by the
+    /// widget that dispatched it. See [`DispatchedCallbackStorage::respond`]
+    /// for the frame-ordering constraints of this two-way channel.
+    pub fn respond_to_callback<P, R: 'static>(&self, tk: PollToken<P>, response: R) {
+        self.dispatched_callbacks.borrow_mut().respond(tk, response)
+    }
+
+    /// Reads the response written by the parent widget via
+    /// [`Context::respond_to_callback`] for the internal callback identified
+    /// by `tk`, if any.
+    pub fn poll_callback_response<P, R: 'static>(&self, tk: PollToken<P>) -> Option<R> {
+        self.dispatched_callbacks.borrow_mut().poll_response(tk)
+    }
+
     /// Requests focus for the given `widget_id`. The context will keep track of
     /// this widget being the focused one until some other widget calls this
     /// function, or the [`Context::release_focus`] function is called.
@@ -138,6 +827,34 @@ impl Context {
         }
     }
 
+    /// Makes the widget with `widget_id` reachable with the Tab key during
+    /// this frame. Focusable widgets should call this from `on_event` with
+    /// their absolute bounds, even when the events were consumed. See the
+    /// [`focus_order`](crate::focus_order) module for the traversal order.
+    pub fn register_focusable(&self, widget_id: WidgetId, rect: Rect, tab_index: Option<i32>) {
+        self.focus_order.borrow_mut().register(FocusEntry {
+            widget_id,
+            rect,
+            tab_index,
+        });
+    }
+
+    /// Keeps the Tab key from moving the focus during this frame, for widgets
+    /// that use it themselves, like autocompletion in a text field.
+    pub fn claim_tab_key(&self) {
+        self.tab_claimed.set(true);
+    }
+
+    /// Moves the focus to the next widget registered with
+    /// [`Context::register_focusable`], or to the previous one when
+    /// `backwards` is set. This is what pressing Tab and Shift+Tab does.
+    pub fn move_focus(&self, backwards: bool) {
+        let next = self.focus_order.borrow().next(self.get_focus(), backwards);
+        if let Some(next) = next {
+            self.request_focus(next);
+        }
+    }
+
     /// Returns the currently focused widget, if any.
     pub fn get_focus(&self) -> Option<WidgetId> {
         self.input_widget_state.borrow().focus
@@ -152,6 +869,92 @@ impl Context {
             .unwrap_or(false)
     }
 
+    /// Returns whether the given `widget_id` gained or lost focus since the
+    /// last time this function was called for it. Widgets can use this to
+    /// expose focus / blur callbacks.
+    ///
+    /// This should be called exactly once per frame for each widget, and after
+    /// event handling, so that focus changes caused by other widgets during
+    /// this frame are taken into account. The `draw` method is a good place.
+    pub fn focus_transition(&self, widget_id: WidgetId) -> FocusTransition {
+        let focused = self.is_focused(widget_id);
+        let mut last = self.memory.get_mut_or(widget_id, LastFocusState(false));
+        let transition = match (last.0, focused) {
+            (false, true) => FocusTransition::Gained,
+            (true, false) => FocusTransition::Lost,
+            _ => FocusTransition::Unchanged,
+        };
+        last.0 = focused;
+        transition
+    }
+
+    /// Registers an app-global keyboard shortcut for the current frame. When
+    /// `combo` is pressed, `callback` is dispatched and the key press is not
+    /// seen by any widget.
+    ///
+    /// Like widget callbacks, shortcuts only last for one frame, so they
+    /// should be registered every frame, before calling [`Context::run`] or
+    /// during layout. Shortcuts registered this way are ignored while a text
+    /// field has keyboard focus. Use [`Context::register_priority_shortcut`]
+    /// for shortcuts that should work anyway.
+    pub fn register_shortcut(&self, combo: KeyCombo, callback: Callback<()>) {
+        self.shortcuts.borrow_mut().push(Shortcut {
+            combo,
+            callback,
+            over_text_input: false,
+        });
+    }
+
+    /// Same as [`Context::register_shortcut`], but the shortcut also triggers
+    /// while a text field has keyboard focus. Useful for combos text fields
+    /// don't use, like Ctrl+S.
+    pub fn register_priority_shortcut(&self, combo: KeyCombo, callback: Callback<()>) {
+        self.shortcuts.borrow_mut().push(Shortcut {
+            combo,
+            callback,
+            over_text_input: true,
+        });
+    }
+
+    /// Signals that a text field has keyboard focus during this frame. Text
+    /// input widgets should call this during layout when focused, so that
+    /// regular shortcuts don't steal their key presses.
+    pub fn claim_text_input(&self) {
+        self.text_input_focused.set(true);
+    }
+
+    /// Registers the access key `key` for the given `widget_id` during this
+    /// frame. Widgets with access keys should call this during layout. Returns
+    /// false, and logs a warning, when another widget already registered the
+    /// same key, since only the first widget handling the key press would
+    /// react to it.
+    pub fn register_access_key(&self, key: char, widget_id: WidgetId) -> bool {
+        let key = key.to_ascii_lowercase();
+        let mut access_keys = self.access_keys.borrow_mut();
+        match access_keys.get(&key) {
+            Some(other) if *other != widget_id => {
+                log::warn!("Access key '{key}' is used by more than one widget");
+                false
+            }
+            _ => {
+                access_keys.insert(key, widget_id);
+                true
+            }
+        }
+    }
+
+    /// Returns whether the given access `key` was pressed among `events`,
+    /// that is, whether its key was pressed while holding Alt.
+    pub fn access_key_pressed(&self, key: char, events: &[Event]) -> bool {
+        let Some(keycode) = access_keys::keycode_for(key) else {
+            return false;
+        };
+        self.input_state.modifiers.alt
+            && events
+                .iter()
+                .any(|ev| matches!(ev, Event::KeyPressed(k) if *k == keycode))
+    }
+
     /// If there is an ongoing mouse drag event inside `rect`, and no other
     /// widget claimed this drag event before, registers the given `widget_id`
     /// as the widget that is currently handling that event.
@@ -193,6 +996,354 @@ impl Context {
         false
     }
 
+    /// Runs `f`, typically the layout of a child widget, with `index` as the
+    /// sibling index. Containers call this for each of their children, so
+    /// children without an explicit id get a different one each.
+    pub fn with_sibling_index<T>(&self, index: usize, f: impl FnOnce() -> T) -> T {
+        let old = self.sibling_index.replace(index);
+        let result = f();
+        self.sibling_index.set(old);
+        result
+    }
+
+    /// Returns the position of the widget being laid out among its siblings,
+    /// as set by its parent with [`Context::with_sibling_index`]. Widgets
+    /// that don't take an id use it to build one, see [`IdGen::index`].
+    ///
+    /// [`IdGen::index`]: crate::widget_id::IdGen::index
+    pub fn sibling_index(&self) -> usize {
+        self.sibling_index.get()
+    }
+
+    /// Opens the popup owned by `widget_id`, closing all the other popups, so
+    /// e.g. only one menu is open at a time. Clicking outside all popups
+    /// closes the topmost one.
+    ///
+    /// While open, the widget must call [`Context::register_popup_area`] every
+    /// frame when drawing its popup, or it will be closed.
+    pub fn open_popup(&self, widget_id: WidgetId) {
+        self.popups.borrow_mut().open(widget_id, true);
+    }
+
+    /// Like [`Context::open_popup`], but keeps the other popups open, e.g. for
+    /// a popup opened from within another popup.
+    pub fn open_nested_popup(&self, widget_id: WidgetId) {
+        self.popups.borrow_mut().open(widget_id, false);
+    }
+
+    /// Closes the popup owned by `widget_id`, and any popups above it.
+    pub fn close_popup(&self, widget_id: WidgetId) {
+        self.popups.borrow_mut().close(widget_id);
+    }
+
+    pub fn is_popup_open(&self, widget_id: WidgetId) -> bool {
+        self.popups.borrow().is_open(widget_id)
+    }
+
+    /// Registers `rect` as part of the area covered by the open popup of
+    /// `widget_id` during this frame. Clicks inside it don't dismiss popups.
+    /// The rect is in layout coordinates, like the ones given to `draw`.
+    pub fn register_popup_area(&self, widget_id: WidgetId, rect: Rect) {
+        let rect = self.painter.borrow().transform.transform_rectangle(rect);
+        self.popups.borrow_mut().register_area(widget_id, rect);
+    }
+
+    /// Returns a handle to the state of type `T` of the widget with the given
+    /// resolved `widget_id`, stored in the widget memory. See [`WidgetState`].
+    pub fn state<T: 'static>(&self, widget_id: WidgetId) -> WidgetState<'_, T> {
+        WidgetState::new(&self.memory, widget_id)
+    }
+
+    /// Returns the scroll position of the [`VScrollContainer`] with the given
+    /// resolved `widget_id`, as a fraction between 0 (top) and 1 (bottom).
+    ///
+    /// Scroll state lives in widget memory, so the container's id must stay
+    /// the same across frames (and view rebuilds) for the position to be
+    /// found. Use an explicit key rather than an index-derived id when the
+    /// surrounding widgets may change.
+    ///
+    /// [`VScrollContainer`]: crate::base_widgets::scroll_container::VScrollContainer
+    pub fn scroll_offset(&self, widget_id: WidgetId) -> f32 {
+        self.state::<VScrollContainerState>(widget_id)
+            .get_or_default()
+            .scrollbar_frac
+    }
+
+    /// Sets the scroll position of the [`VScrollContainer`] with the given
+    /// resolved `widget_id`. Stops any ongoing smooth scrolling. See
+    /// [`Context::scroll_offset`] for the id stability requirements.
+    ///
+    /// [`VScrollContainer`]: crate::base_widgets::scroll_container::VScrollContainer
+    pub fn set_scroll_offset(&self, widget_id: WidgetId, frac: f32) {
+        let mut state = self
+            .state::<VScrollContainerState>(widget_id)
+            .get_mut_or_default();
+        state.scrollbar_frac = frac.clamp(0.0, 1.0);
+        state.velocity = 0.0;
+        state.scroll_request = None;
+        drop(state);
+        self.request_repaint();
+    }
+
+    /// Sets the status text for the current frame, like a description of the
+    /// hovered control for the application's status bar. Widgets typically
+    /// call this from `on_event` while hovered. The last call wins.
+    pub fn set_status(&self, text: impl Into<String>) {
+        *self.status.borrow_mut() = Some(text.into());
+    }
+
+    /// Returns the status text set by a widget during the last call to
+    /// [`Context::run`], if any.
+    pub fn status(&self) -> Option<String> {
+        self.status.borrow().clone()
+    }
+
+    /// Routes pointer events to `widget_id` until all mouse buttons are
+    /// released, or [`Context::release_pointer`] is called. Widgets typically
+    /// capture the pointer when pressed, so they keep tracking the cursor
+    /// (e.g. to get the release) even if it leaves their bounds.
+    ///
+    /// Widgets should use [`Context::is_pointer_over`] instead of checking
+    /// their bounds directly, so they respect the capture.
+    pub fn capture_pointer(&self, widget_id: WidgetId) {
+        self.input_widget_state.borrow_mut().pointer_capture = Some(widget_id);
+    }
+
+    /// Releases the pointer capture, if held by `widget_id`.
+    pub fn release_pointer(&self, widget_id: WidgetId) {
+        let mut state = self.input_widget_state.borrow_mut();
+        if state.pointer_capture == Some(widget_id) {
+            state.pointer_capture = None;
+        }
+    }
+
+    /// Returns whether `widget_id` has captured the pointer.
+    pub fn has_pointer_capture(&self, widget_id: WidgetId) -> bool {
+        self.input_widget_state.borrow().pointer_capture == Some(widget_id)
+    }
+
+    /// Asks the integration to hide or show the OS cursor. Takes effect after
+    /// the frame, see [`Context::apply_cursor_requests`].
+    pub fn set_cursor_hidden(&self, hidden: bool) {
+        let mut requests = self.cursor_requests.get();
+        requests.hidden = Some(hidden);
+        self.cursor_requests.set(requests);
+    }
+
+    /// Asks the integration to move the OS cursor to `pos`, in screen
+    /// coordinates. Widgets can warp the cursor back every frame while
+    /// dragging, so the drag can go on past the edges of the screen.
+    ///
+    /// The mouse delta of the next frame is measured from `pos`, so warping
+    /// the cursor doesn't register as mouse movement.
+    pub fn warp_cursor(&self, pos: Pos2) {
+        let mut requests = self.cursor_requests.get();
+        requests.warp_to = Some(pos);
+        self.cursor_requests.set(requests);
+    }
+
+    /// Returns the cursor changes requested by widgets during the last frame,
+    /// and clears them. Integrations not using winit should call this after
+    /// [`Context::run`] and apply the changes themselves.
+    pub fn take_cursor_requests(&mut self) -> CursorRequests {
+        let requests = self.cursor_requests.take();
+        if let Some(pos) = requests.warp_to {
+            self.input_state.mouse.position = pos;
+            self.input_state.mouse.prev_position = pos;
+        }
+        requests
+    }
+
+    /// Applies the cursor changes requested by widgets during the last frame
+    /// to the given `window`. Integrations should call this after
+    /// [`Context::run`].
+    pub fn apply_cursor_requests(&mut self, window: &winit::window::Window) {
+        let requests = self.take_cursor_requests();
+        if let Some(hidden) = requests.hidden {
+            window.set_cursor_visible(!hidden);
+        }
+        if let Some(pos) = requests.warp_to {
+            let position = winit::dpi::PhysicalPosition::new(pos.x as f64, pos.y as f64);
+            if let Err(err) = window.set_cursor_position(position) {
+                log::warn!("Could not warp the cursor: {err}");
+            }
+        }
+    }
+
+    /// Returns whether pointer events at `cursor_position` should go to the
+    /// widget with the given `widget_id` and `rect`. That is, either the widget
+    /// captured the pointer, or the cursor is inside `rect` and no other
+    /// widget captured it.
+    pub fn is_pointer_over(&self, widget_id: WidgetId, rect: Rect, cursor_position: Pos2) -> bool {
+        match self.input_widget_state.borrow().pointer_capture {
+            Some(captured) => captured == widget_id,
+            None => rect.contains(cursor_position),
+        }
+    }
+
+    /// Starts a drag and drop operation carrying the given `payload`. Source
+    /// widgets typically call this from `on_event` when they claim a drag
+    /// event (see [`Context::claim_drag_event`]) and it just started.
+    ///
+    /// While the primary mouse button is held, the `preview` widget is drawn
+    /// next to the cursor, on top of everything else. When the button is
+    /// released, a target widget under the cursor can get the payload via
+    /// [`Context::accept_drop`].
+    pub fn begin_drag<T: 'static>(&self, payload: T, preview: DynWidget) {
+        *self.drag_and_drop.borrow_mut() = Some(DragAndDrop {
+            payload: Some(Box::new(payload)),
+            preview: Some(preview),
+        });
+    }
+
+    /// Returns whether there is a drag and drop operation carrying a payload
+    /// of type `T`. Target widgets can use this to highlight themselves.
+    pub fn is_dragging_payload<T: 'static>(&self) -> bool {
+        self.drag_and_drop
+            .borrow()
+            .as_ref()
+            .and_then(|dnd| dnd.payload.as_ref())
+            .map(|payload| payload.is::<T>())
+            .unwrap_or(false)
+    }
+
+    /// Takes the payload of the ongoing drag and drop operation, if it was
+    /// dropped during this frame inside `rect`, and it is of type `T`. Target
+    /// widgets should call this from `on_event`.
+    ///
+    /// Like [`Context::claim_drag_event`], this takes the current cursor
+    /// transform into account.
+    pub fn accept_drop<T: 'static>(&self, rect: Rect) -> Option<T> {
+        let mouse = &self.input_state.mouse;
+        if !mouse.button_state.is_released(MouseButton::Primary) {
+            return None;
+        }
+        let cursor = self
+            .input_widget_state
+            .borrow()
+            .cursor_transform
+            .transform_point(mouse.position);
+        if !rect.contains(cursor) || !self.is_dragging_payload::<T>() {
+            return None;
+        }
+        self.drag_and_drop
+            .borrow_mut()
+            .as_mut()
+            .and_then(|dnd| dnd.payload.take())
+            .map(|payload| *payload.downcast().expect("Failed downcast"))
+    }
+
+    /// Draws the preview widget of the ongoing drag and drop operation next to
+    /// the cursor.
+    fn draw_drag_preview(&self) {
+        let Some(mut preview) = self
+            .drag_and_drop
+            .borrow_mut()
+            .as_mut()
+            .and_then(|dnd| dnd.preview.take())
+        else {
+            return;
+        };
+
+        let mut layout = preview.widget.layout(
+            self,
+            WidgetId::new("__DRAG_PREVIEW__"),
+            self.input_state.screen_size,
+            true,
+        );
+        layout.to_absolute(self.input_state.mouse.position.to_vec2() + Vec2::new(8.0, 8.0));
+
+        let prev_overlay = self.painter().set_overlay(true);
+        preview.widget.draw(self, &layout);
+        self.painter().set_overlay(prev_overlay);
+
+        if let Some(dnd) = self.drag_and_drop.borrow_mut().as_mut() {
+            dnd.preview = Some(preview);
+        }
+    }
+
+    /// Queues a transient notification. Toasts are stacked in a corner of the
+    /// screen, on top of everything else, and fade out after their duration
+    /// or when clicked. See [`ToastStyle`](crate::toast::ToastStyle) to
+    /// change how they look.
+    pub fn toast(&self, toast: Toast) {
+        self.toasts.borrow_mut().push(toast);
+        self.request_repaint();
+    }
+
+    /// Draws the queued toasts on the overlay layer, and removes the ones that
+    /// finished fading out.
+    fn draw_toasts(&self) {
+        let style = self.theme.borrow().toast.clone();
+        let now = self.time();
+        let mut queue = self.toasts.borrow_mut();
+        if queue.toasts.is_empty() {
+            return;
+        }
+
+        for toast in &mut queue.toasts {
+            let anim_id = WidgetId::new(("__TOAST__", toast.id));
+            if toast.shown_at.is_none() {
+                toast.shown_at = Some(now);
+                // Start from transparent, to fade in
+                self.animate(anim_id, 0.0, style.fade_duration);
+            }
+            let expired = toast.is_expired(now);
+            let target = if expired { 0.0 } else { 1.0 };
+            toast.opacity = self.animate(anim_id, target, style.fade_duration);
+            if !expired {
+                let shown_for = now - toast.shown_at.unwrap_or(now);
+                let remaining = (toast.toast.duration as f64 - shown_for).max(0.0);
+                self.request_repaint_after(Duration::from_secs_f64(remaining));
+            }
+        }
+        queue
+            .toasts
+            .retain(|toast| !toast.is_expired(now) || toast.opacity > 0.0);
+
+        let mut painter = self.painter();
+        let font = FontId::proportional(style.font_size);
+        let heights: Vec<f32> = queue
+            .toasts
+            .iter()
+            .map(|toast| {
+                let text_size = painter.measure_text(
+                    toast.toast.text.clone(),
+                    font.clone(),
+                    text_width(&style),
+                );
+                text_size.y.max(style.font_size) + style.padding.y * 2.0
+            })
+            .collect();
+        let screen = Rect::from_min_size(Pos2::ZERO, self.input_state.screen_size);
+        let rects = ToastQueue::stack_rects(&style, screen, &heights);
+
+        let prev_overlay = painter.set_overlay(true);
+        for (toast, rect) in queue.toasts.iter_mut().zip(rects) {
+            toast.rect = rect;
+            paint_toast(&mut painter, rect, &toast.toast, &style, toast.opacity);
+        }
+        painter.set_overlay(prev_overlay);
+    }
+
+    /// Runs `f`, clipping everything it draws to the given `rect`. The rect is
+    /// intersected with the current clip rect, so nested clipping works as
+    /// expected, and the previous clip rect is restored afterwards.
+    ///
+    /// Like layouts, `rect` is affected by the current painter transform.
+    pub fn with_clip_rect<T>(&self, rect: Rect, f: impl FnOnce() -> T) -> T {
+        let old_clip_rect = {
+            let mut painter = self.painter();
+            let old_clip_rect = painter.clip_rect;
+            let rect = painter.transform.transform_rectangle(rect);
+            painter.clip_rect = old_clip_rect.intersect(rect);
+            old_clip_rect
+        };
+        let t = f();
+        self.painter().clip_rect = old_clip_rect;
+        t
+    }
+
     pub fn with_cursor_transform<T>(&self, tr: TranslateScale, f: impl FnOnce() -> T) -> T {
         let old = self
             .input_widget_state
@@ -203,6 +1354,71 @@ impl Context {
         t
     }
 
+    /// Sets the options used to turn shapes into triangles in
+    /// [`Context::tessellate`], like the bezier tolerance or anti-aliasing.
+    ///
+    /// Anti-aliasing is done by feathering the edges of shapes: When
+    /// `feathering` is enabled, a gradient `feathering_size_in_pixels` wide is
+    /// added around them. This size is in physical pixels, and is converted to
+    /// points dividing by `pixels_per_point`. Disabling feathering gives crisp,
+    /// aliased edges, which may be preferable for pixel art UIs.
+    pub fn set_tessellation_options(&mut self, options: TessellationOptions) {
+        self.tessellation_options = options;
+    }
+
+    /// Sets the reading direction of the UI. Containers use it to mirror their
+    /// layout for right-to-left languages. See [`Direction`].
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Enables or disables high contrast mode. When enabled, all the colors
+    /// drawn are pushed away from mid gray by [`Context::tessellate`], so the
+    /// difference between light and dark colors in the theme is emphasized.
+    ///
+    /// winit doesn't report the OS accessibility settings, so the application
+    /// is responsible for calling this.
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.high_contrast = high_contrast;
+    }
+
+    pub fn high_contrast(&self) -> bool {
+        self.high_contrast
+    }
+
+    /// Enables or disables reduced motion mode, for users sensitive to motion.
+    /// When enabled, [`Context::animate`] snaps to the end of animations, and
+    /// widgets avoid blinking or animating on their own.
+    ///
+    /// winit doesn't report the OS accessibility settings, so the application
+    /// is responsible for calling this.
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+    }
+
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Runs `f` using the given `theme` instead of the current one. The themes
+    /// are swapped back afterwards, so `theme` is left as it was. Like the
+    /// other `with_*` methods, the current theme is not restored if `f`
+    /// panics.
+    ///
+    /// # Panics
+    ///
+    /// If the theme is borrowed when calling this method.
+    pub fn with_theme<T>(&self, theme: &mut Theme, f: impl FnOnce() -> T) -> T {
+        std::mem::swap(&mut *self.theme.borrow_mut(), theme);
+        let t = f();
+        std::mem::swap(&mut *self.theme.borrow_mut(), theme);
+        t
+    }
+
     /// Sets the theme for this context to the given `theme`.
     pub fn set_theme(&mut self, theme: Theme) {
         self.theme = RefCell::new(theme);
@@ -0,0 +1,13 @@
+use std::any::Any;
+
+use crate::widget::DynWidget;
+
+/// An ongoing drag and drop operation. See [`Context::begin_drag`].
+///
+/// [`Context::begin_drag`]: crate::context::Context::begin_drag
+pub struct DragAndDrop {
+    /// The dragged data. Taken by the widget accepting the drop.
+    pub payload: Option<Box<dyn Any>>,
+    /// A widget drawn next to the cursor while dragging.
+    pub preview: Option<DynWidget>,
+}
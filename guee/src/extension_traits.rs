@@ -16,11 +16,25 @@ pub trait Color32Ext: Sized + Copy {
         this
     }
 
-    /// Sets the alpha for this color
+    /// Sets the alpha for this color. The color channels are un-premultiplied
+    /// first, so the hue of translucent colors is kept.
     fn with_alpha(self, new_alpha: u8) -> Self {
         let mut this = self;
         let color = this.get_color();
-        *color = Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), new_alpha);
+        let [r, g, b, _] = color.to_srgba_unmultiplied();
+        *color = Color32::from_rgba_unmultiplied(r, g, b, new_alpha);
+        this
+    }
+
+    /// Doubles the contrast of the color, pushing each channel away from the
+    /// middle of its range. Keeps alpha as-is.
+    fn high_contrast(self) -> Self {
+        let mut this = self;
+        let color = this.get_color();
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let [r, g, b] =
+            [r, g, b].map(|x| ((x as f32 - 127.5) * 2.0 + 127.5).clamp(0.0, 255.0) as u8);
+        *color = Color32::from_rgba_unmultiplied(r, g, b, a);
         this
     }
 
@@ -70,3 +84,27 @@ impl Vec2Ext for Vec2 {
         *self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_alpha_keeps_premultiplied_colors() {
+        let half_red = Color32::from_rgba_unmultiplied(255, 0, 0, 128);
+        let opaque = half_red.with_alpha(255);
+        assert_eq!(opaque, Color32::from_rgb(255, 0, 0));
+
+        let opaque_red = Color32::from_rgb(255, 0, 0);
+        assert_eq!(opaque_red.with_alpha(128), half_red);
+    }
+
+    #[test]
+    fn test_high_contrast() {
+        assert_eq!(
+            Color32::from_rgb(40, 200, 128).high_contrast(),
+            Color32::from_rgb(0, 255, 128)
+        );
+        assert_eq!(Color32::TRANSPARENT.high_contrast(), Color32::TRANSPARENT);
+    }
+}
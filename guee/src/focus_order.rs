@@ -0,0 +1,112 @@
+//! Keyboard focus traversal with the Tab key.
+//!
+//! Focusable widgets register themselves every frame with
+//! [`Context::register_focusable`](crate::context::Context::register_focusable).
+//! After the events of a frame are handled, pressing Tab moves the focus to
+//! the next registered widget, and Shift+Tab to the previous one.
+//!
+//! Like the HTML `tabindex`, widgets with an explicit tab index come first,
+//! in increasing order. Widgets without one come after, in reading order.
+//! Ties are broken by layout position: top to bottom, then left to right.
+
+use std::cmp::Ordering;
+
+use epaint::Rect;
+
+use crate::widget_id::WidgetId;
+
+/// A focusable widget registered for the current frame.
+#[derive(Clone, Debug)]
+pub struct FocusEntry {
+    pub widget_id: WidgetId,
+    pub rect: Rect,
+    pub tab_index: Option<i32>,
+}
+
+#[derive(Default)]
+pub struct FocusOrder {
+    pub entries: Vec<FocusEntry>,
+}
+
+impl FocusOrder {
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn register(&mut self, entry: FocusEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns the registered widgets in the order Tab goes through them.
+    pub fn sorted(&self) -> Vec<WidgetId> {
+        let mut entries: Vec<&FocusEntry> = self.entries.iter().collect();
+        // The sort is stable, so widgets at the same position keep the order
+        // in which they were registered
+        entries.sort_by(|a, b| {
+            let by_index = match (a.tab_index, b.tab_index) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            by_index
+                .then(a.rect.top().total_cmp(&b.rect.top()))
+                .then(a.rect.left().total_cmp(&b.rect.left()))
+        });
+        entries.into_iter().map(|e| e.widget_id).collect()
+    }
+
+    /// Returns the widget that should get the focus after (or before, when
+    /// `backwards` is set) the `current` one. When nothing is focused, or the
+    /// focused widget isn't registered, traversal starts from the ends.
+    pub fn next(&self, current: Option<WidgetId>, backwards: bool) -> Option<WidgetId> {
+        let order = self.sorted();
+        if order.is_empty() {
+            return None;
+        }
+        let position = current.and_then(|id| order.iter().position(|x| *x == id));
+        let idx = match (position, backwards) {
+            (Some(i), false) => (i + 1) % order.len(),
+            (Some(i), true) => (i + order.len() - 1) % order.len(),
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+        };
+        Some(order[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use epaint::{Pos2, Vec2};
+
+    #[test]
+    fn test_custom_tab_order() {
+        let mut order = FocusOrder::default();
+        let entry = |key: &str, x: f32, y: f32, tab_index| FocusEntry {
+            widget_id: WidgetId::new(key),
+            rect: Rect::from_min_size(Pos2::new(x, y), Vec2::new(10.0, 10.0)),
+            tab_index,
+        };
+        order.register(entry("bottom", 0.0, 50.0, None));
+        order.register(entry("top_right", 20.0, 0.0, None));
+        order.register(entry("top_left", 0.0, 0.0, None));
+        order.register(entry("submit", 0.0, 100.0, Some(1)));
+        order.register(entry("cancel", 0.0, 100.0, Some(2)));
+
+        let id = |key: &str| WidgetId::new(key);
+        assert_eq!(
+            order.sorted(),
+            vec![
+                id("submit"),
+                id("cancel"),
+                id("top_left"),
+                id("top_right"),
+                id("bottom")
+            ]
+        );
+        assert_eq!(order.next(None, false), Some(id("submit")));
+        assert_eq!(order.next(Some(id("bottom")), false), Some(id("submit")));
+        assert_eq!(order.next(Some(id("submit")), true), Some(id("bottom")));
+    }
+}
@@ -0,0 +1,174 @@
+//! A cache of text layouts, so unchanged text doesn't need to be laid out
+//! again every frame.
+
+use std::sync::Arc;
+
+use epaint::{ahash::HashMap, FontFamily, FontId, Galley};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct GalleyKey {
+    text: String,
+    family: FontFamily,
+    size_bits: u32,
+    wrap_width_bits: u32,
+}
+
+struct CachedGalley {
+    galley: Arc<Galley>,
+    /// The value of `GalleyCache::generation` when this entry was last used.
+    last_used: u64,
+}
+
+/// A least recently used cache of galleys, keyed by the text, font and wrap
+/// width they were laid out with. Galleys are reference counted, so returning
+/// them from the cache is cheap.
+pub struct GalleyCache {
+    entries: HashMap<GalleyKey, CachedGalley>,
+    capacity: usize,
+    /// Incremented on every lookup, to keep track of recently used entries.
+    generation: u64,
+}
+
+impl Default for GalleyCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl GalleyCache {
+    pub const DEFAULT_CAPACITY: usize = 2048;
+
+    /// Creates an empty cache holding at most `capacity` galleys.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::default(),
+            capacity: capacity.max(1),
+            generation: 0,
+        }
+    }
+
+    /// Returns the cached galley for the given parameters, or calls `layout`
+    /// to create it and stores the result. When the cache is full, the least
+    /// recently used entries are evicted.
+    pub fn get_or_insert_with(
+        &mut self,
+        text: String,
+        font_id: &FontId,
+        wrap_width: f32,
+        layout: impl FnOnce(String) -> Arc<Galley>,
+    ) -> Arc<Galley> {
+        self.generation += 1;
+        let key = GalleyKey {
+            text,
+            family: font_id.family.clone(),
+            size_bits: font_id.size.to_bits(),
+            wrap_width_bits: wrap_width.to_bits(),
+        };
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = self.generation;
+            return entry.galley.clone();
+        }
+
+        let galley = layout(key.text.clone());
+        if self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.entries.insert(
+            key,
+            CachedGalley {
+                galley: galley.clone(),
+                last_used: self.generation,
+            },
+        );
+        galley
+    }
+
+    /// Removes all the cached galleys. Must be called when the fonts change,
+    /// since the cached layouts are no longer valid.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Evicts the least recently used quarter of the cache. Evicting in
+    /// batches means the cost of finding old entries is paid only once in a
+    /// while, instead of on every insertion.
+    fn evict(&mut self) {
+        let mut last_used: Vec<u64> = self.entries.values().map(|e| e.last_used).collect();
+        let num_evicted = (self.capacity / 4).max(1).min(last_used.len());
+        let (_, threshold, _) = last_used.select_nth_unstable(num_evicted - 1);
+        let threshold = *threshold;
+        self.entries.retain(|_, e| e.last_used > threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use epaint::{text::FontDefinitions, Color32, Fonts};
+
+    use super::*;
+
+    fn layout_with(fonts: &Fonts, text: String) -> Arc<Galley> {
+        fonts.layout(
+            text,
+            FontId::proportional(14.0),
+            Color32::BLACK,
+            f32::INFINITY,
+        )
+    }
+
+    #[test]
+    fn test_cache_hits() {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        let mut cache = GalleyCache::default();
+        let font_id = FontId::proportional(14.0);
+        let mut layouts = 0;
+        for _ in 0..3 {
+            cache.get_or_insert_with("Hello".into(), &font_id, f32::INFINITY, |text| {
+                layouts += 1;
+                layout_with(&fonts, text)
+            });
+        }
+        assert_eq!(layouts, 1);
+
+        // A different wrap width is a different layout
+        cache.get_or_insert_with("Hello".into(), &font_id, 10.0, |text| {
+            layouts += 1;
+            layout_with(&fonts, text)
+        });
+        assert_eq!(layouts, 2);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        let mut cache = GalleyCache::new(4);
+        let font_id = FontId::proportional(14.0);
+        let get = |cache: &mut GalleyCache, text: &str| {
+            let mut hit = true;
+            cache.get_or_insert_with(text.into(), &font_id, f32::INFINITY, |text| {
+                hit = false;
+                layout_with(&fonts, text)
+            });
+            hit
+        };
+
+        for text in ["a", "b", "c", "d"] {
+            get(&mut cache, text);
+        }
+        // Touch "a", so "b" becomes the oldest entry
+        assert!(get(&mut cache, "a"));
+        assert!(!get(&mut cache, "e"));
+        assert_eq!(cache.len(), 4);
+        assert!(get(&mut cache, "a"));
+        assert!(!get(&mut cache, "b"));
+    }
+}
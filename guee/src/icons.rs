@@ -0,0 +1,47 @@
+//! Codepoints for glyph-based icons, to be used with the
+//! [`Icon`](crate::base_widgets::icon::Icon) widget.
+//!
+//! The constants in this module match the *Font Awesome 6 Free (Solid)* icon
+//! font. guee does not bundle the font itself: Register it as an
+//! [`ExtraFont`](crate::painter::ExtraFont) in the `FontFamily::Name` family
+//! given by [`DEFAULT_ICON_FAMILY`] (or any other name, see
+//! `Icon::font_family`) when creating the [`Context`](crate::context::Context).
+
+/// The font family name used by default by the `Icon` widget.
+pub const DEFAULT_ICON_FAMILY: &str = "icons";
+
+pub const BARS: char = '\u{f0c9}';
+pub const CHECK: char = '\u{f00c}';
+pub const CHEVRON_DOWN: char = '\u{f078}';
+pub const CHEVRON_LEFT: char = '\u{f053}';
+pub const CHEVRON_RIGHT: char = '\u{f054}';
+pub const CHEVRON_UP: char = '\u{f077}';
+pub const CIRCLE_CHECK: char = '\u{f058}';
+pub const CIRCLE_INFO: char = '\u{f05a}';
+pub const CIRCLE_XMARK: char = '\u{f057}';
+pub const COPY: char = '\u{f0c5}';
+pub const DOWNLOAD: char = '\u{f019}';
+pub const EYE: char = '\u{f06e}';
+pub const EYE_SLASH: char = '\u{f070}';
+pub const FILE: char = '\u{f15b}';
+pub const FLOPPY_DISK: char = '\u{f0c7}';
+pub const FOLDER: char = '\u{f07b}';
+pub const FOLDER_OPEN: char = '\u{f07c}';
+pub const GEAR: char = '\u{f013}';
+pub const HOUSE: char = '\u{f015}';
+pub const LOCK: char = '\u{f023}';
+pub const MAGNIFYING_GLASS: char = '\u{f002}';
+pub const MINUS: char = '\u{f068}';
+pub const PASTE: char = '\u{f0ea}';
+pub const PAUSE: char = '\u{f04c}';
+pub const PEN: char = '\u{f304}';
+pub const PLAY: char = '\u{f04b}';
+pub const PLUS: char = '\u{2b}';
+pub const ROTATE_LEFT: char = '\u{f2ea}';
+pub const ROTATE_RIGHT: char = '\u{f2f9}';
+pub const STOP: char = '\u{f04d}';
+pub const TRASH: char = '\u{f1f8}';
+pub const TRIANGLE_EXCLAMATION: char = '\u{f071}';
+pub const UNLOCK: char = '\u{f09c}';
+pub const UPLOAD: char = '\u{f093}';
+pub const XMARK: char = '\u{f00d}';
@@ -0,0 +1,67 @@
+use epaint::ColorImage;
+
+/// The error returned when an encoded image can't be turned into a texture.
+#[derive(Clone, Debug)]
+pub enum ImageLoadError {
+    /// The format of the image bytes could not be guessed.
+    UnknownFormat,
+    /// The image format was recognized, but is not one of the supported
+    /// formats (PNG, JPEG).
+    UnsupportedFormat(String),
+    /// The image bytes could not be decoded.
+    Decode(String),
+}
+
+impl std::fmt::Display for ImageLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageLoadError::UnknownFormat => write!(f, "Could not guess the image format"),
+            ImageLoadError::UnsupportedFormat(format) => write!(
+                f,
+                "Unsupported image format: {format}. Only PNG and JPEG are supported"
+            ),
+            ImageLoadError::Decode(err) => write!(f, "Error decoding image: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ImageLoadError {}
+
+/// Decodes the given PNG or JPEG `bytes` into an image that can be uploaded
+/// as a texture.
+pub fn decode_image(bytes: &[u8]) -> Result<ColorImage, ImageLoadError> {
+    let format = image::guess_format(bytes).map_err(|_| ImageLoadError::UnknownFormat)?;
+    match format {
+        image::ImageFormat::Png | image::ImageFormat::Jpeg => {}
+        other => return Err(ImageLoadError::UnsupportedFormat(format!("{other:?}"))),
+    }
+
+    let image = image::load_from_memory_with_format(bytes, format)
+        .map_err(|err| ImageLoadError::Decode(err.to_string()))?;
+    let rgba = image.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Ok(ColorImage::from_rgba_unmultiplied(
+        size,
+        rgba.as_flat_samples().as_slice(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_format() {
+        let result = decode_image(b"definitely not an image");
+        assert!(matches!(result, Err(ImageLoadError::UnknownFormat)));
+    }
+
+    #[test]
+    fn test_corrupt_png() {
+        // A valid PNG signature, followed by garbage instead of the chunks.
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        bytes.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef, 0x00, 0x01, 0x02, 0x03]);
+        let result = decode_image(&bytes);
+        assert!(matches!(result, Err(ImageLoadError::Decode(_))));
+    }
+}
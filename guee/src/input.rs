@@ -22,6 +22,19 @@ pub enum Event {
     KeyReleased(VirtualKeyCode),
 }
 
+/// Changes to the OS cursor requested by widgets during a frame. See
+/// [`Context::apply_cursor_requests`](crate::context::Context::apply_cursor_requests).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CursorRequests {
+    /// When set, the cursor should be hidden or shown again.
+    pub hidden: Option<bool>,
+    /// When set, the cursor should be moved to this position, in screen
+    /// coordinates.
+    pub warp_to: Option<Pos2>,
+}
+
+/// Whether the events of the current frame were already handled by some
+/// widget. See [`Widget::on_event`](crate::widget::Widget::on_event).
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum EventStatus {
     Ignored,
@@ -43,6 +56,12 @@ impl EventStatus {
         }
     }
 
+    pub fn is_ignored(&self) -> bool {
+        !self.is_consumed()
+    }
+
+    /// Marks the events as handled. Consuming them twice is logged, since it
+    /// usually means two widgets reacted to the same event.
     pub fn consume_event(&mut self) {
         if self.is_consumed() {
             log::warn!("Widget consuming an already consumed event!");
@@ -51,6 +70,28 @@ impl EventStatus {
     }
 }
 
+/// A change in the focus state of a widget since the previous frame. See
+/// [`Context::focus_transition`](crate::context::Context::focus_transition).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FocusTransition {
+    Unchanged,
+    Gained,
+    Lost,
+}
+
+impl FocusTransition {
+    pub fn gained(&self) -> bool {
+        matches!(self, FocusTransition::Gained)
+    }
+
+    pub fn lost(&self) -> bool {
+        matches!(self, FocusTransition::Lost)
+    }
+}
+
+/// Stored in the widget memory to detect focus transitions.
+pub(crate) struct LastFocusState(pub bool);
+
 #[derive(Copy, Clone, Debug, Default)]
 pub enum ClickDragState {
     /// The mouse button isn't pressed
@@ -131,6 +172,9 @@ pub struct InputWidgetState {
     pub cursor_transform: TranslateScale,
     pub focus: Option<WidgetId>,
     pub drag: Option<WidgetId>,
+    /// The widget receiving all pointer events, see
+    /// [`Context::capture_pointer`](crate::context::Context::capture_pointer).
+    pub pointer_capture: Option<WidgetId>,
 }
 
 impl InputWidgetState {
@@ -161,7 +205,7 @@ impl ButtonStateMap {
     pub fn is_released(&self, button: MouseButton) -> bool {
         self.state
             .get(&button)
-            .map(|x| !x.just_released)
+            .map(|x| x.just_released)
             .unwrap_or(false)
     }
 
@@ -268,6 +312,11 @@ impl InputState {
         self.mouse.prev_position = self.mouse.position;
         self.mouse.button_state.end_frame();
         widget_state.cursor_transform = TranslateScale::identity();
+        // Unlike drags, pointer capture is released at the end of the frame,
+        // so the capturing widget still gets to see the button release.
+        if !self.mouse.button_state.state.values().any(|b| b.down) {
+            widget_state.pointer_capture = None;
+        }
     }
 
     pub fn on_winit_event(&mut self, widget_state: &mut InputWidgetState, ev: &WindowEvent) {
@@ -352,3 +401,26 @@ fn is_printable_char(chr: char) -> bool {
 
     !is_in_private_use_area && !chr.is_ascii_control()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_status() {
+        let mut status = EventStatus::Ignored;
+        assert!(status.is_ignored());
+        status.consume_event();
+        assert!(status.is_consumed());
+        status.consume_event();
+        assert_eq!(status, EventStatus::Consumed);
+
+        let mut called = false;
+        let status = status.or_else(|| {
+            called = true;
+            EventStatus::Ignored
+        });
+        assert!(!called);
+        assert_eq!(status, EventStatus::Consumed);
+    }
+}
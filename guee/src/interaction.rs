@@ -0,0 +1,76 @@
+//! Shared logic to pick the appearance of interactive widgets, like buttons,
+//! depending on what the user is doing with them.
+
+use epaint::{Color32, Stroke};
+
+/// The states an interactive widget can be in. Several can be active at the
+/// same time, see [`InteractionState::resolve`] for their priority.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InteractionState {
+    pub hovered: bool,
+    pub pressed: bool,
+    pub focused: bool,
+    pub disabled: bool,
+}
+
+/// The single state that decides the appearance of a widget.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interaction {
+    Idle,
+    Focused,
+    Hovered,
+    Pressed,
+    Disabled,
+}
+
+impl InteractionState {
+    /// Returns the state with the highest priority: Disabled widgets always
+    /// look disabled, then pressed goes over hovered, and hovered goes over
+    /// focused.
+    pub fn resolve(&self) -> Interaction {
+        if self.disabled {
+            Interaction::Disabled
+        } else if self.pressed {
+            Interaction::Pressed
+        } else if self.hovered {
+            Interaction::Hovered
+        } else if self.focused {
+            Interaction::Focused
+        } else {
+            Interaction::Idle
+        }
+    }
+}
+
+/// Implemented by the styles of interactive widgets, so they all compute
+/// their appearance the same way.
+pub trait InteractionStyle {
+    /// Returns the fill and stroke for the given `state`.
+    fn resolve(&self, state: InteractionState) -> (Color32, Stroke);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_priority() {
+        let state = InteractionState {
+            hovered: true,
+            pressed: true,
+            focused: true,
+            disabled: false,
+        };
+        assert_eq!(state.resolve(), Interaction::Pressed);
+        let state = InteractionState {
+            disabled: true,
+            ..state
+        };
+        assert_eq!(state.resolve(), Interaction::Disabled);
+        let state = InteractionState {
+            focused: true,
+            ..Default::default()
+        };
+        assert_eq!(state.resolve(), Interaction::Focused);
+    }
+}
@@ -2,6 +2,7 @@ use epaint::{Pos2, Rect, Vec2};
 
 use crate::widget_id::WidgetId;
 
+#[derive(Clone, Debug)]
 pub struct Layout {
     // Bounds of this node. When creating this in a `layout` callback, it is
     // relative to its parent. The engine will convert the bounds to absolute
@@ -29,6 +30,20 @@ pub enum Align {
     Center,
 }
 
+/// The horizontal direction in which the UI is read. In right-to-left mode,
+/// containers mirror their layout horizontally: Rows are laid out from right
+/// to left, and `Align::Start` means the right side.
+///
+/// This only affects layout. Text is shaped by epaint, which has no support
+/// for bidirectional text, so right-to-left scripts are laid out glyph by glyph
+/// in logical order and can appear reversed or unjoined.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum SizeHint {
     #[default]
@@ -162,11 +177,26 @@ impl Layout {
         self
     }
 
+    /// Mirrors the horizontal position of this layout inside a parent of the
+    /// given `width`, for right-to-left layouts.
+    pub fn mirror_x(&mut self, width: f32) {
+        let new_min_x = width - self.bounds.max.x;
+        self.translate_x(new_min_x - self.bounds.min.x);
+    }
+
     pub fn clear_translation(self) -> Self {
         let delta = self.bounds.min.to_vec2();
         self.translated(-delta)
     }
 
+    /// Searches this layout tree for the node with the given `widget_id`.
+    pub fn find(&self, widget_id: WidgetId) -> Option<&Layout> {
+        if self.widget_id == widget_id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|ch| ch.find(widget_id))
+    }
+
     pub fn to_absolute(&mut self, parent_offset: Vec2) {
         self.bounds = self.bounds.translate(parent_offset);
         for ch in &mut self.children {
@@ -175,6 +205,38 @@ impl Layout {
     }
 }
 
+/// A value for each of the four sides of a rectangle, like its borders.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Edges<T> {
+    pub left: T,
+    pub right: T,
+    pub top: T,
+    pub bottom: T,
+}
+
+impl<T: Clone> Edges<T> {
+    /// Uses the same value for all sides.
+    pub fn all(value: T) -> Self {
+        Self {
+            left: value.clone(),
+            right: value.clone(),
+            top: value.clone(),
+            bottom: value,
+        }
+    }
+
+    /// Uses one value for the left and right sides, and another for the top
+    /// and bottom.
+    pub fn symmetric(horizontal: T, vertical: T) -> Self {
+        Self {
+            left: horizontal.clone(),
+            right: horizontal,
+            top: vertical.clone(),
+            bottom: vertical,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Axis {
     Vertical,
@@ -10,18 +10,59 @@ pub mod widget;
 
 pub mod painter;
 
+pub mod galley_cache;
+
+pub mod text_spacing;
+
+pub mod renderer;
+
+#[cfg(feature = "software")]
+pub mod software_renderer;
+
 pub mod context;
 
+pub mod surface;
+
 pub mod input;
 
+pub mod interaction;
+
+pub mod access_keys;
+
+pub mod focus_order;
+
+pub mod shortcuts;
+
+pub mod drag_and_drop;
+
+pub mod popup;
+
+pub mod animation;
+
+pub mod toast;
+
+pub mod repeat;
+
+pub mod snap;
+
 pub mod base_widgets;
 
 pub mod callback;
 
 pub mod callback_accessor;
 
+pub mod ui;
+
 pub mod memory;
 
 pub mod theme;
 
+#[cfg(feature = "hot_reload")]
+pub mod theme_watcher;
+
 pub mod extension_traits;
+
+pub mod icons;
+
+#[cfg(feature = "images")]
+pub mod image_loading;
@@ -1,6 +1,7 @@
 use std::{
     any::{Any, TypeId},
     cell::{Ref, RefCell, RefMut},
+    marker::PhantomData,
     ops::{Deref, DerefMut},
 };
 
@@ -45,6 +46,14 @@ impl Memory {
         }
     }
 
+    /// Returns the value of type `T` for the given `id`.
+    ///
+    /// # Panics
+    ///
+    /// When there's no value. Use this when the value is known to exist, e.g.
+    /// because one of the `get_or` variants was called for it earlier in the
+    /// same frame. Otherwise, use [`Memory::try_get`] or the `get_or`
+    /// variants.
     pub fn get<T: 'static>(&self, id: WidgetId) -> impl Deref<Target = T> + '_ {
         let mem = self.widget_memory.borrow();
         Ref::map(mem, |x| {
@@ -55,6 +64,11 @@ impl Memory {
         })
     }
 
+    /// Like [`Memory::get`], but for mutable access.
+    ///
+    /// # Panics
+    ///
+    /// When there's no value. See [`Memory::get`].
     #[track_caller]
     pub fn get_mut<T: 'static>(&self, id: WidgetId) -> impl DerefMut<Target = T> + '_ {
         let mem = self.widget_memory.borrow_mut();
@@ -66,6 +80,28 @@ impl Memory {
         })
     }
 
+    /// Returns the value of type `T` for the given `id`, or `None` if there's
+    /// no value. Useful in code that can't be sure the value was created,
+    /// like a `draw` that relies on state created during `on_event`.
+    pub fn try_get<T: 'static>(&self, id: WidgetId) -> Option<impl Deref<Target = T> + '_> {
+        let mem = self.widget_memory.borrow();
+        Ref::filter_map(mem, |x| {
+            x.get(&Self::key::<T>(id))
+                .map(|v| v.downcast_ref::<T>().expect("Failed downcast"))
+        })
+        .ok()
+    }
+
+    /// Like [`Memory::try_get`], but for mutable access.
+    pub fn try_get_mut<T: 'static>(&self, id: WidgetId) -> Option<impl DerefMut<Target = T> + '_> {
+        let mem = self.widget_memory.borrow_mut();
+        RefMut::filter_map(mem, |x| {
+            x.get_mut(&Self::key::<T>(id))
+                .map(|v| v.downcast_mut::<T>().expect("Failed downcast"))
+        })
+        .ok()
+    }
+
     pub fn get_or_default<T: Default + 'static>(
         &self,
         id: WidgetId,
@@ -92,3 +128,101 @@ impl Memory {
         self.get_mut(id)
     }
 }
+
+/// A handle to the state of type `T` of a single widget, so the widget id
+/// doesn't need to be repeated on every access. Create it once with
+/// [`Context::state`], using the resolved id of the widget, and use it for
+/// all the accesses in `layout`, `draw` or `on_event`.
+///
+/// [`Context::state`]: crate::context::Context::state
+pub struct WidgetState<'a, T> {
+    memory: &'a Memory,
+    id: WidgetId,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T: 'static> WidgetState<'a, T> {
+    pub fn new(memory: &'a Memory, id: WidgetId) -> Self {
+        Self {
+            memory,
+            id,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id(&self) -> WidgetId {
+        self.id
+    }
+
+    pub fn set(&self, t: T) {
+        self.memory.set(self.id, t)
+    }
+
+    /// See [`Memory::get`].
+    pub fn get(&self) -> impl Deref<Target = T> + 'a {
+        self.memory.get(self.id)
+    }
+
+    /// See [`Memory::get_mut`].
+    #[track_caller]
+    pub fn get_mut(&self) -> impl DerefMut<Target = T> + 'a {
+        self.memory.get_mut(self.id)
+    }
+
+    pub fn try_get(&self) -> Option<impl Deref<Target = T> + 'a> {
+        self.memory.try_get(self.id)
+    }
+
+    pub fn try_get_mut(&self) -> Option<impl DerefMut<Target = T> + 'a> {
+        self.memory.try_get_mut(self.id)
+    }
+
+    pub fn get_or(&self, t: T) -> impl Deref<Target = T> + 'a {
+        self.memory.get_or(self.id, t)
+    }
+
+    pub fn get_mut_or(&self, t: T) -> impl DerefMut<Target = T> + 'a {
+        self.memory.get_mut_or(self.id, t)
+    }
+}
+
+impl<'a, T: Default + 'static> WidgetState<'a, T> {
+    pub fn get_or_default(&self) -> impl Deref<Target = T> + 'a {
+        self.memory.get_or_default(self.id)
+    }
+
+    pub fn get_mut_or_default(&self) -> impl DerefMut<Target = T> + 'a {
+        self.memory.get_mut_or_default(self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_get() {
+        let memory = Memory::default();
+        let id = WidgetId::new("widget");
+        assert!(memory.try_get::<u32>(id).is_none());
+        assert!(memory.try_get_mut::<u32>(id).is_none());
+
+        memory.set(id, 1u32);
+        *memory.try_get_mut::<u32>(id).unwrap() += 1;
+        assert_eq!(*memory.try_get::<u32>(id).unwrap(), 2);
+        // Values are keyed by type too
+        assert!(memory.try_get::<f32>(id).is_none());
+    }
+
+    #[test]
+    fn test_widget_state() {
+        let memory = Memory::default();
+        let id = WidgetId::new("widget");
+        let state = WidgetState::<Vec<u32>>::new(&memory, id);
+        assert!(state.try_get().is_none());
+        state.get_mut_or_default().push(1);
+        state.get_mut().push(2);
+        assert_eq!(*state.get(), vec![1, 2]);
+        assert_eq!(*memory.get::<Vec<u32>>(id), vec![1, 2]);
+    }
+}
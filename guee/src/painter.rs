@@ -1,20 +1,125 @@
-use std::sync::Arc;
+use std::{f32::consts::PI, sync::Arc};
 
 use epaint::{
-    emath::Align2,
-    text::{FontData, FontDefinitions},
-    CircleShape, ClippedShape, Color32, CubicBezierShape, FontFamily, FontId, Fonts, Galley, Mesh,
-    Pos2, Rect, RectShape, Rounding, Stroke, TextShape, TextureId, Vec2,
+    ahash::HashMap,
+    emath::{lerp, Align2},
+    text::{cursor::Cursor, FontData, FontDefinitions, LayoutJob},
+    textures::{TextureManager, TextureOptions, TexturesDelta},
+    CircleShape, ClippedShape, Color32, ColorImage, CubicBezierShape, FontFamily, FontId, Fonts,
+    Galley, Mesh, Pos2, Rect, RectShape, Rounding, Shadow, Stroke, TextShape, TextureId, Vec2,
+    Vertex, WHITE_UV,
 };
 
+use crate::{galley_cache::GalleyCache, text_spacing::TextSpacing, theme::FocusRingStyle};
+
 pub struct Painter {
     pub clip_rect: Rect,
+    /// The whole screen, as given to [`Painter::prepare`].
+    pub screen_rect: Rect,
     pub text_color: Color32,
     pub shapes: Vec<ClippedShape>,
     pub overlay_shapes: Vec<ClippedShape>,
     pub transform: TranslateScale,
     pub use_overlay: bool,
     pub fonts: Fonts,
+    /// Keeps track of all the textures used by the UI, including the font
+    /// atlas, which is always allocated as `TextureId::default()`.
+    pub textures: TextureManager,
+    /// Caches the galleys created by [`Painter::galley`].
+    pub galley_cache: GalleyCache,
+}
+
+/// The direction of a [`GradientSpec`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GradientDirection {
+    LeftToRight,
+    TopToBottom,
+}
+
+/// A linear gradient between two colors, going across the whole rect it's
+/// painted on. See [`Painter::gradient_rect`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GradientSpec {
+    pub start: Color32,
+    pub end: Color32,
+    pub direction: GradientDirection,
+}
+
+impl GradientSpec {
+    pub fn horizontal(start: Color32, end: Color32) -> Self {
+        Self {
+            start,
+            end,
+            direction: GradientDirection::LeftToRight,
+        }
+    }
+
+    pub fn vertical(start: Color32, end: Color32) -> Self {
+        Self {
+            start,
+            end,
+            direction: GradientDirection::TopToBottom,
+        }
+    }
+
+    /// Returns the color of the gradient at `pos`, when painted over `rect`.
+    pub fn color_at(&self, rect: Rect, pos: Pos2) -> Color32 {
+        let t = match self.direction {
+            GradientDirection::LeftToRight => (pos.x - rect.left()) / rect.width(),
+            GradientDirection::TopToBottom => (pos.y - rect.top()) / rect.height(),
+        };
+        let t = if t.is_finite() {
+            t.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        // Color32 is premultiplied, so channels can be interpolated directly
+        let channel = |a: u8, b: u8| lerp(a as f32..=b as f32, t).round() as u8;
+        Color32::from_rgba_premultiplied(
+            channel(self.start.r(), self.end.r()),
+            channel(self.start.g(), self.end.g()),
+            channel(self.start.b(), self.end.b()),
+            channel(self.start.a(), self.end.a()),
+        )
+    }
+}
+
+/// Returns the points around a rounded rect in clockwise order, starting at
+/// the top left corner.
+fn rounded_rect_outline(rect: Rect, rounding: Rounding) -> Vec<Pos2> {
+    const CORNER_SEGMENTS: usize = 8;
+    let max_radius = rect.width().min(rect.height()) / 2.0;
+    // Corner centers, radii and the angle where each arc starts
+    let corners = [
+        (rect.left_top(), rounding.nw, Vec2::new(1.0, 1.0), PI),
+        (
+            rect.right_top(),
+            rounding.ne,
+            Vec2::new(-1.0, 1.0),
+            1.5 * PI,
+        ),
+        (rect.right_bottom(), rounding.se, Vec2::new(-1.0, -1.0), 0.0),
+        (
+            rect.left_bottom(),
+            rounding.sw,
+            Vec2::new(1.0, -1.0),
+            0.5 * PI,
+        ),
+    ];
+    let mut points = Vec::new();
+    for (corner, radius, inwards, start_angle) in corners {
+        let radius = radius.clamp(0.0, max_radius);
+        if radius <= 0.0 {
+            points.push(corner);
+            continue;
+        }
+        let center = corner + inwards * radius;
+        for i in 0..=CORNER_SEGMENTS {
+            let angle = start_angle + 0.5 * PI * i as f32 / CORNER_SEGMENTS as f32;
+            points.push(center + radius * Vec2::angled(angle));
+        }
+    }
+    points
 }
 
 /// Wraps an `epaint::galley`. This is necessary because epaint galleys don't
@@ -32,6 +137,16 @@ impl GueeGalley {
     pub fn bounds(&self) -> Rect {
         self.epaint_galley.rect
     }
+
+    /// Returns the text cursor closest to `pos`, for text drawn with its top
+    /// left corner at `text_pos`. Positions outside the text snap to the
+    /// nearest row and character, so clicks anywhere give a valid cursor.
+    ///
+    /// Both positions are in layout coordinates, which are the ones given to
+    /// `Widget::on_event`. Those already account for the painter transform.
+    pub fn cursor_from_pos(&self, text_pos: Pos2, pos: Pos2) -> Cursor {
+        self.epaint_galley.cursor_from_pos(pos - text_pos)
+    }
 }
 
 pub struct GueeTextShape {
@@ -41,7 +156,12 @@ pub struct GueeTextShape {
     pub angle: f32,
 }
 
+/// An additional font to register in the painter's font definitions.
+#[derive(Clone)]
 pub struct ExtraFont {
+    /// The family this font is added to. Extra fonts take priority over the
+    /// default fonts of their family. Use `FontFamily::Name` to register a new
+    /// named family, e.g. for icon fonts.
     pub font_family: FontFamily,
     pub name: &'static str,
     pub data: &'static [u8],
@@ -51,31 +171,87 @@ pub struct ExtraFont {
 impl Painter {
     pub fn new(extra_fonts: Vec<ExtraFont>) -> Self {
         let mut font_defs = FontDefinitions::default();
-        for (i, extra_font) in extra_fonts.into_iter().enumerate() {
+        // Number of extra fonts inserted so far in each family, so that extra
+        // fonts keep the order they were given in.
+        let mut inserted_per_family = HashMap::<FontFamily, usize>::default();
+        for extra_font in extra_fonts {
             font_defs.font_data.insert(
                 extra_font.name.to_owned(),
                 FontData::from_static(extra_font.data),
             );
+            let idx = inserted_per_family
+                .entry(extra_font.font_family.clone())
+                .or_default();
             font_defs
                 .families
-                .entry(epaint::FontFamily::Proportional)
+                .entry(extra_font.font_family)
                 .or_default()
-                .insert(i, extra_font.name.to_string())
+                .insert(*idx, extra_font.name.to_string());
+            *idx += 1;
         }
 
+        let fonts = Fonts::new(1.0, 1024, font_defs);
+
+        let mut textures = TextureManager::default();
+        let font_texture = textures.alloc(
+            "guee_font_texture".into(),
+            fonts.image().into(),
+            TextureOptions::LINEAR,
+        );
+        assert_eq!(
+            font_texture,
+            TextureId::default(),
+            "The font texture should be the first allocated texture"
+        );
+
         Self {
             clip_rect: Rect::from_min_max(Pos2::ZERO, Pos2::ZERO),
+            screen_rect: Rect::from_min_max(Pos2::ZERO, Pos2::ZERO),
             text_color: Color32::BLACK,
             shapes: Vec::new(),
             overlay_shapes: Vec::new(),
             transform: TranslateScale::identity(),
             use_overlay: false,
-            fonts: Fonts::new(1.0, 1024, font_defs),
+            fonts,
+            textures,
+            galley_cache: GalleyCache::default(),
+        }
+    }
+
+    /// Uploads the given `image` as a new texture, and returns its id. The
+    /// texture will be sent to the renderer the next time
+    /// [`Painter::take_textures_delta`] is called.
+    pub fn load_texture(&mut self, name: impl Into<String>, image: ColorImage) -> TextureId {
+        self.textures
+            .alloc(name.into(), image.into(), TextureOptions::LINEAR)
+    }
+
+    /// Frees a texture previously allocated with [`Painter::load_texture`].
+    pub fn free_texture(&mut self, texture_id: TextureId) {
+        self.textures.free(texture_id);
+    }
+
+    /// Returns the size in pixels of the given texture, if it is managed by
+    /// this painter.
+    pub fn texture_size(&self, texture_id: TextureId) -> Option<Vec2> {
+        self.textures
+            .meta(texture_id)
+            .map(|meta| Vec2::new(meta.size[0] as f32, meta.size[1] as f32))
+    }
+
+    /// Returns all the texture changes since the last call to this function,
+    /// including font atlas updates. Renderers should apply this delta before
+    /// drawing the frame.
+    pub fn take_textures_delta(&mut self) -> TexturesDelta {
+        if let Some(font_image_delta) = self.fonts.font_image_delta() {
+            self.textures.set(TextureId::default(), font_image_delta);
         }
+        self.textures.take_delta()
     }
 
     pub fn prepare(&mut self, clip_rect: Rect, text_color: Color32) {
         self.clip_rect = clip_rect;
+        self.screen_rect = clip_rect;
         self.text_color = text_color;
     }
 
@@ -96,6 +272,46 @@ impl Painter {
         self.use_overlay = old_overlay;
     }
 
+    /// Draws an outline around the given `rect` to show it has keyboard
+    /// focus. The outline is drawn outside the rect, so it doesn't overlap the
+    /// widget's contents.
+    pub fn focus_ring(&mut self, rect: Rect, style: &FocusRingStyle) {
+        // Strokes are centered on the rect's edges, so expand by half the
+        // width to get the whole stroke outside.
+        let expand = style.offset + style.width / 2.0;
+        self.rect(RectShape {
+            rect: rect.expand(expand),
+            rounding: Rounding {
+                nw: style.rounding.nw + expand,
+                ne: style.rounding.ne + expand,
+                sw: style.rounding.sw + expand,
+                se: style.rounding.se + expand,
+            },
+            fill: Color32::TRANSPARENT,
+            stroke: Stroke::new(style.width, style.color),
+        });
+    }
+
+    /// Covers the whole screen with a translucent `color`, dimming the shapes
+    /// drawn so far in the current layer and all the shapes in the layers
+    /// below it. Shapes pushed afterwards to the current layer are not
+    /// affected, but shapes pushed to a layer below are dimmed too, even if
+    /// they are pushed after this call, since each layer is drawn as a whole.
+    ///
+    /// This is meant for modals, which should call this from the overlay
+    /// layer right before drawing their contents, so the rest of the UI looks
+    /// disabled behind them.
+    pub fn dim_below(&mut self, color: Color32) {
+        let shape = epaint::Shape::rect_filled(self.screen_rect, Rounding::none(), color);
+        // Ignore the current clip rect, the whole screen is dimmed.
+        let clipped = ClippedShape(self.screen_rect, shape);
+        if self.use_overlay {
+            self.overlay_shapes.push(clipped)
+        } else {
+            self.shapes.push(clipped)
+        }
+    }
+
     /// Pushes a shape to be drawn
     pub fn push_shape(&mut self, shape: epaint::Shape) {
         if self.use_overlay {
@@ -123,6 +339,51 @@ impl Painter {
         }));
     }
 
+    /// Fills `rect` with a linear gradient. Unlike `epaint` rects, the fill
+    /// is built as a mesh, so the outline for the `rounding` is approximated
+    /// with a few segments per corner.
+    pub fn gradient_rect(&mut self, rect: Rect, rounding: Rounding, gradient: GradientSpec) {
+        let rect = self.transform.transform_rectangle(rect);
+        let rounding = self.transform.transform_rounding(rounding);
+        let outline = rounded_rect_outline(rect, rounding);
+
+        // The outline is convex, so it can be triangulated as a fan around
+        // the center
+        let mut mesh = Mesh::default();
+        let color_at = |pos: Pos2| gradient.color_at(rect, pos);
+        mesh.vertices.push(Vertex {
+            pos: rect.center(),
+            uv: WHITE_UV,
+            color: color_at(rect.center()),
+        });
+        for &pos in &outline {
+            mesh.vertices.push(Vertex {
+                pos,
+                uv: WHITE_UV,
+                color: color_at(pos),
+            });
+        }
+        let n = outline.len() as u32;
+        for i in 0..n {
+            mesh.add_triangle(0, 1 + i, 1 + (i + 1) % n);
+        }
+        self.push_shape(epaint::Shape::mesh(mesh));
+    }
+
+    /// Paints a soft shadow around `rect`, fading out over `elevation`
+    /// points. Should be drawn before the shape casting it.
+    pub fn drop_shadow(&mut self, rect: Rect, rounding: Rounding, elevation: f32, color: Color32) {
+        let shadow = Shadow {
+            extrusion: self.transform.transform_scalar(elevation),
+            color,
+        };
+        let mesh = shadow.tessellate(
+            self.transform.transform_rectangle(rect),
+            self.transform.transform_rounding(rounding),
+        );
+        self.push_shape(epaint::Shape::mesh(mesh));
+    }
+
     /// Paints the given `CircleShape`
     pub fn circle(&mut self, circle_shape: CircleShape) {
         let CircleShape {
@@ -148,19 +409,67 @@ impl Painter {
         self.push_shape(epaint::Shape::mesh(mesh));
     }
 
+    /// Lays out the given text. Results are cached, so calling this every
+    /// frame with the same arguments is cheap.
     pub fn galley(&mut self, contents: String, font_id: FontId, wrap_width: f32) -> GueeGalley {
         GueeGalley {
-            epaint_galley: self.fonts.layout(
-                contents,
-                font_id.clone(),
-                Color32::BLACK, // Ignored
-                wrap_width,
-            ),
+            epaint_galley: self.layout_cached(contents, &font_id, wrap_width),
             font_id,
             wrap_width,
         }
     }
 
+    /// Like [`Painter::galley`], but applies the given line height and letter
+    /// spacing to the text.
+    pub fn galley_with_spacing(
+        &mut self,
+        contents: String,
+        font_id: FontId,
+        wrap_width: f32,
+        spacing: TextSpacing,
+    ) -> GueeGalley {
+        let mut galley = self.galley(contents, font_id, wrap_width);
+        if !spacing.is_default() {
+            galley.epaint_galley =
+                Arc::new(spacing.apply(&galley.epaint_galley, galley.font_id.size));
+        }
+        galley
+    }
+
+    /// Returns the size the given text would take when laid out with
+    /// [`Painter::galley`].
+    pub fn measure_text(&mut self, contents: String, font_id: FontId, wrap_width: f32) -> Vec2 {
+        self.layout_cached(contents, &font_id, wrap_width)
+            .rect
+            .size()
+    }
+
+    fn layout_cached(
+        &mut self,
+        contents: String,
+        font_id: &FontId,
+        wrap_width: f32,
+    ) -> Arc<Galley> {
+        let fonts = &self.fonts;
+        self.galley_cache
+            .get_or_insert_with(contents, font_id, wrap_width, |contents| {
+                fonts.layout(
+                    contents,
+                    font_id.clone(),
+                    Color32::BLACK, // Ignored
+                    wrap_width,
+                )
+            })
+    }
+
+    /// Replaces the fonts used to draw text. Previously created galleys
+    /// should not be drawn anymore, since they refer to the old fonts.
+    pub fn set_font_definitions(&mut self, font_defs: FontDefinitions) {
+        self.fonts = Fonts::new(1.0, 1024, font_defs);
+        // The new font atlas will be uploaded in the next textures delta
+        self.galley_cache.clear();
+    }
+
     /// Paints the given `TextShape`.
     ///
     /// ## Text color
@@ -182,10 +491,9 @@ impl Painter {
             font_id.size = self.transform.transform_scalar(font_id.size);
             let wrap_width = self.transform.transform_scalar(galley.wrap_width);
             GueeGalley {
-                epaint_galley: self.fonts.layout(
+                epaint_galley: self.layout_cached(
                     galley.epaint_galley.job.text.clone(),
-                    font_id.clone(),
-                    Color32::BLACK, // Ignored
+                    &font_id,
                     wrap_width,
                 ),
                 font_id,
@@ -204,6 +512,40 @@ impl Painter {
         }));
     }
 
+    /// Lays out text with mixed formatting. Unlike [`Painter::galley`], the
+    /// result is not cached.
+    pub fn layout_job(&mut self, job: LayoutJob) -> Arc<Galley> {
+        self.fonts.layout_job(job)
+    }
+
+    /// Draws a galley created with [`Painter::layout_job`], keeping the colors
+    /// of its sections. Under a scaled transform, the job is laid out again at
+    /// the new size, so text and decorations stay crisp.
+    pub fn rich_text(&mut self, pos: Pos2, galley: Arc<Galley>) {
+        let galley = if self.transform.scale != 1.0 {
+            let mut job = (*galley.job).clone();
+            for section in &mut job.sections {
+                let format = &mut section.format;
+                format.font_id.size = self.transform.transform_scalar(format.font_id.size);
+                format.underline.width = self.transform.transform_scalar(format.underline.width);
+                format.strikethrough.width =
+                    self.transform.transform_scalar(format.strikethrough.width);
+            }
+            job.wrap.max_width = self.transform.transform_scalar(job.wrap.max_width);
+            self.fonts.layout_job(job)
+        } else {
+            galley
+        };
+
+        self.push_shape(epaint::Shape::Text(TextShape {
+            pos: self.transform.transform_point(pos),
+            override_text_color: None,
+            galley,
+            underline: Stroke::NONE,
+            angle: 0.0,
+        }));
+    }
+
     pub fn text(&mut self, pos: Pos2, align: Align2, label: impl ToString, font: FontId) {
         let galley = self.galley(label.to_string(), font, f32::INFINITY);
         let rect = align.anchor_rect(Rect::from_min_size(pos, galley.bounds().size()));
@@ -247,6 +589,19 @@ impl Painter {
         }))
     }
 
+    /// Paints a closed convex polygon with the given `points`, in clockwise
+    /// order.
+    pub fn convex_polygon(&mut self, points: Vec<Pos2>, fill: Color32, stroke: Stroke) {
+        let points = points
+            .into_iter()
+            .map(|p| self.transform.transform_point(p))
+            .collect();
+        let mut stroke = stroke;
+        stroke.width = self.transform.transform_scalar(stroke.width);
+
+        self.push_shape(epaint::Shape::convex_polygon(points, fill, stroke))
+    }
+
     /// Returns and drains the inner shape buffers. Use this method to draw the
     /// shapes, as it will handle the correct ordering
     pub fn take_shapes(&mut self) -> Vec<ClippedShape> {
@@ -307,6 +662,24 @@ impl TranslateScale {
         Pos2::new(point.x * self.scale, point.y * self.scale) + self.translation
     }
 
+    /// The inverse of [`TranslateScale::transform_point`], mapping a
+    /// transformed point back to layout coordinates.
+    pub fn inverse_transform_point(&self, point: Pos2) -> Pos2 {
+        ((point - self.translation) / self.scale).to_pos2()
+    }
+
+    /// The inverse of [`TranslateScale::transform_rectangle`].
+    pub fn inverse_transform_rectangle(&self, rectangle: Rect) -> Rect {
+        Rect::from_min_max(
+            self.inverse_transform_point(rectangle.min),
+            self.inverse_transform_point(rectangle.max),
+        )
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
     /// Applies the scaling of this transformation to the given `scalar`.
     /// Translation is ignored.
     pub fn transform_scalar(&self, s: f32) -> f32 {
@@ -335,3 +708,85 @@ impl TranslateScale {
         Rect::from_min_size(top_left, size)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_from_pos() {
+        let mut painter = Painter::new(vec![]);
+        let galley = painter.galley("abc".into(), FontId::monospace(10.0), f32::INFINITY);
+        let text_pos = Pos2::new(100.0, 50.0);
+        let char_width = galley.bounds().width() / 3.0;
+        let row_center = text_pos.y + galley.bounds().height() / 2.0;
+        let index_at = |x: f32, y: f32| {
+            galley
+                .cursor_from_pos(text_pos, Pos2::new(x, y))
+                .ccursor
+                .index
+        };
+
+        // Clicks snap to the closest boundary between characters
+        assert_eq!(index_at(text_pos.x + char_width * 0.9, row_center), 1);
+        assert_eq!(index_at(text_pos.x + char_width * 2.2, row_center), 2);
+        // Clicks outside the text give the nearest valid cursor
+        assert_eq!(index_at(0.0, row_center), 0);
+        assert_eq!(index_at(1000.0, row_center), 3);
+        assert_eq!(index_at(1000.0, 1000.0), 3);
+    }
+
+    #[test]
+    fn test_dim_below_ordering() {
+        let mut painter = Painter::new(vec![]);
+        let screen = Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 100.0));
+        painter.prepare(screen, Color32::WHITE);
+        let rect = |color| epaint::Shape::rect_filled(screen, Rounding::none(), color);
+
+        painter.push_shape(rect(Color32::RED));
+        painter.with_overlay(|painter| {
+            painter.push_shape(rect(Color32::GREEN));
+            painter.dim_below(Color32::BLACK);
+            painter.push_shape(rect(Color32::BLUE));
+        });
+        painter.push_shape(rect(Color32::YELLOW));
+
+        let fills: Vec<Color32> = painter
+            .take_shapes()
+            .into_iter()
+            .map(|ClippedShape(_, shape)| match shape {
+                epaint::Shape::Rect(rect) => rect.fill,
+                _ => unreachable!(),
+            })
+            .collect();
+        // Base shapes are always dimmed, even the ones drawn after the modal.
+        assert_eq!(
+            fills,
+            vec![
+                Color32::RED,
+                Color32::YELLOW,
+                Color32::GREEN,
+                Color32::BLACK,
+                Color32::BLUE
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gradient_rect() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 40.0));
+        let gradient = GradientSpec::horizontal(Color32::BLACK, Color32::WHITE);
+        assert_eq!(gradient.color_at(rect, rect.left_center()), Color32::BLACK);
+        assert_eq!(gradient.color_at(rect, rect.right_center()), Color32::WHITE);
+        assert_eq!(
+            gradient.color_at(rect, rect.center()),
+            Color32::from_gray(128)
+        );
+
+        // Rounded corners stay inside the rect
+        let outline = rounded_rect_outline(rect, Rounding::same(10.0));
+        assert!(outline.iter().all(|p| rect.expand(0.001).contains(*p)));
+        assert!(!outline.contains(&rect.left_top()));
+        assert_eq!(rounded_rect_outline(rect, Rounding::none()).len(), 4);
+    }
+}
@@ -0,0 +1,309 @@
+//! Popups, like menus or tooltips: Keeping track of which ones are open, and
+//! placing them next to the widget that opens them, inside the screen.
+
+use epaint::{ahash::HashMap, Pos2, Rect, Vec2};
+
+use crate::{layout::Align, widget_id::WidgetId};
+
+/// Coordinates the popups opened by different widgets, so they don't pile up.
+/// See [`Context::open_popup`](crate::context::Context::open_popup).
+///
+/// Popups are identified by the id of the widget that owns them. While open, a
+/// popup must register the area it covers every frame, when drawn. Popups that
+/// aren't drawn during a frame are closed, e.g. when their widget is removed.
+#[derive(Default)]
+pub struct PopupManager {
+    /// The open popups, from bottom to top.
+    open: Vec<WidgetId>,
+    /// The areas covered by each popup, as registered during the last frame.
+    areas: HashMap<WidgetId, Vec<Rect>>,
+    /// The areas registered so far during the current frame.
+    next_areas: HashMap<WidgetId, Vec<Rect>>,
+}
+
+impl PopupManager {
+    /// Opens the popup on top of the others. When `exclusive` is set, all the
+    /// other popups are closed first.
+    pub fn open(&mut self, id: WidgetId, exclusive: bool) {
+        if exclusive {
+            self.open.clear();
+        } else {
+            self.open.retain(|open| *open != id);
+        }
+        self.open.push(id);
+    }
+
+    /// Closes the popup, along with all the popups opened on top of it.
+    pub fn close(&mut self, id: WidgetId) {
+        if let Some(idx) = self.open.iter().position(|open| *open == id) {
+            self.open.truncate(idx);
+        }
+    }
+
+    pub fn is_open(&self, id: WidgetId) -> bool {
+        self.open.contains(&id)
+    }
+
+    pub fn topmost(&self) -> Option<WidgetId> {
+        self.open.last().copied()
+    }
+
+    /// Returns the stacking order of the popup, with 0 being the bottom one.
+    pub fn z_index(&self, id: WidgetId) -> Option<usize> {
+        self.open.iter().position(|open| *open == id)
+    }
+
+    /// Registers part of the area covered by the popup during this frame.
+    pub fn register_area(&mut self, id: WidgetId, rect: Rect) {
+        self.next_areas.entry(id).or_default().push(rect);
+    }
+
+    /// Handles a mouse press at `pos`: When it lands outside all the popups,
+    /// the topmost one is closed.
+    pub fn on_mouse_pressed(&mut self, pos: Pos2) {
+        let inside_any = self
+            .open
+            .iter()
+            .filter_map(|id| self.areas.get(id))
+            .flatten()
+            .any(|rect| rect.contains(pos));
+        if !inside_any {
+            self.open.pop();
+        }
+    }
+
+    /// Closes the popups that weren't drawn during the frame, and starts
+    /// collecting the areas for the next one.
+    pub fn end_frame(&mut self) {
+        self.areas = std::mem::take(&mut self.next_areas);
+        let areas = &self.areas;
+        self.open.retain(|id| areas.contains_key(id));
+    }
+}
+
+/// The side of the anchor a popup is placed on.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Side {
+    #[default]
+    Below,
+    Above,
+    Left,
+    Right,
+}
+
+impl Side {
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Below => Side::Above,
+            Side::Above => Side::Below,
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Side::Below | Side::Above)
+    }
+}
+
+/// Returns the position of the top-left corner of a popup of `popup_size`
+/// placed on the `preferred` side of `anchor`, and aligned to it with `align`
+/// along the other axis. E.g. a dropdown is placed `Below` its button with
+/// `Align::Start`, so their left edges line up.
+///
+/// When the popup doesn't fit in `screen` on the preferred side, it's flipped
+/// to the opposite side, if it fits there. Along the other axis, it's shifted
+/// inwards until it's inside the screen. Popups bigger than the screen stick
+/// to its top-left corner.
+pub fn place_popup(
+    anchor: Rect,
+    popup_size: Vec2,
+    preferred: Side,
+    align: Align,
+    screen: Rect,
+) -> Pos2 {
+    let main_pos = |side: Side| match side {
+        Side::Below => anchor.bottom(),
+        Side::Above => anchor.top() - popup_size.y,
+        Side::Left => anchor.left() - popup_size.x,
+        Side::Right => anchor.right(),
+    };
+    // The main axis is the one along which the popup moves away from the
+    // anchor, the cross axis is the one it's aligned along.
+    let (main_size, screen_main, anchor_cross, cross_size, screen_cross) =
+        if preferred.is_vertical() {
+            (
+                popup_size.y,
+                (screen.top(), screen.bottom()),
+                (anchor.left(), anchor.right()),
+                popup_size.x,
+                (screen.left(), screen.right()),
+            )
+        } else {
+            (
+                popup_size.x,
+                (screen.left(), screen.right()),
+                (anchor.top(), anchor.bottom()),
+                popup_size.y,
+                (screen.top(), screen.bottom()),
+            )
+        };
+    let fits = |pos: f32| pos >= screen_main.0 && pos + main_size <= screen_main.1;
+
+    let mut main = main_pos(preferred);
+    if !fits(main) && fits(main_pos(preferred.opposite())) {
+        main = main_pos(preferred.opposite());
+    }
+    let main = shift_inside(main, main_size, screen_main);
+
+    let cross = match align {
+        Align::Start => anchor_cross.0,
+        Align::Center => (anchor_cross.0 + anchor_cross.1 - cross_size) / 2.0,
+        Align::End => anchor_cross.1 - cross_size,
+    };
+    let cross = shift_inside(cross, cross_size, screen_cross);
+
+    if preferred.is_vertical() {
+        Pos2::new(cross, main)
+    } else {
+        Pos2::new(main, cross)
+    }
+}
+
+/// Moves the segment starting at `pos` inside `min..max`, keeping `pos` in
+/// range when the segment doesn't fit.
+fn shift_inside(pos: f32, size: f32, (min, max): (f32, f32)) -> f32 {
+    pos.min(max - size).max(min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_popup_manager() {
+        let [a, b, c] = ["a", "b", "c"].map(WidgetId::new);
+        let mut popups = PopupManager::default();
+        popups.open(a, true);
+        popups.open(b, false);
+        assert_eq!(popups.z_index(b), Some(1));
+        // Exclusive popups close everything else
+        popups.open(c, true);
+        assert!(!popups.is_open(a) && !popups.is_open(b));
+
+        popups.open(a, true);
+        popups.open(b, false);
+        popups.register_area(a, Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0)));
+        popups.register_area(
+            b,
+            Rect::from_min_size(Pos2::new(20.0, 0.0), Vec2::splat(10.0)),
+        );
+        popups.end_frame();
+        // Clicking any popup keeps them all open
+        popups.on_mouse_pressed(Pos2::new(5.0, 5.0));
+        assert_eq!(popups.topmost(), Some(b));
+        // Clicking outside closes the topmost
+        popups.on_mouse_pressed(Pos2::new(50.0, 50.0));
+        assert_eq!(popups.topmost(), Some(a));
+        // Popups not drawn during a frame are closed
+        popups.end_frame();
+        assert_eq!(popups.topmost(), None);
+    }
+
+    fn screen() -> Rect {
+        Rect::from_min_max(Pos2::ZERO, Pos2::new(100.0, 100.0))
+    }
+
+    fn anchor(x: f32, y: f32) -> Rect {
+        Rect::from_min_size(Pos2::new(x, y), Vec2::new(20.0, 10.0))
+    }
+
+    #[test]
+    fn test_place_popup_fits() {
+        let size = Vec2::new(30.0, 20.0);
+        let pos = place_popup(
+            anchor(10.0, 10.0),
+            size,
+            Side::Below,
+            Align::Start,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::new(10.0, 20.0));
+        let pos = place_popup(
+            anchor(40.0, 40.0),
+            size,
+            Side::Above,
+            Align::Center,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::new(35.0, 20.0));
+        let pos = place_popup(anchor(40.0, 40.0), size, Side::Right, Align::End, screen());
+        assert_eq!(pos, Pos2::new(60.0, 30.0));
+    }
+
+    #[test]
+    fn test_place_popup_flips() {
+        let size = Vec2::new(30.0, 20.0);
+        // Bottom edge
+        let pos = place_popup(
+            anchor(10.0, 85.0),
+            size,
+            Side::Below,
+            Align::Start,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::new(10.0, 65.0));
+        // Top edge
+        let pos = place_popup(anchor(10.0, 5.0), size, Side::Above, Align::Start, screen());
+        assert_eq!(pos, Pos2::new(10.0, 15.0));
+        // Right edge
+        let pos = place_popup(
+            anchor(70.0, 10.0),
+            size,
+            Side::Right,
+            Align::Start,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::new(40.0, 10.0));
+        // Left edge
+        let pos = place_popup(anchor(10.0, 10.0), size, Side::Left, Align::Start, screen());
+        assert_eq!(pos, Pos2::new(30.0, 10.0));
+    }
+
+    #[test]
+    fn test_place_popup_shifts() {
+        let size = Vec2::new(30.0, 20.0);
+        // Past the right edge along the cross axis
+        let pos = place_popup(
+            anchor(90.0, 10.0),
+            size,
+            Side::Below,
+            Align::Start,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::new(70.0, 20.0));
+        // Past the left edge along the cross axis
+        let pos = place_popup(anchor(-5.0, 10.0), size, Side::Below, Align::End, screen());
+        assert_eq!(pos, Pos2::new(0.0, 20.0));
+        // Fits on neither side, so it's shifted inside the screen
+        let tall = Vec2::new(30.0, 60.0);
+        let pos = place_popup(
+            anchor(10.0, 45.0),
+            tall,
+            Side::Below,
+            Align::Start,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::new(10.0, 40.0));
+        // Bigger than the screen
+        let huge = Vec2::new(300.0, 300.0);
+        let pos = place_popup(
+            anchor(10.0, 45.0),
+            huge,
+            Side::Below,
+            Align::Start,
+            screen(),
+        );
+        assert_eq!(pos, Pos2::ZERO);
+    }
+}
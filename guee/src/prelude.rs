@@ -1,23 +1,63 @@
 pub use crate::{
     base_widgets::{
+        accordion::{Accordion, AccordionStyle},
+        autocomplete::{Autocomplete, AutocompleteStyle},
+        badge::{Badge, BadgeStyle},
         box_container::BoxContainer,
-        button::{Button, ButtonStyle},
-        colored_box::ColoredBox,
+        button::{Button, ButtonStyle, IconLabel, IconPosition},
+        card::{Card, CardStyle},
+        chevron::{Chevron, ChevronDirection},
+        clip_container::ClipContainer,
+        colored_box::{Background, ColoredBox},
+        connection::{Connection, ConnectionStyle},
+        form::{Form, FormRow, FormStyle},
+        grid_background::{GridBackground, GridBackgroundStyle, GridKind},
+        grid_container::{GridContainer, GridContainerStyle},
+        icon::Icon,
+        image::Image,
+        indent_container::{IndentContainer, IndentContainerStyle},
+        keyed_list::{IdScope, KeyedList},
         tinker_container::TinkerContainer,
         margin_container::MarginContainer,
+        menubar::Menubar,
+        menubar_button::{MenuEntry, MenubarButton},
+        popover::{Popover, PopoverStyle},
+        range_slider::{RangeSlider, RangeSliderStyle},
+        reorderable_list::ReorderableList,
+        rich_text::{RichText, TextSpan},
+        segmented_control::{SegmentedControl, SegmentedControlStyle},
+        selectable_row::{SelectableRow, SelectableRowStyle},
         sized_container::SizedContainer,
         spacer::Spacer,
+        spin_box::SpinBox,
         split_pane_container::SplitPaneContainer,
         stack_container::StackContainer,
+        sticky_header_container::StickyHeaderContainer,
+        table::{SortDir, Table, TableColumn, TableStyle},
         text::Text,
-        text_edit::TextEdit,
-        scroll_container::VScrollContainer,
+        text_edit::{TextEdit, TextEditStyle},
+        themed_container::ThemedContainer,
+        time_picker::{ClockMode, TimePicker},
+        toggle_button::ToggleButton,
+        tooltip::{Tooltip, TooltipMode, TooltipStyle},
+        scroll_container::{ScrollTarget, VScrollContainer, VScrollContainerStyle},
+        xy_pad::{XYPad, XYPadStyle},
     },
     callback::Callback,
+    callback_accessor::CallbackAccessor,
     context::Context,
-    input::{Event, EventStatus, InputState},
-    layout::{Align, Axis, AxisDirections, Layout, LayoutHints, SizeHint, SizeHints},
-    theme::{StyledWidget, Theme},
+    icons,
+    input::{Event, EventStatus, FocusTransition, InputState},
+    interaction::{Interaction, InteractionState, InteractionStyle},
+    layout::{
+        Align, Axis, AxisDirections, Direction, Edges, Layout, LayoutHints, SizeHint,
+        SizeHints,
+    },
+    painter::{GradientDirection, GradientSpec},
+    shortcuts::KeyCombo,
+    theme::{FocusRingStyle, StyledWidget, Theme},
+    toast::{Toast, ToastKind},
+    ui::Ui,
     widget::{DynWidget, ToDynWidget, Widget},
     widget_id::{IdGen, WidgetId},
 };
@@ -26,3 +66,6 @@ pub use epaint::{
     Pos2, Rect, Shape, Stroke, TessellationOptions, TextShape, TextureId, Vec2,
 };
 pub use guee_derives::{self, color};
+
+#[cfg(feature = "dates")]
+pub use crate::base_widgets::date_picker::{DatePicker, DatePickerStyle};
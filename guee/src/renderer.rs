@@ -0,0 +1,82 @@
+use epaint::{textures::TexturesDelta, ClippedPrimitive};
+
+/// A rendering backend, able to draw the output of [`Context::tessellate`].
+///
+/// The [`Context`] is agnostic to how its output is drawn, so any GPU or CPU
+/// integration can be plugged in by implementing this trait.
+///
+/// # Conventions
+///
+/// Implementors should follow the same conventions as egui renderers:
+///
+/// - All vertex positions and clip rectangles are given in *points*, with the
+///   origin at the top-left corner of the screen and the y axis pointing down.
+///   Multiply by `pixels_per_point` to get physical pixel coordinates.
+/// - Every primitive must be clipped (scissored) to its `clip_rect`. Clip
+///   rectangles may extend beyond the screen.
+/// - Vertex colors are in gamma (sRGB) space with premultiplied alpha, so
+///   blending should be `src + (1 - src_alpha) * dst`.
+/// - `TextureId::default()` is the font atlas. New textures in
+///   `textures_delta.set` must be uploaded before drawing, and the textures in
+///   `textures_delta.free` released after drawing.
+///
+/// [`Context`]: crate::context::Context
+/// [`Context::tessellate`]: crate::context::Context::tessellate
+pub trait Renderer {
+    fn render(
+        &mut self,
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+        pixels_per_point: f32,
+    );
+
+    /// Called when the window is resized, with the new size in physical
+    /// pixels, so the render surface can be reconfigured. Renderers that
+    /// don't own a surface can ignore this.
+    fn resize(&mut self, _width: u32, _height: u32) {}
+}
+
+/// The default [`Renderer`], drawing to a winit window using `egui-wgpu`.
+#[cfg(feature = "wgpu")]
+pub struct EguiWgpuRenderer {
+    pub painter: egui_wgpu::winit::Painter,
+    /// The color the window is cleared to before drawing.
+    pub clear_color: epaint::Rgba,
+}
+
+#[cfg(feature = "wgpu")]
+impl EguiWgpuRenderer {
+    pub fn new(painter: egui_wgpu::winit::Painter) -> Self {
+        Self {
+            painter,
+            clear_color: epaint::Rgba::BLACK,
+        }
+    }
+
+    /// Should be called when the window is resized, so the surface can be
+    /// reconfigured. Same as [`Renderer::resize`].
+    pub fn on_window_resized(&mut self, width: u32, height: u32) {
+        self.painter.on_window_resized(width, height);
+    }
+}
+
+#[cfg(feature = "wgpu")]
+impl Renderer for EguiWgpuRenderer {
+    fn render(
+        &mut self,
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+        pixels_per_point: f32,
+    ) {
+        self.painter.paint_and_update_textures(
+            pixels_per_point,
+            self.clear_color,
+            primitives,
+            textures_delta,
+        );
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.on_window_resized(width, height);
+    }
+}
@@ -0,0 +1,63 @@
+//! Auto-repeat for held controls, like stepper buttons or scroll arrows. See
+//! [`Context::repeat`](crate::context::Context::repeat).
+
+/// The timing of a held control, stored in the widget memory.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RepeatState {
+    /// The time the control started being held, as given by `Context::time`.
+    /// `None` while it's released.
+    pub held_since: Option<f64>,
+    /// How many times the action has fired since the control was pressed.
+    pub fired: u32,
+}
+
+impl RepeatState {
+    /// Advances the state to `now`, and returns how many times the action
+    /// should fire, along with the time until the next repeat is due.
+    ///
+    /// The action fires once when the control is pressed, and then every
+    /// `interval` seconds after the initial `delay`.
+    pub fn advance(&mut self, now: f64, held: bool, delay: f64, interval: f64) -> (u32, f64) {
+        if !held {
+            *self = Self::default();
+            return (0, f64::INFINITY);
+        }
+        let held_since = *self.held_since.get_or_insert(now);
+        let elapsed = now - held_since;
+        let interval = interval.max(f64::EPSILON);
+
+        let (total, next_in) = if elapsed < delay {
+            (1, delay - elapsed)
+        } else {
+            let repeats = ((elapsed - delay) / interval).floor();
+            (
+                2 + repeats as u32,
+                interval - (elapsed - delay - repeats * interval),
+            )
+        };
+        let count = total.saturating_sub(self.fired);
+        self.fired = self.fired.max(total);
+        (count, next_in)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeat_timing() {
+        let mut state = RepeatState::default();
+        let mut advance = |now, held| state.advance(now, held, 0.5, 0.1).0;
+
+        assert_eq!(advance(1.0, true), 1);
+        assert_eq!(advance(1.2, true), 0);
+        // Initial delay elapsed
+        assert_eq!(advance(1.5, true), 1);
+        assert_eq!(advance(1.55, true), 0);
+        // Slow frames fire all the missed repeats at once
+        assert_eq!(advance(1.82, true), 3);
+        assert_eq!(advance(1.9, false), 0);
+        assert_eq!(advance(2.0, true), 1);
+    }
+}
@@ -0,0 +1,66 @@
+//! App-global keyboard shortcuts, like Ctrl+S. See
+//! [`Context::register_shortcut`](crate::context::Context::register_shortcut).
+
+use winit::event::VirtualKeyCode;
+
+use crate::{callback::Callback, input::ModifierState};
+
+/// A key, plus the modifiers that need to be held when pressing it. Modifiers
+/// must match exactly: Ctrl+S does not trigger on Ctrl+Shift+S.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    pub key: VirtualKeyCode,
+    /// The Command key on MacOS, the Ctrl key on every other OS.
+    pub command: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyCombo {
+    pub fn new(key: VirtualKeyCode) -> Self {
+        Self {
+            key,
+            command: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    /// Shorthand for `KeyCombo::new(key).command()`.
+    pub fn command_key(key: VirtualKeyCode) -> Self {
+        Self::new(key).command()
+    }
+
+    pub fn command(mut self) -> Self {
+        self.command = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Returns whether pressing `key` with the given `modifiers` triggers this
+    /// combo.
+    pub fn matches(&self, key: VirtualKeyCode, modifiers: &ModifierState) -> bool {
+        self.key == key
+            && self.command == modifiers.ctrl_or_command
+            && self.shift == modifiers.shift
+            && self.alt == modifiers.alt
+    }
+}
+
+/// A shortcut registered for the current frame.
+pub struct Shortcut {
+    pub combo: KeyCombo,
+    pub callback: Callback<()>,
+    /// When false, the shortcut is ignored while a text field has keyboard
+    /// focus, so the text field gets the key instead.
+    pub over_text_input: bool,
+}
@@ -0,0 +1,60 @@
+//! Snapping dragged positions to a grid. Draggable widgets opt into it by
+//! passing their computed position through [`snap`] before storing it.
+
+use epaint::{Pos2, Vec2};
+
+use crate::input::ModifierState;
+
+/// Returns the point of the grid closest to `pos`. The grid has cells of size
+/// `grid`, and one of its points is at `offset`. Axes with a non-positive
+/// grid size are not snapped.
+pub fn snap(pos: Pos2, grid: Vec2, offset: Vec2) -> Pos2 {
+    let snap_axis = |x: f32, grid: f32, offset: f32| {
+        if grid > 0.0 {
+            ((x - offset) / grid).round() * grid + offset
+        } else {
+            x
+        }
+    };
+    Pos2::new(
+        snap_axis(pos.x, grid.x, offset.x),
+        snap_axis(pos.y, grid.y, offset.y),
+    )
+}
+
+/// Like [`snap`], but leaves `pos` as is while Alt is held, so users can
+/// temporarily disable snapping when they need finer placement.
+pub fn snap_unless_alt(pos: Pos2, grid: Vec2, offset: Vec2, modifiers: &ModifierState) -> Pos2 {
+    if modifiers.alt {
+        pos
+    } else {
+        snap(pos, grid, offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap() {
+        let grid = Vec2::splat(10.0);
+        assert_eq!(
+            snap(Pos2::new(14.0, 16.0), grid, Vec2::ZERO),
+            Pos2::new(10.0, 20.0)
+        );
+        assert_eq!(
+            snap(Pos2::new(-4.0, -6.0), grid, Vec2::ZERO),
+            Pos2::new(0.0, -10.0)
+        );
+        assert_eq!(
+            snap(Pos2::new(14.0, 16.0), grid, Vec2::new(3.0, 3.0)),
+            Pos2::new(13.0, 13.0)
+        );
+        // Only snaps horizontally
+        assert_eq!(
+            snap(Pos2::new(14.0, 16.0), Vec2::new(10.0, 0.0), Vec2::ZERO),
+            Pos2::new(10.0, 16.0)
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use epaint::{
+    ahash::HashMap, textures::TexturesDelta, ClippedPrimitive, Color32, ColorImage, ImageData,
+    Mesh, Pos2, Primitive, Rect, TextureId, Vertex,
+};
+
+use crate::renderer::Renderer;
+
+/// A very simple CPU rasterizer for tessellated guee output.
+///
+/// This is not meant to be fast, or to match GPU output pixel by pixel. It
+/// exists so that layout and drawing code can be tested without a window or a
+/// GPU. Triangles are sampled at pixel centers without antialiasing (other than
+/// epaint's feathering) and textures are sampled with nearest filtering.
+#[derive(Default)]
+pub struct SoftwareRasterizer {
+    textures: HashMap<TextureId, ColorImage>,
+}
+
+impl SoftwareRasterizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the texture changes for a frame. Must be called before
+    /// [`SoftwareRasterizer::rasterize`] so that textures are up to date.
+    pub fn update_textures(&mut self, textures_delta: &TexturesDelta) {
+        for (id, delta) in &textures_delta.set {
+            let image = match &delta.image {
+                ImageData::Color(image) => image.clone(),
+                ImageData::Font(font_image) => ColorImage {
+                    size: font_image.size,
+                    pixels: font_image.srgba_pixels(1.0).collect(),
+                },
+            };
+
+            match delta.pos {
+                None => {
+                    self.textures.insert(*id, image);
+                }
+                Some([x0, y0]) => {
+                    let Some(texture) = self.textures.get_mut(id) else {
+                        log::warn!("Partial update for unknown texture {id:?}");
+                        continue;
+                    };
+                    for y in 0..image.size[1] {
+                        for x in 0..image.size[0] {
+                            let (tx, ty) = (x0 + x, y0 + y);
+                            if tx < texture.size[0] && ty < texture.size[1] {
+                                texture.pixels[ty * texture.size[0] + tx] =
+                                    image.pixels[y * image.size[0] + x];
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        for id in &textures_delta.free {
+            self.textures.remove(id);
+        }
+    }
+
+    /// Rasterizes the given `primitives` on top of an image of the given
+    /// `size` (in pixels), filled with `clear_color`.
+    pub fn rasterize(
+        &self,
+        primitives: &[ClippedPrimitive],
+        size: [usize; 2],
+        pixels_per_point: f32,
+        clear_color: Color32,
+    ) -> ColorImage {
+        let mut image = ColorImage::new(size, clear_color);
+        self.rasterize_into(&mut image, primitives, pixels_per_point);
+        image
+    }
+
+    /// Same as [`SoftwareRasterizer::rasterize`], but draws on top of an
+    /// existing `image`.
+    pub fn rasterize_into(
+        &self,
+        image: &mut ColorImage,
+        primitives: &[ClippedPrimitive],
+        pixels_per_point: f32,
+    ) {
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            match primitive {
+                Primitive::Mesh(mesh) => {
+                    let clip_rect = Rect::from_min_max(
+                        (clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+                        (clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+                    );
+                    self.rasterize_mesh(image, mesh, clip_rect, pixels_per_point);
+                }
+                Primitive::Callback(_) => {
+                    log::warn!("Paint callbacks are not supported by the software rasterizer");
+                }
+            }
+        }
+    }
+
+    fn rasterize_mesh(
+        &self,
+        image: &mut ColorImage,
+        mesh: &Mesh,
+        clip_rect: Rect,
+        pixels_per_point: f32,
+    ) {
+        let texture = self.textures.get(&mesh.texture_id);
+        if texture.is_none() {
+            log::warn!("Missing texture {:?}, drawing untextured", mesh.texture_id);
+        }
+
+        let image_rect = Rect::from_min_max(
+            Pos2::ZERO,
+            Pos2::new(image.size[0] as f32, image.size[1] as f32),
+        );
+        let clip_rect = clip_rect.intersect(image_rect);
+        if clip_rect.width() <= 0.0 || clip_rect.height() <= 0.0 {
+            return;
+        }
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| {
+                let v = mesh.vertices[i as usize];
+                Vertex {
+                    pos: (v.pos.to_vec2() * pixels_per_point).to_pos2(),
+                    ..v
+                }
+            });
+
+            let area = edge(a.pos, b.pos, c.pos);
+            if area.abs() < f32::EPSILON {
+                continue;
+            }
+
+            let bbox = Rect::from_min_max(a.pos.min(b.pos).min(c.pos), a.pos.max(b.pos).max(c.pos))
+                .intersect(clip_rect);
+            if bbox.width() <= 0.0 || bbox.height() <= 0.0 {
+                continue;
+            }
+
+            let (x0, x1) = (bbox.min.x.floor() as usize, bbox.max.x.ceil() as usize);
+            let (y0, y1) = (bbox.min.y.floor() as usize, bbox.max.y.ceil() as usize);
+
+            for y in y0..y1.min(image.size[1]) {
+                for x in x0..x1.min(image.size[0]) {
+                    let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                    if !clip_rect.contains(p) {
+                        continue;
+                    }
+
+                    // Barycentric coordinates, normalized so it doesn't
+                    // matter whether the triangle is clockwise or not.
+                    let wa = edge(b.pos, c.pos, p) / area;
+                    let wb = edge(c.pos, a.pos, p) / area;
+                    let wc = edge(a.pos, b.pos, p) / area;
+                    if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                        continue;
+                    }
+
+                    let color = interpolate_color([a.color, b.color, c.color], [wa, wb, wc]);
+                    let color = match texture {
+                        Some(texture) => {
+                            let uv =
+                                a.uv.to_vec2() * wa + b.uv.to_vec2() * wb + c.uv.to_vec2() * wc;
+                            multiply(color, sample(texture, uv.to_pos2()))
+                        }
+                        None => color,
+                    };
+
+                    let dst = &mut image.pixels[y * image.size[0] + x];
+                    *dst = blend(color, *dst);
+                }
+            }
+        }
+    }
+}
+
+/// A [`Renderer`] drawing on the CPU, into an in-memory framebuffer. Useful
+/// for headless environments without a GPU, like CI servers, snapshot tests or
+/// simple embedded displays.
+///
+/// After each call to [`Renderer::render`], the frame can be read from
+/// `framebuffer`.
+pub struct SoftwareRenderer {
+    pub rasterizer: SoftwareRasterizer,
+    /// The last rendered frame. Its size is the one given to
+    /// [`Renderer::resize`], in physical pixels.
+    pub framebuffer: ColorImage,
+    /// The color the framebuffer is cleared to before drawing.
+    pub clear_color: Color32,
+}
+
+impl SoftwareRenderer {
+    /// Creates a renderer with a framebuffer of the given size, in physical
+    /// pixels.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            rasterizer: SoftwareRasterizer::new(),
+            framebuffer: ColorImage::new([width, height], Color32::BLACK),
+            clear_color: Color32::BLACK,
+        }
+    }
+}
+
+impl Renderer for SoftwareRenderer {
+    fn render(
+        &mut self,
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+        pixels_per_point: f32,
+    ) {
+        self.rasterizer.update_textures(textures_delta);
+        self.framebuffer.pixels.fill(self.clear_color);
+        self.rasterizer
+            .rasterize_into(&mut self.framebuffer, primitives, pixels_per_point);
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.framebuffer = ColorImage::new([width as usize, height as usize], self.clear_color);
+    }
+}
+
+/// Twice the signed area of the triangle (a, b, p).
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+fn interpolate_color(colors: [Color32; 3], weights: [f32; 3]) -> Color32 {
+    let mut acc = [0.0f32; 4];
+    for (color, w) in colors.iter().zip(weights) {
+        for (acc, channel) in acc.iter_mut().zip(color.to_array()) {
+            *acc += channel as f32 * w;
+        }
+    }
+    let [r, g, b, a] = acc.map(|x| x.round().clamp(0.0, 255.0) as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+fn sample(texture: &ColorImage, uv: Pos2) -> Color32 {
+    let [w, h] = texture.size;
+    let x = ((uv.x * w as f32) as usize).min(w.saturating_sub(1));
+    let y = ((uv.y * h as f32) as usize).min(h.saturating_sub(1));
+    texture.pixels[y * w + x]
+}
+
+fn multiply(x: Color32, y: Color32) -> Color32 {
+    let [r, g, b, a] =
+        [0, 1, 2, 3].map(|i| ((x.to_array()[i] as u32 * y.to_array()[i] as u32 + 127) / 255) as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+/// Premultiplied alpha "over" compositing of `src` on top of `dst`.
+fn blend(src: Color32, dst: Color32) -> Color32 {
+    let inv_alpha = 255 - src.a() as u32;
+    let [r, g, b, a] = [0, 1, 2, 3].map(|i| {
+        let s = src.to_array()[i] as u32;
+        let d = dst.to_array()[i] as u32;
+        (s + (d * inv_alpha + 127) / 255).min(255) as u8
+    });
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use epaint::{Rect, Vec2};
+
+    use super::*;
+
+    #[test]
+    fn test_rasterize_rect() {
+        let mut rasterizer = SoftwareRasterizer::new();
+        let mut delta = TexturesDelta::default();
+        delta.set.push((
+            TextureId::default(),
+            epaint::ImageDelta::full(
+                ColorImage::new([1, 1], Color32::WHITE),
+                epaint::textures::TextureOptions::NEAREST,
+            ),
+        ));
+        rasterizer.update_textures(&delta);
+
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(
+            Rect::from_min_size(Pos2::new(2.0, 2.0), Vec2::new(4.0, 4.0)),
+            Color32::RED,
+        );
+        let primitives = vec![ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+
+        let image = rasterizer.rasterize(&primitives, [8, 8], 1.0, Color32::BLACK);
+        assert_eq!(image.pixels[3 * 8 + 3], Color32::RED);
+        assert_eq!(image.pixels[0], Color32::BLACK);
+        assert_eq!(image.pixels[7 * 8 + 7], Color32::BLACK);
+    }
+
+    #[test]
+    fn test_software_renderer_clips() {
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let mut delta = TexturesDelta::default();
+        delta.set.push((
+            TextureId::default(),
+            epaint::ImageDelta::full(
+                ColorImage::new([1, 1], Color32::WHITE),
+                epaint::textures::TextureOptions::NEAREST,
+            ),
+        ));
+
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(
+            Rect::from_min_size(Pos2::ZERO, Vec2::new(8.0, 8.0)),
+            Color32::RED,
+        );
+        let primitives = vec![ClippedPrimitive {
+            clip_rect: Rect::from_min_size(Pos2::ZERO, Vec2::new(4.0, 8.0)),
+            primitive: Primitive::Mesh(mesh),
+        }];
+
+        renderer.render(&primitives, &delta, 1.0);
+        assert_eq!(renderer.framebuffer.pixels[3], Color32::RED);
+        assert_eq!(renderer.framebuffer.pixels[4], Color32::BLACK);
+
+        // Resizing changes the size of the next frames
+        renderer.resize(2, 2);
+        renderer.render(&primitives, &TexturesDelta::default(), 1.0);
+        assert_eq!(renderer.framebuffer.size, [2, 2]);
+    }
+
+    #[test]
+    fn test_overlapping_translucent_rects() {
+        let mut rasterizer = SoftwareRasterizer::new();
+        let mut delta = TexturesDelta::default();
+        delta.set.push((
+            TextureId::default(),
+            epaint::ImageDelta::full(
+                ColorImage::new([1, 1], Color32::WHITE),
+                epaint::textures::TextureOptions::NEAREST,
+            ),
+        ));
+        rasterizer.update_textures(&delta);
+
+        // 50% red, then 50% blue on top, overlapping in the middle. Vertex
+        // colors are premultiplied.
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(
+            Rect::from_min_size(Pos2::ZERO, Vec2::new(6.0, 8.0)),
+            Color32::from_rgba_premultiplied(128, 0, 0, 128),
+        );
+        mesh.add_colored_rect(
+            Rect::from_min_size(Pos2::new(2.0, 0.0), Vec2::new(6.0, 8.0)),
+            Color32::from_rgba_premultiplied(0, 0, 128, 128),
+        );
+        let primitives = vec![ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+
+        let image = rasterizer.rasterize(&primitives, [8, 8], 1.0, Color32::BLACK);
+        let pixel = |x: usize| image.pixels[4 * 8 + x];
+        assert_eq!(pixel(1), Color32::from_rgb(128, 0, 0));
+        assert_eq!(pixel(7), Color32::from_rgb(0, 0, 128));
+        // The blue rect lets half of the red one through
+        assert_eq!(pixel(4), Color32::from_rgb(64, 0, 128));
+    }
+}
@@ -0,0 +1,149 @@
+//! Drawing several independent UI trees with a single [`Context`], e.g. one
+//! per window. See [`Context::run_surface`].
+//!
+//! Each surface has its own painter, input and layout, while the memory,
+//! theme and callbacks are shared. Only one surface is active at a time: its
+//! state lives in the regular fields of the context, so widgets don't need to
+//! know about surfaces at all, and the inactive ones are stored aside.
+
+use std::any::Any;
+
+use epaint::{ahash::HashMap, Vec2};
+
+use crate::{
+    context::Context,
+    input::{InputState, InputWidgetState},
+    layout::Layout,
+    painter::Painter,
+    popup::PopupManager,
+    widget::DynWidget,
+    widget_id::WidgetId,
+};
+
+/// Identifies a surface of a [`Context`]. A new context has a single surface,
+/// [`SurfaceId::MAIN`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SurfaceId(pub u32);
+
+impl SurfaceId {
+    pub const MAIN: SurfaceId = SurfaceId(0);
+
+    /// The id of the root widget of this surface. Surfaces other than the main
+    /// one are namespaced, so their widgets never share memory with the
+    /// widgets of another surface.
+    pub fn root_widget_id(self) -> WidgetId {
+        let root = WidgetId::new("__ROOT__");
+        if self == Self::MAIN {
+            root
+        } else {
+            root.with(("surface", self.0))
+        }
+    }
+}
+
+/// The per-surface state of a [`Context`], while the surface is inactive.
+pub struct Surface {
+    pub painter: Painter,
+    pub input_state: InputState,
+    pub input_widget_state: InputWidgetState,
+    pub last_layout: Option<Layout>,
+    pub pending_resize: Option<Vec2>,
+    pub popups: PopupManager,
+}
+
+/// The surfaces of a context, besides the state of the active one.
+#[derive(Default)]
+pub struct Surfaces {
+    pub active: SurfaceId,
+    pub inactive: HashMap<SurfaceId, Surface>,
+}
+
+impl Default for SurfaceId {
+    fn default() -> Self {
+        Self::MAIN
+    }
+}
+
+impl Context {
+    /// Adds a new surface with the given `screen_size`. The surface starts
+    /// inactive. Panics if the id is already in use.
+    pub fn add_surface(&mut self, id: SurfaceId, screen_size: Vec2) {
+        assert!(
+            id != self.surfaces.active && !self.surfaces.inactive.contains_key(&id),
+            "Surface {id:?} already exists"
+        );
+        let surface = Surface {
+            painter: Painter::new(self.extra_fonts.clone()),
+            input_state: InputState::new(screen_size),
+            input_widget_state: Default::default(),
+            last_layout: None,
+            pending_resize: None,
+            popups: Default::default(),
+        };
+        self.surfaces.inactive.insert(id, surface);
+    }
+
+    /// Removes a surface, e.g. when its window is closed. The active surface
+    /// can't be removed.
+    pub fn remove_surface(&mut self, id: SurfaceId) -> Option<Surface> {
+        self.surfaces.inactive.remove(&id)
+    }
+
+    /// Returns the currently active surface.
+    pub fn active_surface(&self) -> SurfaceId {
+        self.surfaces.active
+    }
+
+    /// Makes `id` the active surface. All the methods of the context that deal
+    /// with painting, input or layout, like [`Context::on_winit_event`] or
+    /// [`Context::tessellate`], act on the active surface. Panics if there's no
+    /// surface with that id.
+    pub fn switch_surface(&mut self, id: SurfaceId) {
+        if id == self.surfaces.active {
+            return;
+        }
+        let mut surface = self
+            .surfaces
+            .inactive
+            .remove(&id)
+            .unwrap_or_else(|| panic!("Unknown surface {id:?}"));
+        std::mem::swap(self.painter.get_mut(), &mut surface.painter);
+        std::mem::swap(&mut self.input_state, &mut surface.input_state);
+        std::mem::swap(
+            self.input_widget_state.get_mut(),
+            &mut surface.input_widget_state,
+        );
+        std::mem::swap(&mut self.last_layout, &mut surface.last_layout);
+        std::mem::swap(&mut self.pending_resize, &mut surface.pending_resize);
+        std::mem::swap(self.popups.get_mut(), &mut surface.popups);
+        let previous = std::mem::replace(&mut self.surfaces.active, id);
+        self.surfaces.inactive.insert(previous, surface);
+    }
+
+    /// Switches to the surface `id`, and draws the `widget` tree on it, like
+    /// [`Context::run`].
+    pub fn run_surface(&mut self, id: SurfaceId, widget: &mut DynWidget, state: &mut dyn Any) {
+        self.switch_surface(id);
+        self.run(widget, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_surface() {
+        let mut ctx = Context::new(Vec2::new(800.0, 600.0), vec![]);
+        let hud = SurfaceId(1);
+        ctx.add_surface(hud, Vec2::new(200.0, 100.0));
+
+        ctx.switch_surface(hud);
+        assert_eq!(ctx.active_surface(), hud);
+        assert_eq!(ctx.screen_size(), Vec2::new(200.0, 100.0));
+
+        ctx.switch_surface(SurfaceId::MAIN);
+        assert_eq!(ctx.screen_size(), Vec2::new(800.0, 600.0));
+        assert_ne!(hud.root_widget_id(), SurfaceId::MAIN.root_widget_id());
+    }
+}
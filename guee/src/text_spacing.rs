@@ -0,0 +1,142 @@
+//! Custom line height and letter spacing for text, which epaint's text layout
+//! doesn't support. Galleys are laid out as usual, and then their rows and
+//! glyphs are moved into place.
+
+use epaint::{text::Row, Galley, Rect, Vec2};
+
+/// Spacing adjustments applied to laid out text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextSpacing {
+    /// The distance between the top of consecutive lines, as a multiple of the
+    /// font size. When `None`, the font's own row height is used.
+    pub line_height: Option<f32>,
+    /// Extra horizontal space added between letters, in points. Can be
+    /// negative to bring letters closer together.
+    pub letter_spacing: f32,
+}
+
+impl TextSpacing {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns a copy of `galley` with the spacing applied, for text using a
+    /// font of the given `font_size`.
+    ///
+    /// Wrapping is computed without the letter spacing, so rows of wrapped
+    /// text with a positive letter spacing may be slightly wider than the
+    /// wrap width.
+    pub fn apply(&self, galley: &Galley, font_size: f32) -> Galley {
+        let mut galley = galley.clone();
+        if self.letter_spacing != 0.0 {
+            for row in &mut galley.rows {
+                apply_letter_spacing(row, self.letter_spacing);
+            }
+        }
+        if let Some(line_height) = self.line_height {
+            let pitch = line_height * font_size;
+            for (i, row) in galley.rows.iter_mut().enumerate() {
+                // Keep the glyphs vertically centered in the new line
+                let top = i as f32 * pitch;
+                let offset = top + (pitch - row.rect.height()) / 2.0 - row.rect.min.y;
+                translate_row(row, Vec2::new(0.0, offset));
+                row.rect.min.y = top;
+                row.rect.max.y = top + pitch;
+            }
+        }
+
+        let mut rect = Rect::NOTHING;
+        let mut mesh_bounds = Rect::NOTHING;
+        for row in &galley.rows {
+            rect = rect.union(row.rect);
+            mesh_bounds = mesh_bounds.union(row.visuals.mesh_bounds);
+        }
+        if !galley.rows.is_empty() {
+            galley.rect = rect;
+            galley.mesh_bounds = mesh_bounds;
+        }
+        galley
+    }
+}
+
+fn translate_row(row: &mut Row, offset: Vec2) {
+    row.rect = row.rect.translate(offset);
+    for glyph in &mut row.glyphs {
+        glyph.pos += offset;
+    }
+    for vertex in &mut row.visuals.mesh.vertices {
+        vertex.pos += offset;
+    }
+    row.visuals.mesh_bounds = row.visuals.mesh_bounds.translate(offset);
+}
+
+/// Moves the `i`-th glyph of the row `i * spacing` to the right.
+fn apply_letter_spacing(row: &mut Row, spacing: f32) {
+    // Glyph quads don't map one to one to glyphs, since blank glyphs have no
+    // quad, so each quad is matched to the glyph it overlaps.
+    let range = row.visuals.glyph_vertex_range.clone();
+    for quad in row.visuals.mesh.vertices[range].chunks_mut(4) {
+        let center_x = quad.iter().map(|v| v.pos.x).sum::<f32>() / quad.len() as f32;
+        let index = row
+            .glyphs
+            .partition_point(|g| g.pos.x <= center_x)
+            .saturating_sub(1);
+        for vertex in quad {
+            vertex.pos.x += index as f32 * spacing;
+        }
+    }
+    for (i, glyph) in row.glyphs.iter_mut().enumerate() {
+        glyph.pos.x += i as f32 * spacing;
+    }
+
+    let extra_width = row.glyphs.len().saturating_sub(1) as f32 * spacing;
+    row.rect.max.x += extra_width;
+    row.visuals.mesh_bounds.max.x += extra_width;
+}
+
+#[cfg(test)]
+mod tests {
+    use epaint::{text::FontDefinitions, Color32, FontId, Fonts};
+
+    use super::*;
+
+    fn layout(text: &str, wrap_width: f32) -> std::sync::Arc<Galley> {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        fonts.layout(
+            text.into(),
+            FontId::proportional(10.0),
+            Color32::BLACK,
+            wrap_width,
+        )
+    }
+
+    #[test]
+    fn test_line_height() {
+        let galley = layout("some words that wrap into several lines", 40.0);
+        let num_rows = galley.rows.len();
+        assert!(num_rows > 1);
+
+        let spacing = TextSpacing {
+            line_height: Some(3.0),
+            letter_spacing: 0.0,
+        };
+        let spaced = spacing.apply(&galley, 10.0);
+        assert_eq!(spaced.rect.height(), num_rows as f32 * 30.0);
+        assert_eq!(spaced.rows[1].rect.min.y, 30.0);
+    }
+
+    #[test]
+    fn test_letter_spacing() {
+        let galley = layout("abc", f32::INFINITY);
+        let spacing = TextSpacing {
+            line_height: None,
+            letter_spacing: 2.0,
+        };
+        let spaced = spacing.apply(&galley, 10.0);
+        assert_eq!(spaced.rect.width(), galley.rect.width() + 4.0);
+        assert_eq!(
+            spaced.rows[0].glyphs[2].pos.x,
+            galley.rows[0].glyphs[2].pos.x + 4.0
+        );
+    }
+}
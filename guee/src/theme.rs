@@ -3,9 +3,9 @@ use std::{
     collections::HashMap,
 };
 
-use epaint::Color32;
+use epaint::{Color32, Rounding};
 
-use crate::prelude::Widget;
+use crate::{prelude::Widget, toast::ToastStyle};
 
 pub trait StyledWidget: Widget {
     type Style;
@@ -13,13 +13,57 @@ pub trait StyledWidget: Widget {
 
 pub struct Theme {
     pub text_color: Color32,
-    widget_styles: HashMap<TypeId, Box<dyn Any>>,
+    /// How focused widgets are highlighted, shared by all widgets so keyboard
+    /// navigation looks consistent. See [`Painter::focus_ring`].
+    ///
+    /// [`Painter::focus_ring`]: crate::painter::Painter::focus_ring
+    pub focus_ring: FocusRingStyle,
+    /// The color used by input widgets to show validation errors.
+    pub error_color: Color32,
+    /// Alternating backgrounds for the children of striped containers, see
+    /// `BoxContainer::striped`.
+    pub stripe_even: Color32,
+    pub stripe_odd: Color32,
+    /// How the notifications shown with [`Context::toast`] look.
+    ///
+    /// [`Context::toast`]: crate::context::Context::toast
+    pub toast: ToastStyle,
+    /// Styles for each widget type and variant. The default variant is
+    /// stored under `None`.
+    widget_styles: HashMap<(TypeId, Option<&'static str>), Box<dyn Any>>,
+}
+
+/// The outline drawn around focused widgets.
+#[derive(Clone, Debug)]
+pub struct FocusRingStyle {
+    pub color: Color32,
+    /// The thickness of the outline.
+    pub width: f32,
+    /// The gap between the widget bounds and the inner side of the outline.
+    pub offset: f32,
+    pub rounding: Rounding,
+}
+
+impl Default for FocusRingStyle {
+    fn default() -> Self {
+        Self {
+            color: Color32::from_rgb(90, 155, 213),
+            width: 2.0,
+            offset: 1.0,
+            rounding: Rounding::same(3.0),
+        }
+    }
 }
 
 impl Theme {
     pub fn new_empty() -> Self {
         Theme {
             text_color: Color32::BLACK,
+            focus_ring: FocusRingStyle::default(),
+            error_color: Color32::from_rgb(213, 59, 59),
+            stripe_even: Color32::TRANSPARENT,
+            stripe_odd: Color32::from_white_alpha(8),
+            toast: ToastStyle::default(),
             widget_styles: Default::default(),
         }
     }
@@ -30,7 +74,7 @@ impl Theme {
         W::Style: Sized + 'static,
     {
         self.widget_styles
-            .insert(TypeId::of::<W>(), Box::new(style));
+            .insert((TypeId::of::<W>(), None), Box::new(style));
     }
 
     pub fn get_style<W>(&self) -> Option<&W::Style>
@@ -38,10 +82,53 @@ impl Theme {
         W: StyledWidget + Sized + 'static,
         W::Style: Sized + 'static,
     {
-        self.widget_styles.get(&TypeId::of::<W>()).map(|x| {
-            x.downcast_ref::<W::Style>()
-                .expect("Downcast failed: Should contain the right style type")
-        })
+        self.get_style_variant::<W>(None)
+    }
+
+    /// Sets the style for a named variant of a widget, e.g. "danger" buttons.
+    /// Widgets select their variant with their `variant` field.
+    pub fn set_style_variant<W>(&mut self, variant: &'static str, style: W::Style)
+    where
+        W: StyledWidget + Sized + 'static,
+        W::Style: Sized + 'static,
+    {
+        self.widget_styles
+            .insert((TypeId::of::<W>(), Some(variant)), Box::new(style));
+    }
+
+    /// Returns the style for the given variant of a widget. Falls back to the
+    /// default style when the variant has no style of its own.
+    pub fn get_style_variant<W>(&self, variant: Option<&'static str>) -> Option<&W::Style>
+    where
+        W: StyledWidget + Sized + 'static,
+        W::Style: Sized + 'static,
+    {
+        let type_id = TypeId::of::<W>();
+        self.widget_styles
+            .get(&(type_id, variant))
+            .or_else(|| self.widget_styles.get(&(type_id, None)))
+            .map(|x| {
+                x.downcast_ref::<W::Style>()
+                    .expect("Downcast failed: Should contain the right style type")
+            })
+    }
+
+    /// Returns the style a widget should use: its `style_override` when set,
+    /// otherwise the theme style for its `variant`, or the default style when
+    /// the theme has none.
+    pub fn resolve_style<W>(
+        &self,
+        style_override: Option<&W::Style>,
+        variant: Option<&'static str>,
+    ) -> W::Style
+    where
+        W: StyledWidget + Sized + 'static,
+        W::Style: Clone + Default + Sized + 'static,
+    {
+        style_override
+            .or_else(|| self.get_style_variant::<W>(variant))
+            .cloned()
+            .unwrap_or_default()
     }
 
     pub fn set_text_color(&mut self, color: Color32) -> epaint::Color32 {
@@ -50,3 +137,65 @@ impl Theme {
         old
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Button, ButtonStyle};
+
+    #[test]
+    fn test_style_variant_fallback() {
+        let mut theme = Theme::new_empty();
+        let default = ButtonStyle::new(
+            Color32::RED,
+            Default::default(),
+            Color32::RED,
+            Default::default(),
+            Color32::RED,
+            Default::default(),
+        );
+        let mut danger = default.clone();
+        danger.idle_fill = Color32::BLUE;
+
+        assert!(theme.get_style_variant::<Button>(Some("danger")).is_none());
+        theme.set_style::<Button>(default);
+        theme.set_style_variant::<Button>("danger", danger);
+
+        let idle_fill = |variant| {
+            theme
+                .get_style_variant::<Button>(variant)
+                .map(|s| s.idle_fill)
+        };
+        assert_eq!(idle_fill(None), Some(Color32::RED));
+        assert_eq!(idle_fill(Some("danger")), Some(Color32::BLUE));
+        assert_eq!(idle_fill(Some("primary")), Some(Color32::RED));
+    }
+
+    #[test]
+    fn test_resolve_style() {
+        let mut theme = Theme::new_empty();
+        let resolve = |theme: &Theme, style_override, variant| {
+            theme
+                .resolve_style::<Button>(style_override, variant)
+                .idle_fill
+        };
+        let default_fill = ButtonStyle::default().idle_fill;
+        assert_eq!(resolve(&theme, None, Some("danger")), default_fill);
+
+        let danger = ButtonStyle {
+            idle_fill: Color32::BLUE,
+            ..Default::default()
+        };
+        theme.set_style_variant::<Button>("danger", danger);
+        assert_eq!(resolve(&theme, None, Some("danger")), Color32::BLUE);
+
+        let style_override = ButtonStyle {
+            idle_fill: Color32::GREEN,
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve(&theme, Some(&style_override), Some("danger")),
+            Color32::GREEN
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Reloading the theme when a file changes, to tune the look of an application
+//! without recompiling it.
+//!
+//! The file is watched from a background thread, which only reads its
+//! contents. Parsing them into a [`Theme`] happens on the thread calling
+//! [`ThemeHandle::poll`], so the theme never needs to cross threads.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{context::Context, theme::Theme};
+
+type ParseFn = Box<dyn Fn(&str) -> Result<Theme, String>>;
+
+/// Keeps a theme file watched. Dropping the handle stops watching.
+pub struct ThemeHandle {
+    path: PathBuf,
+    parse: ParseFn,
+    receiver: Receiver<String>,
+    _watcher: RecommendedWatcher,
+}
+
+impl Theme {
+    /// Starts watching the file at `path`. Every time the file changes, its
+    /// contents are turned into a theme by `parse`, and the next call to
+    /// [`ThemeHandle::poll`] applies it. The current contents of the file are
+    /// applied on the first poll.
+    pub fn watch(
+        path: impl AsRef<Path>,
+        parse: impl Fn(&str) -> Result<Theme, String> + 'static,
+    ) -> notify::Result<ThemeHandle> {
+        let path = path.as_ref().to_path_buf();
+        let (sender, receiver) = mpsc::channel();
+
+        if let Ok(contents) = std::fs::read_to_string(&path) {
+            let _ = sender.send(contents);
+        }
+
+        let watched_path = path.clone();
+        let mut watcher = notify::recommended_watcher(move |ev: notify::Result<notify::Event>| {
+            match ev {
+                Ok(ev) if ev.kind.is_create() || ev.kind.is_modify() => {
+                    if !ev
+                        .paths
+                        .iter()
+                        .any(|p| p.file_name() == watched_path.file_name())
+                    {
+                        return;
+                    }
+                    match std::fs::read_to_string(&watched_path) {
+                        Ok(contents) => {
+                            let _ = sender.send(contents);
+                        }
+                        // Editors may briefly remove the file while saving
+                        Err(err) => log::debug!("Could not read theme file: {err}"),
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => log::error!("Error watching theme file: {err}"),
+            }
+        })?;
+
+        // Editors often save by replacing the file, which would stop a
+        // watcher on the file itself, so the parent directory is watched.
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+        Ok(ThemeHandle {
+            path,
+            parse: Box::new(parse),
+            receiver,
+            _watcher: watcher,
+        })
+    }
+}
+
+impl ThemeHandle {
+    /// Applies the latest version of the theme file to `ctx`, if it changed
+    /// since the last call. Returns whether the theme was replaced.
+    ///
+    /// When the file can't be parsed, the error is logged and the current
+    /// theme is kept. Should be called once per iteration of the event loop.
+    /// Note that file changes don't wake up the event loop by themselves.
+    pub fn poll(&mut self, ctx: &mut Context) -> bool {
+        let Some(contents) = self.receiver.try_iter().last() else {
+            return false;
+        };
+        match (self.parse)(&contents) {
+            Ok(theme) => {
+                ctx.set_theme(theme);
+                ctx.request_relayout();
+                true
+            }
+            Err(err) => {
+                log::error!("Could not parse theme file {}: {err}", self.path.display());
+                false
+            }
+        }
+    }
+}
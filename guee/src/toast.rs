@@ -0,0 +1,338 @@
+use epaint::{emath::Align2, Color32, FontFamily, FontId, Pos2, Rect, RectShape, Rounding, Vec2};
+
+use crate::{
+    icons::{self, DEFAULT_ICON_FAMILY},
+    painter::{GueeTextShape, Painter},
+};
+
+/// How long a toast stays on screen when created with the [`Toast`]
+/// constructors, in seconds.
+pub const DEFAULT_TOAST_DURATION: f32 = 4.0;
+
+/// The kind of a [`Toast`], which picks its color and icon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToastKind {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastKind {
+    /// The icon shown when [`ToastStyle::use_icon_font`] is set.
+    pub fn icon(self) -> char {
+        match self {
+            ToastKind::Info => icons::CIRCLE_INFO,
+            ToastKind::Success => icons::CIRCLE_CHECK,
+            ToastKind::Warning => icons::TRIANGLE_EXCLAMATION,
+            ToastKind::Error => icons::CIRCLE_XMARK,
+        }
+    }
+}
+
+/// A transient notification. See [`Context::toast`].
+///
+/// [`Context::toast`]: crate::context::Context::toast
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub text: String,
+    pub kind: ToastKind,
+    /// How long the toast stays on screen, in seconds, not counting the fade.
+    pub duration: f32,
+}
+
+impl Toast {
+    pub fn new(text: impl Into<String>, kind: ToastKind) -> Self {
+        Self {
+            text: text.into(),
+            kind,
+            duration: DEFAULT_TOAST_DURATION,
+        }
+    }
+
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(text, ToastKind::Info)
+    }
+
+    pub fn success(text: impl Into<String>) -> Self {
+        Self::new(text, ToastKind::Success)
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self::new(text, ToastKind::Warning)
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(text, ToastKind::Error)
+    }
+
+    pub fn duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// The corner of the screen toasts are stacked in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToastCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// How toasts look. Part of the [`Theme`](crate::theme::Theme), since
+/// toasts are drawn by the context rather than by a widget.
+#[derive(Clone, Debug)]
+pub struct ToastStyle {
+    pub corner: ToastCorner,
+    pub fill: Color32,
+    pub text_color: Color32,
+    pub info_color: Color32,
+    pub success_color: Color32,
+    pub warning_color: Color32,
+    pub error_color: Color32,
+    pub width: f32,
+    pub padding: Vec2,
+    /// The distance from the toasts to the screen edges.
+    pub margin: f32,
+    /// The space between stacked toasts.
+    pub spacing: f32,
+    pub corner_radius: f32,
+    pub font_size: f32,
+    /// How long toasts take to fade in and out, in seconds.
+    pub fade_duration: f32,
+    /// When set, an icon for the kind is drawn next to the text. The icon
+    /// font must be registered, see [`icons`]. Otherwise, a colored bar is
+    /// drawn on the left side of the toast.
+    pub use_icon_font: bool,
+}
+
+impl Default for ToastStyle {
+    fn default() -> Self {
+        Self {
+            corner: ToastCorner::default(),
+            fill: Color32::from_rgb(36, 36, 36),
+            text_color: Color32::from_rgb(220, 220, 220),
+            info_color: Color32::from_rgb(90, 155, 213),
+            success_color: Color32::from_rgb(92, 184, 92),
+            warning_color: Color32::from_rgb(230, 170, 60),
+            error_color: Color32::from_rgb(213, 59, 59),
+            width: 280.0,
+            padding: Vec2::new(10.0, 8.0),
+            margin: 12.0,
+            spacing: 6.0,
+            corner_radius: 4.0,
+            font_size: 14.0,
+            fade_duration: 0.2,
+            use_icon_font: false,
+        }
+    }
+}
+
+impl ToastStyle {
+    pub fn kind_color(&self, kind: ToastKind) -> Color32 {
+        match kind {
+            ToastKind::Info => self.info_color,
+            ToastKind::Success => self.success_color,
+            ToastKind::Warning => self.warning_color,
+            ToastKind::Error => self.error_color,
+        }
+    }
+}
+
+/// A toast on screen.
+pub struct ActiveToast {
+    pub toast: Toast,
+    /// Unique for each toast, used to keep its animation state.
+    pub id: u64,
+    /// The time the toast was first drawn, or `None` if it wasn't drawn yet.
+    pub shown_at: Option<f64>,
+    /// Set when the user clicks the toast, to start fading out right away.
+    pub dismissed: bool,
+    /// The opacity for the current frame, while fading in and out.
+    pub opacity: f32,
+    /// The bounds of the toast the last time it was drawn. Used to detect
+    /// clicks on the next frame.
+    pub rect: Rect,
+}
+
+impl ActiveToast {
+    /// Returns whether the toast should be fading out at the given `time`.
+    pub fn is_expired(&self, time: f64) -> bool {
+        self.dismissed
+            || self.shown_at.map_or(false, |shown_at| {
+                time - shown_at >= self.toast.duration as f64
+            })
+    }
+}
+
+/// The toasts waiting to be dismissed, in the order they were created. See
+/// [`Context::toast`].
+///
+/// [`Context::toast`]: crate::context::Context::toast
+#[derive(Default)]
+pub struct ToastQueue {
+    pub toasts: Vec<ActiveToast>,
+    next_id: u64,
+}
+
+impl ToastQueue {
+    pub fn push(&mut self, toast: Toast) {
+        self.toasts.push(ActiveToast {
+            toast,
+            id: self.next_id,
+            shown_at: None,
+            dismissed: false,
+            opacity: 0.0,
+            rect: Rect::NOTHING,
+        });
+        self.next_id += 1;
+    }
+
+    /// Dismisses the toast under `pos`, if any. Returns whether there was one.
+    pub fn dismiss_at(&mut self, pos: Pos2) -> bool {
+        match self.toasts.iter_mut().find(|t| t.rect.contains(pos)) {
+            Some(toast) => {
+                toast.dismissed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the bounds of each toast, stacked from the given corner of the
+    /// `screen`. `heights` has the height of each toast, in queue order.
+    pub fn stack_rects(style: &ToastStyle, screen: Rect, heights: &[f32]) -> Vec<Rect> {
+        let screen = screen.shrink(style.margin);
+        let (left, from_top) = match style.corner {
+            ToastCorner::TopLeft => (true, true),
+            ToastCorner::TopRight => (false, true),
+            ToastCorner::BottomLeft => (true, false),
+            ToastCorner::BottomRight => (false, false),
+        };
+        let x = if left {
+            screen.left()
+        } else {
+            screen.right() - style.width
+        };
+        let mut y = if from_top {
+            screen.top()
+        } else {
+            screen.bottom()
+        };
+        heights
+            .iter()
+            .map(|height| {
+                let size = Vec2::new(style.width, *height);
+                let rect = if from_top {
+                    Rect::from_min_size(Pos2::new(x, y), size)
+                } else {
+                    Rect::from_min_size(Pos2::new(x, y - height), size)
+                };
+                let step = height + style.spacing;
+                y += if from_top { step } else { -step };
+                rect
+            })
+            .collect()
+    }
+}
+
+/// Paints a single toast at the given `rect`, faded by `opacity`.
+pub(crate) fn paint_toast(
+    painter: &mut Painter,
+    rect: Rect,
+    toast: &Toast,
+    style: &ToastStyle,
+    opacity: f32,
+) {
+    let kind_color = style.kind_color(toast.kind).linear_multiply(opacity);
+    let rounding = Rounding::same(style.corner_radius);
+    painter.rect(RectShape {
+        rect,
+        rounding,
+        fill: style.fill.linear_multiply(opacity),
+        stroke: epaint::Stroke::new(1.0, kind_color),
+    });
+
+    let inner = rect.shrink2(style.padding);
+    let text_left = if style.use_icon_font {
+        let font = FontId::new(
+            style.font_size,
+            FontFamily::Name(DEFAULT_ICON_FAMILY.into()),
+        );
+        let prev_color = std::mem::replace(&mut painter.text_color, kind_color);
+        painter.text(inner.left_top(), Align2::LEFT_TOP, toast.kind.icon(), font);
+        painter.text_color = prev_color;
+        inner.left() + style.font_size + style.padding.x
+    } else {
+        let bar = Rect::from_min_max(rect.left_top(), Pos2::new(rect.left() + 4.0, rect.bottom()));
+        painter.rect(RectShape {
+            rect: bar,
+            rounding: Rounding {
+                nw: style.corner_radius,
+                sw: style.corner_radius,
+                ne: 0.0,
+                se: 0.0,
+            },
+            fill: kind_color,
+            stroke: epaint::Stroke::NONE,
+        });
+        inner.left()
+    };
+
+    let galley = painter.galley(
+        toast.text.clone(),
+        FontId::proportional(style.font_size),
+        inner.right() - text_left,
+    );
+    let prev_color = std::mem::replace(
+        &mut painter.text_color,
+        style.text_color.linear_multiply(opacity),
+    );
+    painter.text_with_galley(GueeTextShape {
+        galley,
+        pos: Pos2::new(text_left, inner.top()),
+        underline: epaint::Stroke::NONE,
+        angle: 0.0,
+    });
+    painter.text_color = prev_color;
+}
+
+/// Returns the width available for the text of a toast.
+pub(crate) fn text_width(style: &ToastStyle) -> f32 {
+    let icon_width = if style.use_icon_font {
+        style.font_size + style.padding.x
+    } else {
+        0.0
+    };
+    style.width - style.padding.x * 2.0 - icon_width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_from_bottom_right() {
+        let style = ToastStyle::default();
+        let screen = Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0));
+        let rects = ToastQueue::stack_rects(&style, screen, &[30.0, 40.0]);
+        assert_eq!(rects[0].right(), 800.0 - style.margin);
+        assert_eq!(rects[0].bottom(), 600.0 - style.margin);
+        assert_eq!(rects[1].bottom(), rects[0].top() - style.spacing);
+        assert_eq!(rects[1].height(), 40.0);
+    }
+
+    #[test]
+    fn test_dismiss_at() {
+        let mut queue = ToastQueue::default();
+        queue.push(Toast::info("Saved"));
+        queue.toasts[0].rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0));
+        assert!(!queue.dismiss_at(Pos2::new(20.0, 20.0)));
+        assert!(queue.dismiss_at(Pos2::new(5.0, 5.0)));
+        assert!(queue.toasts[0].is_expired(0.0));
+    }
+}
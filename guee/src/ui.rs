@@ -0,0 +1,152 @@
+//! A convenience layer to build widget trees with less boilerplate.
+//!
+//! The [`Ui`] builder generates widget ids automatically from the location in
+//! the source code where each widget is added, and collects children without
+//! the need for `vec![]`s:
+//!
+//! ```ignore
+//! Ui::build(|ui| {
+//!     ui.text("Hello!");
+//!     ui.horizontal(|ui| {
+//!         ui.button("Add", cba.callback(|state, _| state.count += 1));
+//!         ui.button("Remove", cba.callback(|state, _| state.count -= 1));
+//!     });
+//! })
+//! ```
+//!
+//! The result is a regular [`DynWidget`] tree, so the low-level widget API can
+//! still be used for anything the builder doesn't cover, with [`Ui::add`].
+
+use std::panic::Location;
+
+use epaint::ahash::HashMap;
+
+use crate::prelude::*;
+
+/// Collects the children of a container. See the [module docs](self).
+#[derive(Default)]
+pub struct Ui {
+    widgets: Vec<DynWidget>,
+    /// The number of ids generated so far from each source location, so that
+    /// widgets added in a loop get different ids.
+    location_counts: HashMap<Location<'static>, usize>,
+}
+
+impl Ui {
+    /// Runs `f` to add widgets to a new [`Ui`], and returns them inside a
+    /// vertical [`BoxContainer`] filling the available space.
+    #[track_caller]
+    pub fn build(f: impl FnOnce(&mut Ui)) -> DynWidget {
+        let mut ui = Ui::default();
+        let id = ui.id();
+        f(&mut ui);
+        BoxContainer::vertical(id, ui.widgets)
+            .layout_hints(LayoutHints::fill())
+            .build()
+    }
+
+    /// Returns an id generated from the location of the caller. Calling this
+    /// repeatedly from the same location, e.g. in a loop, returns a different
+    /// id each time. Use this to create widgets for [`Ui::add`].
+    #[track_caller]
+    pub fn id(&mut self) -> IdGen {
+        let count = self.location_counts.entry(*Location::caller()).or_default();
+        let id = IdGen::auto().with_index(*count);
+        *count += 1;
+        id
+    }
+
+    /// Adds an arbitrary widget.
+    pub fn add(&mut self, widget: impl Into<DynWidget>) {
+        self.widgets.push(widget.into());
+    }
+
+    /// Adds a vertical [`BoxContainer`] with the widgets added by `f`.
+    #[track_caller]
+    pub fn vertical(&mut self, f: impl FnOnce(&mut Ui)) {
+        let id = self.id();
+        let mut ui = Ui::default();
+        f(&mut ui);
+        self.add(BoxContainer::vertical(id, ui.widgets));
+    }
+
+    /// Adds a horizontal [`BoxContainer`] with the widgets added by `f`.
+    #[track_caller]
+    pub fn horizontal(&mut self, f: impl FnOnce(&mut Ui)) {
+        let id = self.id();
+        let mut ui = Ui::default();
+        f(&mut ui);
+        self.add(BoxContainer::horizontal(id, ui.widgets));
+    }
+
+    /// Adds a [`Text`] label.
+    pub fn text(&mut self, contents: impl Into<String>) {
+        self.add(Text::new(contents.into()));
+    }
+
+    /// Adds a [`Button`] with a text label, which can have an access key (see
+    /// [`Button::with_label`]).
+    #[track_caller]
+    pub fn button(&mut self, label: impl Into<String>, on_click: Callback<()>) {
+        let mut button = Button::with_label(label).on_click(on_click);
+        button.id = self.id();
+        self.add(button);
+    }
+
+    /// Adds a single line [`TextEdit`] filling the available width.
+    #[track_caller]
+    pub fn text_edit(&mut self, contents: impl Into<String>, on_changed: Callback<String>) {
+        let id = self.id();
+        self.add(
+            TextEdit::new(id, contents.into())
+                .layout_hints(LayoutHints::fill_horizontal())
+                .on_changed(on_changed),
+        );
+    }
+
+    /// Adds a [`Spacer`] taking all the remaining space in both directions.
+    pub fn fill_space(&mut self) {
+        self.add(Spacer::new(Vec2::ZERO, LayoutHints::fill()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_from_location() {
+        let mut ui = Ui::default();
+        let ids: Vec<IdGen> = (0..3).map(|_| ui.id()).collect();
+        let other = ui.id();
+
+        let root = WidgetId::new("root");
+        let resolved: Vec<WidgetId> = ids
+            .iter()
+            .chain([&other])
+            .map(|id| id.resolve(root))
+            .collect();
+        for (i, a) in resolved.iter().enumerate() {
+            for b in &resolved[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_builds_nested_tree() {
+        let ctx = Context::new(Vec2::new(200.0, 100.0), vec![]);
+        let mut widget = Ui::build(|ui| {
+            ui.horizontal(|ui| {
+                ui.add(ColoredBox::new(IdGen::key(1)).min_size(Vec2::new(10.0, 10.0)));
+                ui.add(ColoredBox::new(IdGen::key(2)).min_size(Vec2::new(10.0, 10.0)));
+            });
+            ui.add(ColoredBox::new(IdGen::key(3)).min_size(Vec2::new(10.0, 10.0)));
+        });
+
+        let layout = ctx.layout_only(&mut widget);
+        assert_eq!(layout.children.len(), 2);
+        assert_eq!(layout.children[0].children.len(), 2);
+        assert!(layout.children[1].bounds.top() > layout.children[0].bounds.bottom());
+    }
+}
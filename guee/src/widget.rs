@@ -15,8 +15,50 @@ pub trait Widget {
         available: Vec2,
         force_shrink: bool,
     ) -> Layout;
+    /// Returns the size this widget would have when laid out in shrink mode,
+    /// that is, the same as `layout(ctx, parent_id, available, true)`, but
+    /// without building the whole [`Layout`] tree.
+    ///
+    /// Containers call this to find out the natural size of their children
+    /// before doing the final layout. The default implementation falls back to
+    /// a full layout, and leaf widgets override it with something cheaper.
+    fn measure(&mut self, ctx: &Context, parent_id: WidgetId, available: Vec2) -> Vec2 {
+        self.layout(ctx, parent_id, available, true).bounds.size()
+    }
     fn draw(&mut self, ctx: &Context, layout: &Layout);
     fn layout_hints(&self) -> LayoutHints;
+    /// The capture phase of event handling. Events are handled in two phases:
+    /// First, `on_event_capture` is called top-down on the whole tree, with
+    /// parents called before their children. Then, [`Widget::on_event`] is
+    /// called as usual. Both phases share the same `status`, so consuming
+    /// an event here means no widget will react to it in `on_event`.
+    ///
+    /// This lets a widget intercept events before the widgets below it, e.g.
+    /// a modal blocking the rest of the UI. The default implementation does
+    /// nothing, but containers must forward the call to their children, with
+    /// the same layouts and cursor transforms used in `on_event`.
+    fn on_event_capture(
+        &mut self,
+        _ctx: &Context,
+        _layout: &Layout,
+        _cursor_position: Pos2,
+        _events: &[Event],
+        _status: &mut EventStatus,
+    ) {
+    }
+    /// Handles the `events` received this frame. All widgets get to see the
+    /// events, in tree order, and share the same `status`. A widget that
+    /// reacts to an event should first check [`EventStatus::is_consumed`],
+    /// and then call [`EventStatus::consume_event`] so widgets later in the
+    /// tree ignore it. Containers forward the same `status` to their
+    /// children.
+    ///
+    /// Widgets, and their callbacks, are rebuilt every frame. A widget that
+    /// fires a callback at most once per frame can `take()` it and pass it to
+    /// [`Context::dispatch_callback`], after which it's gone for the rest of
+    /// the frame. Widgets that may fire it several times, like sliders,
+    /// should use [`Context::dispatch_callback_ref`] instead, which leaves
+    /// repeatable callbacks in place so every dispatch runs.
     fn on_event(
         &mut self,
         ctx: &Context,
@@ -88,6 +88,36 @@ impl IdGen {
         Self::Key(WidgetId::new(source).value())
     }
 
+    /// The id for this widget will be generated from the location in the
+    /// source code where this function is called, hashed onto the parent's id.
+    /// This avoids having to come up with a key for every widget.
+    ///
+    /// # Pitfalls
+    ///
+    /// All the ids generated at the same call site are equal. When creating
+    /// widgets in a loop, or in a function called multiple times for the same
+    /// parent, use [`IdGen::with_index`] (or [`IdGen::with`]) to tell them
+    /// apart. Otherwise, their memory and focus state will get mixed up.
+    ///
+    /// Ids depend on the line and column of the call, so they change when the
+    /// code around them is edited. This is fine for state that only needs to
+    /// persist while the app runs, but not for anything stored to disk.
+    #[track_caller]
+    pub fn auto() -> Self {
+        let location = std::panic::Location::caller();
+        Self::key((location.file(), location.line(), location.column()))
+    }
+
+    /// The id for this widget will be generated from its position among its
+    /// siblings, hashed onto the parent's id. Used by widgets that don't take
+    /// an id, like [`Text`], together with [`Context::sibling_index`].
+    ///
+    /// [`Text`]: crate::base_widgets::text::Text
+    /// [`Context::sibling_index`]: crate::context::Context::sibling_index
+    pub fn index(index: usize) -> Self {
+        Self::key(("__index__", index))
+    }
+
     /// The id for this widget will be set to the hash of the exact given value.
     pub fn literal(source: impl std::hash::Hash) -> Self {
         Self::Literal(WidgetId::new(source))
@@ -110,4 +140,30 @@ impl IdGen {
             IdGen::Literal(l) => IdGen::Literal(l.with(child)),
         }
     }
+
+    /// Combines this id generator with an index, to tell apart widgets
+    /// created in a loop, e.g. with [`IdGen::auto`].
+    pub fn with_index(self, index: usize) -> Self {
+        self.with(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_ids() {
+        let root = WidgetId::new("root");
+        let in_loop: Vec<WidgetId> = (0..2).map(|_| IdGen::auto().resolve(root)).collect();
+        assert_eq!(in_loop[0], in_loop[1]);
+
+        let elsewhere = IdGen::auto().resolve(root);
+        assert_ne!(in_loop[0], elsewhere);
+
+        let indexed: Vec<WidgetId> = (0..2)
+            .map(|i| IdGen::auto().with_index(i).resolve(root))
+            .collect();
+        assert_ne!(indexed[0], indexed[1]);
+    }
 }
@@ -231,8 +231,8 @@ pub(crate) fn guee_derive_builder_2(input: syn::DeriveInput) -> syn::Result<Toke
         quote! {
             pub fn #fn_name(#(#mandatory_field_signatures),*) -> Self {
                 Self {
-                    #(#mandatory_field_idents),*,
-                    #(#default_initializers),*
+                    #(#mandatory_field_idents,)*
+                    #(#default_initializers,)*
                 }
             }
         }
@@ -314,3 +314,28 @@ pub fn unwrap_typ<'a>(typ: &'a Type, span: Span, expected: &str) -> syn::Result<
         ),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_default_fields() {
+        let input: syn::DeriveInput = syn::parse_quote! {
+            struct Style {
+                #[builder(default = 1.0)]
+                width: f32,
+                #[builder(default)]
+                color: u32,
+            }
+        };
+        let tokens = guee_derive_builder_2(input).unwrap();
+        let item: syn::ItemImpl = syn::parse2(tokens).expect("Generated code should parse");
+        let new_fn = item.items.iter().find_map(|item| match item {
+            syn::ImplItem::Method(method) if method.sig.ident == "new" => Some(method),
+            _ => None,
+        });
+        let new_fn = new_fn.expect("Should have a constructor");
+        assert!(new_fn.sig.inputs.is_empty());
+    }
+}
@@ -1,32 +1,72 @@
 use egui_wgpu::{winit::Painter, WgpuConfiguration};
 use itertools::Itertools;
 
-use guee::prelude::*;
+use guee::{
+    prelude::*,
+    renderer::{EguiWgpuRenderer, Renderer},
+};
 use winit::{event_loop::{EventLoop, ControlFlow}, window::WindowBuilder};
 
+/// Number of samples used for multisample antialiasing. WebGPU guarantees 4x
+/// MSAA is supported for all renderable surface formats, so this is a safe
+/// default. Set to 1 to disable antialiasing.
+const MSAA_SAMPLES: u32 = 4;
+
+pub struct Item {
+    /// A unique identifier, used as the item's key in the `KeyedList`.
+    id: u64,
+    name: String,
+}
+
 #[derive(Default)]
 pub struct AppState {
-    items: Vec<String>,
+    items: Vec<Item>,
+    next_item_id: u64,
     wip_item_name: String,
 }
 
-fn view(state: &AppState) -> DynWidget {
+fn view(state: &AppState, cba: &CallbackAccessor<AppState>) -> DynWidget {
     MarginContainer::new(
         IdGen::key("margin"),
         BoxContainer::vertical(
             IdGen::key("vbox"),
             vec![
-                BoxContainer::vertical(
+                // Items are keyed by their id, so deleting the first item keeps
+                // the state of the text edits (e.g. focus) of the remaining ones.
+                KeyedList::vertical(
                     IdGen::key("items"),
                     state
                         .items
                         .iter()
-                        .map(|it| Text::new(it.clone()).build())
+                        .map(|it| {
+                            let id = it.id;
+                            let text_edit = TextEdit::new(IdGen::key("name"), it.name.clone())
+                                .layout_hints(LayoutHints::fill_horizontal())
+                                .on_changed(cba.callback(move |state, new| {
+                                    if let Some(item) =
+                                        state.items.iter_mut().find(|it| it.id == id)
+                                    {
+                                        item.name = new;
+                                    }
+                                }))
+                                .build();
+                            (id, text_edit)
+                        })
                         .collect_vec(),
                 )
                 .layout_hints(LayoutHints::fill_horizontal())
                 .cross_align(Align::Center)
                 .build(),
+                // Highlights where the name being typed appears in the list
+                RichText::new(
+                    IdGen::key("matches"),
+                    RichText::highlight_matches(
+                        &state.items.iter().map(|it| it.name.as_str()).join(", "),
+                        &state.wip_item_name,
+                        color!("#b58900"),
+                    ),
+                )
+                .build(),
                 Spacer::fill_v(1).build(),
                 TextEdit::new(
                     IdGen::literal("text_input_field"),
@@ -34,25 +74,31 @@ fn view(state: &AppState) -> DynWidget {
                 )
                 .layout_hints(LayoutHints::fill_horizontal())
                 .padding(Vec2::new(3.0, 3.0))
-                .on_changed(|state: &mut AppState, new| {
+                .on_changed(cba.callback(|state, new| {
                     state.wip_item_name = new;
-                })
+                }))
                 .build(),
                 BoxContainer::horizontal(
                     IdGen::key("buttons"),
                     vec![
                         Button::with_label("Add!")
-                            .on_click(|state: &mut AppState, _| {
+                            .on_click(cba.callback(|state, _| {
                                 if !state.wip_item_name.is_empty() {
-                                    state.items.push(std::mem::take(&mut state.wip_item_name));
+                                    state.items.push(Item {
+                                        id: state.next_item_id,
+                                        name: std::mem::take(&mut state.wip_item_name),
+                                    });
+                                    state.next_item_id += 1;
                                 }
-                            })
+                            }))
                             .hints(LayoutHints::fill_horizontal())
                             .build(),
-                        Button::with_label("Delete!")
-                            .on_click(|state: &mut AppState, _| {
-                                state.items.pop();
-                            })
+                        Button::with_label("Delete first!")
+                            .on_click(cba.callback(|state, _| {
+                                if !state.items.is_empty() {
+                                    state.items.remove(0);
+                                }
+                            }))
                             .hints(LayoutHints::fill_horizontal())
                             .build(),
                     ],
@@ -70,7 +116,7 @@ fn view(state: &AppState) -> DynWidget {
 
 fn main() {
     let screen_size = Vec2::new(800.0, 600.0);
-    let mut ctx = Context::new(screen_size);
+    let mut ctx = Context::new(screen_size, vec![]);
 
     let event_loop = EventLoop::new();
     let window = WindowBuilder::new()
@@ -78,41 +124,40 @@ fn main() {
         .build(&event_loop)
         .unwrap();
 
-    let mut painter = Painter::new(WgpuConfiguration::default(), 1, 0);
+    let mut painter = Painter::new(WgpuConfiguration::default(), MSAA_SAMPLES, 0);
     unsafe { pollster::block_on(painter.set_window(Some(&window))).unwrap() };
+    let mut renderer = EguiWgpuRenderer::new(painter);
+    renderer.clear_color = epaint::Rgba::from_rgb(0.7, 0.3, 0.3);
 
     let mut state = AppState::default();
 
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Wait;
         match event {
             winit::event::Event::MainEventsCleared => {
-                ctx.run(&mut view(&state), &mut state);
+                if let Some(new_size) = ctx.take_resize() {
+                    renderer.resize(new_size.x as u32, new_size.y as u32);
+                }
+                if !ctx.needs_run() {
+                    // Nothing changed, so the last frame is still up to date
+                    return;
+                }
+                ctx.run_view(&mut state, view);
+                ctx.apply_cursor_requests(&window);
+                *control_flow = match ctx.take_repaint_request() {
+                    Some(delay) => ControlFlow::WaitUntil(std::time::Instant::now() + delay),
+                    None => ControlFlow::Wait,
+                };
                 let clipped_primitives = ctx.tessellate();
 
-                let mut textures_delta = TexturesDelta::default();
-                if let Some(img_delta) = ctx.fonts.font_image_delta() {
-                    textures_delta.set.push((TextureId::default(), img_delta));
-                }
-                painter.paint_and_update_textures(
-                    1.0,
-                    epaint::Rgba::from_rgb(0.7, 0.3, 0.3),
-                    &clipped_primitives,
-                    &textures_delta,
-                );
+                let textures_delta = ctx.take_textures_delta();
+                renderer.render(&clipped_primitives, &textures_delta, 1.0);
             }
             winit::event::Event::WindowEvent { window_id, event } if window_id == window.id() => {
-                match &event {
-                    winit::event::WindowEvent::CloseRequested => {
-                        *control_flow = ControlFlow::Exit;
-                    }
-                    winit::event::WindowEvent::Resized(new_size) => {
-                        painter.on_window_resized(new_size.width, new_size.height);
-                    }
-                    _ => (),
+                if let winit::event::WindowEvent::CloseRequested = &event {
+                    *control_flow = ControlFlow::Exit;
                 }
 
-                ctx.input_state.on_winit_event(&event);
+                ctx.on_winit_event(&event);
             }
             _ => (),
         }